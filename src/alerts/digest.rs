@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::FiredAlert;
+use crate::monitor::MonitorData;
+use crate::utils::notifications::{format_number, NotificationKind, Severity};
+use crate::i18n;
+
+fn default_end_of_block() -> bool {
+    true
+}
+
+/// When usage digests are sent, stored in the preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestSchedule {
+    /// Times of day as `HH:MM`, e.g. `["12:00", "18:00"]`
+    #[serde(default)]
+    pub times: Vec<String>,
+    /// Also send a digest when each 5-hour block ends
+    #[serde(default = "default_end_of_block")]
+    pub end_of_block: bool,
+}
+
+impl Default for DigestSchedule {
+    fn default() -> Self {
+        Self {
+            times: Vec::new(),
+            end_of_block: default_end_of_block(),
+        }
+    }
+}
+
+/// Usage observed since the last digest.
+#[derive(Debug, Default)]
+struct PeriodStats {
+    tokens: i64,
+    cost: f64,
+    /// Reset times of the blocks with activity in the period
+    blocks: HashSet<DateTime<Local>>,
+    peak_burn_rate: Option<f64>,
+}
+
+/// Accumulates the usage of the active block between digests and summarizes
+/// it at the scheduled times. Digests of periods without activity are skipped.
+#[derive(Debug)]
+pub struct DigestScheduler {
+    times: Vec<NaiveTime>,
+    end_of_block: bool,
+    period_start: DateTime<Local>,
+    last_check: DateTime<Local>,
+    stats: PeriodStats,
+    /// Reset time, tokens and cost of the block seen in the last update
+    current_block: Option<(DateTime<Local>, i64, f64)>,
+}
+
+impl DigestScheduler {
+    pub fn new(schedule: &DigestSchedule) -> Self {
+        let now = Local::now();
+        let mut scheduler = Self {
+            times: Vec::new(),
+            end_of_block: true,
+            period_start: now,
+            last_check: now,
+            stats: PeriodStats::default(),
+            current_block: None,
+        };
+        scheduler.configure(schedule);
+        scheduler
+    }
+
+    pub fn configure(&mut self, schedule: &DigestSchedule) {
+        self.times = schedule.times.iter()
+            .filter_map(|time| NaiveTime::parse_from_str(time.trim(), "%H:%M").ok())
+            .collect();
+        self.end_of_block = schedule.end_of_block;
+    }
+
+    pub fn evaluate(&mut self, data: &MonitorData) -> Vec<FiredAlert> {
+        self.evaluate_at(data, Local::now())
+    }
+
+    fn evaluate_at(&mut self, data: &MonitorData, now: DateTime<Local>) -> Vec<FiredAlert> {
+        let block_ended = self.observe(data, now);
+
+        let time_reached = self.times.iter().any(|time| {
+            let Some(today) = now.date_naive().and_time(*time).and_local_timezone(Local).single() else {
+                return false;
+            };
+            // Also catch a time just before midnight when the check runs after it
+            let yesterday = today - Duration::days(1);
+            [today, yesterday].iter().any(|at| *at > self.last_check && *at <= now)
+        });
+        self.last_check = now;
+
+        if !(time_reached || (self.end_of_block && block_ended)) {
+            return Vec::new();
+        }
+
+        let stats = std::mem::take(&mut self.stats);
+        let period_start = std::mem::replace(&mut self.period_start, now);
+        if stats.tokens <= 0 && stats.cost <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut lines = vec![
+            i18n::format(i18n::keys::NOTIF_DIGEST_PERIOD, &[
+                &period_start.format("%H:%M").to_string(),
+                &now.format("%H:%M").to_string(),
+            ]),
+            format!("{}: {}", i18n::get(i18n::keys::TRAY_USAGE), format_number(stats.tokens)),
+            format!("{}: ${:.2}", i18n::get(i18n::keys::TRAY_COST), stats.cost),
+            i18n::format(i18n::keys::NOTIF_DIGEST_BLOCKS, &[&stats.blocks.len().to_string()]),
+        ];
+        if let Some(rate) = stats.peak_burn_rate {
+            lines.push(i18n::format(i18n::keys::NOTIF_DIGEST_PEAK_BURN_RATE, &[&format!("{:.0}", rate)]));
+        }
+
+        vec![FiredAlert {
+            rule: "usage_digest".to_string(),
+            kind: NotificationKind::UsageSummary,
+            severity: Severity::Info,
+            title: i18n::get(i18n::keys::NOTIF_USAGE_STATUS),
+            message: lines.join("\n"),
+        }]
+    }
+
+    /// Adds the usage since the previous update to the period. Returns true
+    /// when the previously seen block has ended.
+    fn observe(&mut self, data: &MonitorData, now: DateTime<Local>) -> bool {
+        let ccusage = &data.ccusage_data;
+        let active = ccusage.reset_time.filter(|reset_time| ccusage.status == "ACTIVE" && *reset_time > now);
+
+        let block_ended = match (self.current_block, active) {
+            (Some((previous, _, _)), Some(reset_time)) => previous != reset_time,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        let Some(reset_time) = active else {
+            self.current_block = None;
+            return block_ended;
+        };
+
+        let (tokens, cost) = match self.current_block {
+            Some((previous, tokens, cost)) if previous == reset_time => {
+                ((ccusage.tokens_num - tokens).max(0), (ccusage.cost_num - cost).max(0.0))
+            }
+            // A new block counts from its start if it began during the period;
+            // a block that was already running when we started is a baseline
+            _ if reset_time - Duration::hours(5) >= self.period_start => (ccusage.tokens_num, ccusage.cost_num),
+            _ => (0, 0.0),
+        };
+        self.current_block = Some((reset_time, ccusage.tokens_num, ccusage.cost_num));
+
+        if tokens > 0 || cost > 0.0 {
+            self.stats.tokens += tokens;
+            self.stats.cost += cost;
+            self.stats.blocks.insert(reset_time);
+        }
+        if let Some(rate) = ccusage.burn_rate_tokens_per_min {
+            self.stats.peak_burn_rate = Some(self.stats.peak_burn_rate.map_or(rate, |peak| peak.max(rate)));
+        }
+
+        block_ended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, day, hour, minute, 0).unwrap()
+    }
+
+    fn scheduler(times: &[&str], start: DateTime<Local>) -> DigestScheduler {
+        let mut scheduler = DigestScheduler::new(&DigestSchedule {
+            times: times.iter().map(|time| time.to_string()).collect(),
+            end_of_block: false,
+        });
+        scheduler.period_start = start;
+        scheduler.last_check = start;
+        scheduler
+    }
+
+    fn block(reset_time: DateTime<Local>, tokens: i64) -> MonitorData {
+        let mut data = MonitorData::default();
+        data.ccusage_data.status = "ACTIVE".to_string();
+        data.ccusage_data.reset_time = Some(reset_time);
+        data.ccusage_data.tokens_num = tokens;
+        data.ccusage_data.cost_num = tokens as f64 / 1000.0;
+        data
+    }
+
+    #[test]
+    fn catches_up_on_a_time_missed_before_midnight() {
+        let mut scheduler = scheduler(&["23:30"], at(15, 20, 0));
+        assert!(scheduler.evaluate_at(&block(at(16, 1, 0), 100), at(15, 21, 0)).is_empty());
+        // Asleep from 23:00 until after midnight
+        assert!(scheduler.evaluate_at(&block(at(16, 1, 0), 200), at(15, 23, 0)).is_empty());
+        assert_eq!(scheduler.evaluate_at(&block(at(16, 1, 0), 300), at(16, 0, 20)).len(), 1);
+    }
+
+    #[test]
+    fn block_running_at_start_is_a_baseline() {
+        let mut scheduler = scheduler(&["12:00"], at(15, 10, 0));
+        // Started at 08:00 with 1000 tokens before the scheduler did
+        scheduler.evaluate_at(&block(at(15, 13, 0), 1000), at(15, 10, 0));
+        assert_eq!(scheduler.stats.tokens, 0);
+        scheduler.evaluate_at(&block(at(15, 13, 0), 1500), at(15, 11, 0));
+        assert_eq!(scheduler.stats.tokens, 500);
+
+        // A block starting in the period counts from its start
+        scheduler.evaluate_at(&block(at(15, 16, 0), 200), at(15, 11, 30));
+        assert_eq!(scheduler.stats.tokens, 700);
+        assert_eq!(scheduler.stats.blocks.len(), 2);
+    }
+
+    #[test]
+    fn sends_one_digest_per_day() {
+        let mut scheduler = scheduler(&["18:00"], at(15, 8, 0));
+        let mut tokens = 0;
+        let mut sent = Vec::new();
+        let checks = [(15, 17, 59), (15, 18, 0), (15, 18, 1), (15, 23, 59), (16, 17, 59), (16, 18, 0), (16, 18, 5)];
+        for (day, hour, minute) in checks {
+            tokens += 100;
+            let now = at(day, hour, minute);
+            if !scheduler.evaluate_at(&block(now + Duration::hours(1), tokens), now).is_empty() {
+                sent.push(now);
+            }
+        }
+        assert_eq!(sent, vec![at(15, 18, 0), at(16, 18, 0)]);
+    }
+
+    #[test]
+    fn skips_periods_without_activity() {
+        let mut scheduler = scheduler(&["18:00"], at(15, 8, 0));
+        assert!(scheduler.evaluate_at(&MonitorData::default(), at(15, 18, 0)).is_empty());
+    }
+}
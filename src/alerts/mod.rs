@@ -0,0 +1,7 @@
+pub mod rules;
+pub mod reset;
+pub mod digest;
+
+pub use rules::{AlertEngine, AlertMetric, AlertRule, Comparison, FiredAlert};
+pub use reset::BlockResetNotifier;
+pub use digest::{DigestSchedule, DigestScheduler};
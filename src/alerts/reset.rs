@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local};
+
+use crate::alerts::FiredAlert;
+use crate::monitor::MonitorData;
+use crate::utils::notifications::{NotificationKind, Severity};
+use crate::i18n;
+
+/// Notifies at user-chosen marks before the active block expires, and once a
+/// new 5-hour window is available after the plan limit was hit. Without a
+/// plan limit, the new window is announced whenever a block ends.
+#[derive(Debug, Default)]
+pub struct BlockResetNotifier {
+    /// Minutes before the reset at which to notify, largest first
+    marks: Vec<u32>,
+    notify_window_available: bool,
+    plan_token_limit: Option<u64>,
+    /// Reset time of the block the fired marks belong to
+    current_block: Option<DateTime<Local>>,
+    fired_marks: Vec<u32>,
+    /// Reset time of the block whose end announces a new window
+    pending_reset: Option<DateTime<Local>>,
+}
+
+impl BlockResetNotifier {
+    pub fn new(marks: Vec<u32>, notify_window_available: bool, plan_token_limit: Option<u64>) -> Self {
+        let mut notifier = Self::default();
+        notifier.configure(marks, notify_window_available, plan_token_limit);
+        notifier
+    }
+
+    pub fn configure(&mut self, mut marks: Vec<u32>, notify_window_available: bool, plan_token_limit: Option<u64>) {
+        marks.sort_unstable_by(|a, b| b.cmp(a));
+        marks.dedup();
+        self.marks = marks;
+        self.notify_window_available = notify_window_available;
+        self.plan_token_limit = plan_token_limit;
+    }
+
+    pub fn evaluate(&mut self, data: &MonitorData) -> Vec<FiredAlert> {
+        let now = Local::now();
+        let mut fired = Vec::new();
+
+        // A new window opens once the pending block has reset
+        if let Some(reset_time) = self.pending_reset
+            && now >= reset_time
+        {
+            self.pending_reset = None;
+            if self.notify_window_available {
+                fired.push(FiredAlert {
+                    rule: "block_reset".to_string(),
+                    kind: NotificationKind::BlockReset,
+                    severity: Severity::Info,
+                    title: i18n::get(i18n::keys::NOTIF_TITLE),
+                    message: i18n::get(i18n::keys::NOTIF_WINDOW_AVAILABLE),
+                });
+            }
+        }
+
+        let ccusage = &data.ccusage_data;
+        let Some(reset_time) = ccusage.reset_time.filter(|_| ccusage.status == "ACTIVE") else {
+            return fired;
+        };
+
+        if self.current_block != Some(reset_time) {
+            self.current_block = Some(reset_time);
+            self.fired_marks.clear();
+        }
+
+        let limit_hit = match self.plan_token_limit.filter(|limit| *limit > 0) {
+            Some(limit) => ccusage.tokens_num >= limit as i64,
+            None => true,
+        };
+        if limit_hit {
+            self.pending_reset = Some(reset_time);
+        }
+
+        let remaining_secs = (reset_time - now).num_seconds();
+        if remaining_secs <= 0 {
+            return fired;
+        }
+
+        // When several marks were crossed at once (e.g. right after startup),
+        // only notify for the closest one.
+        let crossed: Vec<u32> = self.marks.iter()
+            .copied()
+            .filter(|mark| !self.fired_marks.contains(mark) && remaining_secs <= *mark as i64 * 60)
+            .collect();
+        if let Some(&mark) = crossed.last() {
+            self.fired_marks.extend(crossed.iter().copied());
+            let minutes = ((remaining_secs + 59) / 60).to_string();
+            let reset_at = reset_time.format("%H:%M").to_string();
+            fired.push(FiredAlert {
+                rule: "block_expiry".to_string(),
+                kind: NotificationKind::BlockReset,
+                severity: if mark <= 1 { Severity::Warning } else { Severity::Info },
+                title: i18n::get(i18n::keys::NOTIF_TITLE),
+                message: i18n::format(i18n::keys::NOTIF_BLOCK_EXPIRING, &[&minutes, &reset_at]),
+            });
+        }
+
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(reset_time: DateTime<Local>, tokens: i64) -> MonitorData {
+        let mut data = MonitorData::default();
+        data.ccusage_data.status = "ACTIVE".to_string();
+        data.ccusage_data.reset_time = Some(reset_time);
+        data.ccusage_data.tokens_num = tokens;
+        data
+    }
+
+    fn window_alerts(fired: &[FiredAlert]) -> usize {
+        fired.iter().filter(|alert| alert.rule == "block_reset").count()
+    }
+
+    #[test]
+    fn announces_new_window_when_block_ends_without_limit() {
+        let mut notifier = BlockResetNotifier::new(Vec::new(), true, None);
+        let now = Local::now();
+
+        assert_eq!(window_alerts(&notifier.evaluate(&block(now + chrono::Duration::hours(1), 10))), 0);
+        assert_eq!(window_alerts(&notifier.evaluate(&MonitorData::default())), 0);
+        assert_eq!(window_alerts(&notifier.evaluate(&block(now - chrono::Duration::seconds(1), 10))), 0);
+        // The block ended before this update
+        assert_eq!(window_alerts(&notifier.evaluate(&MonitorData::default())), 1);
+        assert_eq!(window_alerts(&notifier.evaluate(&MonitorData::default())), 0);
+    }
+
+    #[test]
+    fn announces_new_window_only_after_limit_was_hit() {
+        let mut notifier = BlockResetNotifier::new(Vec::new(), true, Some(1_000));
+        let past = Local::now() - chrono::Duration::seconds(1);
+
+        notifier.evaluate(&block(past, 500));
+        assert_eq!(window_alerts(&notifier.evaluate(&MonitorData::default())), 0);
+        notifier.evaluate(&block(past, 1_000));
+        assert_eq!(window_alerts(&notifier.evaluate(&MonitorData::default())), 1);
+    }
+
+    #[test]
+    fn notifies_closest_crossed_mark_once() {
+        let mut notifier = BlockResetNotifier::new(vec![30, 10, 1], false, None);
+        let data = block(Local::now() + chrono::Duration::minutes(5), 10);
+
+        let fired = notifier.evaluate(&data);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].rule, "block_expiry");
+        assert!(notifier.evaluate(&data).is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::MonitorData;
+use crate::utils::notifications::{NotificationEvent, NotificationKind, Severity};
+use crate::i18n;
+
+/// A value derived from the monitor data that rules can be written against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// Tokens used in the current block
+    BlockTokens,
+    /// Cost of the current block in USD
+    BlockCost,
+    /// Block tokens as a percentage of `plan_token_limit`
+    PlanPercent,
+    /// Burn rate of the current block in tokens per minute
+    BurnRate,
+    /// Minutes until the current block resets
+    RemainingMinutes,
+    /// Today's cost in USD
+    DailyCost,
+    /// 1 when the network is connected, 0 otherwise
+    NetworkConnected,
+}
+
+impl AlertMetric {
+    /// Reads the metric from the monitor data. Returns `None` when the value
+    /// isn't known (no active block, no plan limit, ...), in which case the
+    /// rule is left untouched.
+    pub fn value(&self, data: &MonitorData, plan_token_limit: Option<u64>) -> Option<f64> {
+        let ccusage = &data.ccusage_data;
+        match self {
+            AlertMetric::BlockTokens => Some(ccusage.tokens_num as f64),
+            AlertMetric::BlockCost => Some(ccusage.cost_num),
+            AlertMetric::PlanPercent => data.plan_percent(plan_token_limit),
+            AlertMetric::BurnRate => ccusage.burn_rate_tokens_per_min,
+            AlertMetric::RemainingMinutes => data.remaining_minutes().map(|minutes| minutes as f64),
+            AlertMetric::DailyCost => data.today_cost(),
+            AlertMetric::NetworkConnected => Some(if data.network_connected { 1.0 } else { 0.0 }),
+        }
+    }
+
+    fn label(&self) -> String {
+        let key = match self {
+            AlertMetric::BlockTokens => i18n::keys::ALERT_METRIC_BLOCK_TOKENS,
+            AlertMetric::BlockCost => i18n::keys::ALERT_METRIC_BLOCK_COST,
+            AlertMetric::PlanPercent => i18n::keys::ALERT_METRIC_PLAN_PERCENT,
+            AlertMetric::BurnRate => i18n::keys::ALERT_METRIC_BURN_RATE,
+            AlertMetric::RemainingMinutes => i18n::keys::ALERT_METRIC_REMAINING_MINUTES,
+            AlertMetric::DailyCost => i18n::keys::ALERT_METRIC_DAILY_COST,
+            AlertMetric::NetworkConnected => i18n::keys::ALERT_METRIC_NETWORK,
+        };
+        i18n::get(key)
+    }
+
+    fn format_value(&self, value: f64) -> String {
+        match self {
+            AlertMetric::BlockTokens => format!("{:.0}", value),
+            AlertMetric::BlockCost | AlertMetric::DailyCost => format!("${:.2}", value),
+            AlertMetric::PlanPercent => format!("{:.0}%", value),
+            AlertMetric::BurnRate => format!("{:.0}/min", value),
+            AlertMetric::RemainingMinutes => format!("{:.0} {}", value, i18n::get(i18n::keys::COMMON_MINUTE)),
+            AlertMetric::NetworkConnected => if value >= 0.5 {
+                i18n::get(i18n::keys::NETWORK_CONNECTED)
+            } else {
+                i18n::get(i18n::keys::NETWORK_DISCONNECTED)
+            },
+        }
+    }
+}
+
+/// Which side of the threshold triggers the rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A user-defined alert rule, stored in the preferences.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Unique name of the rule, also used as the notification title
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Minimum time between two notifications of this rule
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    /// How far the value must move back past the threshold before the rule re-arms
+    #[serde(default)]
+    pub hysteresis: f64,
+    /// Optional custom message, replacing the generated one
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl AlertRule {
+    fn is_triggered(&self, value: f64) -> bool {
+        match self.comparison {
+            Comparison::Above => value >= self.threshold,
+            Comparison::Below => value <= self.threshold,
+        }
+    }
+
+    fn is_cleared(&self, value: f64) -> bool {
+        match self.comparison {
+            Comparison::Above => value < self.threshold - self.hysteresis,
+            Comparison::Below => value > self.threshold + self.hysteresis,
+        }
+    }
+}
+
+/// An alert produced by a rule that fired during evaluation.
+#[derive(Debug, Clone)]
+pub struct FiredAlert {
+    pub rule: String,
+    pub kind: NotificationKind,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+}
+
+impl FiredAlert {
+    pub fn into_event(self) -> NotificationEvent {
+        NotificationEvent::new(self.kind, self.severity, self.title, self.message)
+    }
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    /// The threshold is crossed and the rule has not re-armed yet
+    active: bool,
+    last_fired: Option<Instant>,
+}
+
+/// Evaluates the alert rules against each monitor update.
+///
+/// A rule fires once when its threshold is crossed and re-arms only after the
+/// value moves back past the threshold by its hysteresis. Its cooldown limits
+/// how often it can fire across re-arms; a crossing during the cooldown fires
+/// once the cooldown has passed if the value is still past the threshold.
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    plan_token_limit: Option<u64>,
+    states: HashMap<String, RuleState>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, plan_token_limit: Option<u64>) -> Self {
+        Self {
+            rules,
+            plan_token_limit,
+            states: HashMap::new(),
+        }
+    }
+
+    /// Replace the rules, keeping the state of rules that still exist.
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>, plan_token_limit: Option<u64>) {
+        self.states.retain(|name, _| rules.iter().any(|rule| &rule.name == name));
+        self.rules = rules;
+        self.plan_token_limit = plan_token_limit;
+    }
+
+    pub fn evaluate(&mut self, data: &MonitorData) -> Vec<FiredAlert> {
+        self.evaluate_at(data, Instant::now())
+    }
+
+    fn evaluate_at(&mut self, data: &MonitorData, now: Instant) -> Vec<FiredAlert> {
+        let mut fired = Vec::new();
+
+        for rule in self.rules.iter().filter(|rule| rule.enabled) {
+            let Some(value) = rule.metric.value(data, self.plan_token_limit) else {
+                continue;
+            };
+            let state = self.states.entry(rule.name.clone()).or_default();
+
+            if state.active {
+                if rule.is_cleared(value) {
+                    state.active = false;
+                }
+                continue;
+            }
+            if !rule.is_triggered(value) {
+                continue;
+            }
+
+            // Stays armed during the cooldown, so the alert isn't lost
+            let cooled_down = state.last_fired
+                .is_none_or(|last| now.duration_since(last) >= Duration::from_secs(rule.cooldown_secs));
+            if !cooled_down {
+                continue;
+            }
+            state.active = true;
+            state.last_fired = Some(now);
+            fired.push(FiredAlert {
+                rule: rule.name.clone(),
+                kind: NotificationKind::Alert,
+                severity: rule.severity,
+                title: i18n::format(i18n::keys::NOTIF_ALERT_TITLE, &[&rule.name]),
+                message: rule.message.clone().unwrap_or_else(|| Self::describe(rule, value)),
+            });
+        }
+
+        fired
+    }
+
+    fn describe(rule: &AlertRule, value: f64) -> String {
+        let comparison = match rule.comparison {
+            Comparison::Above => i18n::get(i18n::keys::ALERT_ABOVE),
+            Comparison::Below => i18n::get(i18n::keys::ALERT_BELOW),
+        };
+        format!("{}: {} ({} {})",
+            rule.metric.label(),
+            rule.metric.format_value(value),
+            comparison,
+            rule.metric.format_value(rule.threshold))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(cooldown_secs: u64, hysteresis: f64) -> AlertRule {
+        AlertRule {
+            name: "tokens".to_string(),
+            enabled: true,
+            metric: AlertMetric::BlockTokens,
+            comparison: Comparison::Above,
+            threshold: 100.0,
+            severity: Severity::Warning,
+            cooldown_secs,
+            hysteresis,
+            message: None,
+        }
+    }
+
+    fn data(tokens: i64) -> MonitorData {
+        let mut data = MonitorData::default();
+        data.ccusage_data.tokens_num = tokens;
+        data
+    }
+
+    #[test]
+    fn fires_once_until_cleared_past_hysteresis() {
+        let mut engine = AlertEngine::new(vec![rule(0, 10.0)], None);
+        let now = Instant::now();
+
+        assert!(engine.evaluate_at(&data(50), now).is_empty());
+        assert_eq!(engine.evaluate_at(&data(100), now).len(), 1);
+        assert!(engine.evaluate_at(&data(120), now).is_empty());
+        // Below the threshold but within the hysteresis
+        assert!(engine.evaluate_at(&data(95), now).is_empty());
+        assert!(engine.evaluate_at(&data(110), now).is_empty());
+        // Re-armed once past the hysteresis
+        assert!(engine.evaluate_at(&data(89), now).is_empty());
+        assert_eq!(engine.evaluate_at(&data(110), now).len(), 1);
+    }
+
+    #[test]
+    fn crossing_during_cooldown_fires_after_it() {
+        let mut engine = AlertEngine::new(vec![rule(60, 0.0)], None);
+        let start = Instant::now();
+
+        assert_eq!(engine.evaluate_at(&data(150), start).len(), 1);
+        assert!(engine.evaluate_at(&data(50), start + Duration::from_secs(10)).is_empty());
+        assert!(engine.evaluate_at(&data(150), start + Duration::from_secs(20)).is_empty());
+        assert!(engine.evaluate_at(&data(150), start + Duration::from_secs(40)).is_empty());
+        let fired = engine.evaluate_at(&data(150), start + Duration::from_secs(60));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].severity, Severity::Warning);
+        assert!(engine.evaluate_at(&data(150), start + Duration::from_secs(200)).is_empty());
+    }
+
+    #[test]
+    fn crossing_cleared_during_cooldown_is_dropped() {
+        let mut engine = AlertEngine::new(vec![rule(60, 0.0)], None);
+        let start = Instant::now();
+
+        assert_eq!(engine.evaluate_at(&data(150), start).len(), 1);
+        assert!(engine.evaluate_at(&data(50), start + Duration::from_secs(10)).is_empty());
+        assert!(engine.evaluate_at(&data(150), start + Duration::from_secs(20)).is_empty());
+        assert!(engine.evaluate_at(&data(50), start + Duration::from_secs(30)).is_empty());
+        assert!(engine.evaluate_at(&data(50), start + Duration::from_secs(90)).is_empty());
+    }
+
+    #[test]
+    fn unknown_values_and_disabled_rules_are_skipped() {
+        let mut disabled = rule(0, 0.0);
+        disabled.enabled = false;
+        let plan = AlertRule { name: "plan".to_string(), metric: AlertMetric::PlanPercent, threshold: 50.0, ..rule(0, 0.0) };
+        let mut engine = AlertEngine::new(vec![disabled, plan], None);
+
+        assert!(engine.evaluate_at(&data(1_000), Instant::now()).is_empty());
+    }
+}
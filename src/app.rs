@@ -4,7 +4,10 @@ use crate::monitor::MonitorData;
 use crate::ui::main_screen::MainScreen;
 use crate::background::{start_monitor_tokio, submit_monitor_request, MonitorRequest};
 use crate::ui_updates::{dequeue_monitor_updates, MonitorUpdate};
-use crate::tray::TrayHandle;
+#[cfg(feature = "tray")]
+use crate::tray::{TrayCommand, TrayHandle};
+#[cfg(feature = "tray")]
+use crate::utils::notifications;
 
 live_design! {
     use link::theme::*;
@@ -37,6 +40,7 @@ pub struct App {
     
     #[rust] current_data: MonitorData,
     #[rust] time_update_timer: Timer,
+    #[cfg(feature = "tray")]
     #[rust] tray_handle: Option<TrayHandle>,
 }
 
@@ -49,6 +53,10 @@ impl LiveRegister for App {
         crate::ui::widgets::status_indicator::live_design(cx);
         crate::ui::widgets::usage_display::live_design(cx);
         crate::ui::widgets::chart::live_design(cx);
+        crate::ui::widgets::source_health::live_design(cx);
+        crate::ui::widgets::notification_center::live_design(cx);
+        crate::ui::widgets::export_panel::live_design(cx);
+        crate::ui::widgets::import_panel::live_design(cx);
     }
 }
 
@@ -62,11 +70,15 @@ impl MatchEvent for App {
         }
         
         // Initialize system tray
-        let tray_handle = TrayHandle::new();
-        if let Err(e) = tray_handle.init() {
-            error!("Failed to initialize system tray: {}", e);
-        } else {
-            self.tray_handle = Some(tray_handle);
+        #[cfg(feature = "tray")]
+        {
+            let tray_handle = TrayHandle::new();
+            if let Err(e) = tray_handle.init() {
+                error!("Failed to initialize system tray: {}", e);
+            } else {
+                tray_handle.set_unread_notifications(notifications::unread_count());
+                self.tray_handle = Some(tray_handle);
+            }
         }
         
         // Start the background monitoring tasks
@@ -93,7 +105,7 @@ impl MatchEvent for App {
             match update {
                 MonitorUpdate::DataUpdate(data) => {
                     log!("Received data update from background task");
-                    self.current_data = data;
+                    self.current_data = *data;
                     
                     // Update UI
                     if let Some(mut main_screen) = self.ui.widget(id!(body)).borrow_mut::<MainScreen>() {
@@ -101,10 +113,13 @@ impl MatchEvent for App {
                     }
                     
                     // Update tray status
+                    #[cfg(feature = "tray")]
                     if let Some(ref tray) = self.tray_handle {
                         if let Err(e) = tray.update_status(&self.current_data) {
                             error!("Failed to update tray status: {}", e);
                         }
+                        // A timed snooze may have ended since the last update
+                        tray.set_snoozed(notifications::snoozed_until().is_some());
                     }
                 }
                 MonitorUpdate::StatusMessage(msg) => {
@@ -113,6 +128,45 @@ impl MatchEvent for App {
                 MonitorUpdate::ErrorMessage(err) => {
                     error!("Error: {}", err);
                 }
+                MonitorUpdate::NotificationRecorded(record) => {
+                    log!("Notification recorded: {}", record.event.title);
+                    if let Some(mut main_screen) = self.ui.widget(id!(body)).borrow_mut::<MainScreen>() {
+                        main_screen.update_notifications(cx);
+                    }
+                    #[cfg(feature = "tray")]
+                    if let Some(ref tray) = self.tray_handle {
+                        tray.set_unread_notifications(notifications::unread_count());
+                    }
+                }
+                MonitorUpdate::RequestAck(ack) => {
+                    if ack.success {
+                        log!("Request {} acknowledged {}", ack.request, ack.message.as_deref().unwrap_or_default());
+                    } else {
+                        error!("Request {} failed: {}", ack.request, ack.message.as_deref().unwrap_or_default());
+                    }
+                    
+                    // Sent by a second launch or `cc-monitor-rs show` / `quit`
+                    match ack.request {
+                        "Show" => self.ui.window(id!(main_window)).restore(cx),
+                        "Shutdown" => cx.quit(),
+                        "ImportHistory" => {
+                            if let Some(mut main_screen) = self.ui.widget(id!(body)).borrow_mut::<MainScreen>() {
+                                main_screen.import_finished(cx, ack.success, ack.message.as_deref());
+                            }
+                        }
+                        _ => {}
+                    }
+                    
+                    // Keep the tray pause/resume items in sync with the worker
+                    #[cfg(feature = "tray")]
+                    if let Some(ref tray) = self.tray_handle {
+                        match (ack.request, ack.success) {
+                            ("Pause", true) => tray.set_paused(true),
+                            ("Resume", true) => tray.set_paused(false),
+                            _ => {}
+                        }
+                    }
+                }
             }
         }
         
@@ -125,6 +179,18 @@ impl AppMain for App {
         self.match_event(cx, event);
         self.ui.handle_event(cx, event, &mut Scope::empty());
         
+        // Keep the tray badge in sync when notifications are read or cleared in the panel
+        #[cfg(feature = "tray")]
+        if let Event::Actions(actions) = event {
+            if self.ui.button(id!(mark_read_button)).clicked(actions)
+                || self.ui.button(id!(clear_button)).clicked(actions)
+            {
+                if let Some(ref tray) = self.tray_handle {
+                    tray.set_unread_notifications(notifications::unread_count());
+                }
+            }
+        }
+        
         // Handle timer events for updating time display
         if self.time_update_timer.is_event(event).is_some() {
             // Update only the last update time label
@@ -135,11 +201,43 @@ impl AppMain for App {
         }
         
         // Handle tray menu events
+        #[cfg(feature = "tray")]
         if let Ok(event) = tray_icon::menu::MenuEvent::receiver().try_recv() {
             if let Some(ref tray) = self.tray_handle {
-                if tray.handle_menu_event(&event) {
-                    // Quit was selected
-                    cx.quit();
+                let request = match tray.handle_menu_event(&event) {
+                    Some(TrayCommand::Quit) => {
+                        let _ = submit_monitor_request(MonitorRequest::Shutdown);
+                        cx.quit();
+                        None
+                    }
+                    Some(TrayCommand::Refresh) => Some(MonitorRequest::ForceUpdate),
+                    Some(TrayCommand::Pause) => Some(MonitorRequest::Pause { duration_secs: Some(30 * 60) }),
+                    Some(TrayCommand::Resume) => Some(MonitorRequest::Resume),
+                    Some(TrayCommand::Snooze) => {
+                        let until = notifications::snooze(None);
+                        log!("Notifications snoozed until {}", until.format("%H:%M"));
+                        tray.set_snoozed(true);
+                        None
+                    }
+                    Some(TrayCommand::MarkNotificationsRead) => {
+                        notifications::mark_all_read();
+                        tray.set_unread_notifications(0);
+                        if let Some(mut main_screen) = self.ui.widget(id!(body)).borrow_mut::<MainScreen>() {
+                            main_screen.update_notifications(cx);
+                        }
+                        None
+                    }
+                    Some(TrayCommand::Unsnooze) => {
+                        notifications::cancel_snooze();
+                        tray.set_snoozed(false);
+                        None
+                    }
+                    None => None,
+                };
+                if let Some(request) = request {
+                    if let Err(e) = submit_monitor_request(request) {
+                        error!("Failed to submit tray request: {}", e);
+                    }
                 }
             }
         }
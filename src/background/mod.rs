@@ -1,98 +1,429 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::runtime::Runtime;
-use makepad_widgets::log;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
 use anyhow::Result;
+use tokio::sync::broadcast::error::RecvError;
 
-use crate::monitor::{NetworkMonitor, CcusageMonitor, MonitorData, CcusageData};
-use crate::ui_updates::{enqueue_monitor_update, MonitorUpdate};
-use crate::utils::notifications::{show_notification, show_usage_notification};
+use crate::monitor::{NetworkMonitor, CcusageMonitor, MonitorData, HealthState, UsageHistory, DailyCost};
+use crate::monitor::history::{BlockUsage, DayUsage};
+use crate::monitor::latency::{self, LatencyLog, LatencySample};
+use crate::ui_updates::{enqueue_monitor_update, subscribe_monitor_updates, MonitorUpdate, RequestAck};
+use crate::alerts::{AlertEngine, BlockResetNotifier, DigestScheduler};
+use crate::budget::{budget_statuses, BudgetAlerter, BudgetSettings};
+use crate::report::{self, ReportSchedule};
+use crate::import::{Import, ImportSummary};
+use crate::utils::notifications::{self, dispatch, NotificationEvent, NotificationKind, Severity};
+use crate::utils::notifications::email::{self, EmailDigestSchedule};
+use crate::utils::notifications::SinkConfig;
+use crate::utils::preferences::{MonitorProfile, Preferences};
+use crate::telemetry::{self, Cycle, ProbeOutcome};
 use crate::i18n;
+use crate::monitor_log as log;
 
 /// The single global Tokio runtime that is used by all async tasks.
 static TOKIO_RUNTIME: OnceLock<Runtime> = OnceLock::new();
 
-/// The sender used to send requests to the async worker thread.
-static REQUEST_SENDER: OnceLock<UnboundedSender<MonitorRequest>> = OnceLock::new();
+/// The sender used to send requests to the async worker thread, each with
+/// the id its acknowledgement carries.
+static REQUEST_SENDER: OnceLock<UnboundedSender<(u64, MonitorRequest)>> = OnceLock::new();
+
+/// The id of the next submitted request.
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The running worker task, kept so a shutdown can wait for it to finish.
+static WORKER_HANDLE: Mutex<Option<JoinHandle<Result<()>>>> = Mutex::new(None);
+
+pub use crate::monitor::DataSource;
 
 /// Requests that can be sent to the background monitor worker.
 #[derive(Debug, Clone)]
 pub enum MonitorRequest {
-    /// Start monitoring with the given interval in seconds
-    StartMonitoring { interval_secs: u64 },
+    /// Start monitoring, optionally overriding the network and usage intervals of the active profile
+    StartMonitoring { interval_secs: Option<u64> },
     /// Stop monitoring
     StopMonitoring,
     /// Force an immediate update
     ForceUpdate,
+    /// Change the polling interval of a single data source
+    SetInterval { source: DataSource, interval_secs: u64 },
+    /// Pause polling, optionally resuming automatically after the given duration
+    Pause { duration_secs: Option<u64> },
+    /// Resume polling after a pause
+    Resume,
+    /// Refresh the daily cost history immediately
+    RefreshHistory,
+    /// Merge ccusage dumps and exports into the usage history
+    ImportHistory { paths: Vec<PathBuf> },
+    /// Reload preferences from disk and apply the active profile
+    ReloadPreferences,
+    /// Switch to the named monitor profile and persist the choice
+    SwitchProfile { name: String },
+    /// Bring the desktop window to the front; only acknowledged by the worker
+    Show,
+    /// Stop the worker task entirely
+    Shutdown,
+}
+
+impl MonitorRequest {
+    /// A short, stable name for the request used in acknowledgements.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MonitorRequest::StartMonitoring { .. } => "StartMonitoring",
+            MonitorRequest::StopMonitoring => "StopMonitoring",
+            MonitorRequest::ForceUpdate => "ForceUpdate",
+            MonitorRequest::SetInterval { .. } => "SetInterval",
+            MonitorRequest::Pause { .. } => "Pause",
+            MonitorRequest::Resume => "Resume",
+            MonitorRequest::RefreshHistory => "RefreshHistory",
+            MonitorRequest::ImportHistory { .. } => "ImportHistory",
+            MonitorRequest::ReloadPreferences => "ReloadPreferences",
+            MonitorRequest::SwitchProfile { .. } => "SwitchProfile",
+            MonitorRequest::Show => "Show",
+            MonitorRequest::Shutdown => "Shutdown",
+        }
+    }
+}
+
+/// The global Tokio runtime, created on first use.
+pub fn tokio_runtime() -> &'static Runtime {
+    // Save the runtime in a static variable to ensure it isn't dropped.
+    TOKIO_RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().unwrap())
 }
 
 /// Start the background Tokio runtime and monitoring tasks.
+/// Fails when another instance is already running.
 pub fn start_monitor_tokio() -> Result<()> {
-    // Create a Tokio runtime, and save it in a static variable to ensure it isn't dropped.
-    let rt = TOKIO_RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().unwrap());
+    let rt = tokio_runtime();
+
+    // Only one instance per user, the others talk to it over the control socket
+    #[cfg(unix)]
+    crate::instance::start(rt)?;
+
+    // Before the worker, whose first updates are already traced
+    telemetry::init();
 
     // Create a channel to be used between UI thread(s) and the async worker thread.
-    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<MonitorRequest>();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<(u64, MonitorRequest)>();
     REQUEST_SENDER.set(sender).expect("BUG: REQUEST_SENDER already set!");
 
     // Start the async worker task directly on the runtime
-    let _worker_handle = rt.spawn(async_worker(receiver));
+    *WORKER_HANDLE.lock().unwrap() = Some(rt.spawn(async_worker(receiver)));
 
-    // Send initial start monitoring request (1 second for more real-time updates)
-    submit_monitor_request(MonitorRequest::StartMonitoring { interval_secs: 1 })?;
+    #[cfg(feature = "server")]
+    crate::server::spawn_servers(rt);
+    #[cfg(feature = "dbus")]
+    crate::dbus::spawn(rt);
+    #[cfg(feature = "mqtt")]
+    crate::mqtt::spawn(rt);
+
+    // Send initial start monitoring request using the intervals of the active profile
+    submit_monitor_request(MonitorRequest::StartMonitoring { interval_secs: None })?;
 
     Ok(())
 }
 
-/// Submit a request to the background monitor worker.
-pub fn submit_monitor_request(request: MonitorRequest) -> Result<()> {
+/// Submit a request to the background monitor worker and return the id of
+/// its acknowledgement.
+pub fn submit_monitor_request(request: MonitorRequest) -> Result<u64> {
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
     if let Some(sender) = REQUEST_SENDER.get() {
-        sender.send(request)?;
+        sender.send((id, request))?;
     } else {
         anyhow::bail!("Monitor worker not initialized");
     }
+    Ok(id)
+}
+
+/// Submit a request and wait up to `timeout` for the worker to acknowledge it.
+pub async fn submit_monitor_request_and_wait(request: MonitorRequest, timeout: Duration) -> Result<RequestAck> {
+    let name = request.name();
+    // Subscribe before submitting so the acknowledgement can't be missed
+    let mut updates = subscribe_monitor_updates();
+    let id = submit_monitor_request(request)?;
+
+    tokio::time::timeout(timeout, async {
+        loop {
+            match updates.recv().await {
+                Ok(MonitorUpdate::RequestAck(ack)) if ack.id == Some(id) => return Ok(ack),
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => anyhow::bail!("The monitor stopped"),
+            }
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("{} was not acknowledged within {:?}", name, timeout))?
+}
+
+/// Ask the worker to shut down and wait up to `timeout` for it to finish
+/// its current update.
+pub async fn stop_monitor_worker(timeout: Duration) -> Result<()> {
+    // Fails when the worker already stopped, e.g. after `cc-monitor-rs quit`
+    let _ = submit_monitor_request(MonitorRequest::Shutdown);
+    let handle = WORKER_HANDLE.lock().unwrap().take();
+    if let Some(handle) = handle {
+        tokio::time::timeout(timeout, handle)
+            .await
+            .map_err(|_| anyhow::anyhow!("Monitor worker did not stop within {:?}", timeout))???;
+    }
+    // Export the spans of the last updates
+    let _ = tokio::task::spawn_blocking(telemetry::shutdown).await;
     Ok(())
 }
 
+/// Runs a probe as part of an update cycle, recording its duration for the
+/// metrics exporters.
+#[cfg_attr(not(feature = "server"), allow(unused_variables))]
+fn timed<T: ProbeOutcome>(cycle: &Cycle, probe: &'static str, run: impl FnOnce() -> T) -> T {
+    let start = std::time::Instant::now();
+    let result = cycle.probe(probe, run);
+    #[cfg(feature = "server")]
+    crate::server::metrics::observe_probe(probe, start.elapsed());
+    result
+}
+
+/// How often held-back notifications and failed webhook deliveries are
+/// retried, independently of the polling intervals.
+const NOTIFICATION_FLUSH_SECS: u64 = 30;
+
+/// Creates a polling interval that doesn't burst when a slow update overruns a tick.
+fn polling_interval(secs: u64) -> Interval {
+    let mut interval = tokio::time::interval(Duration::from_secs(secs.max(1)));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+/// The polling intervals of the worker, one per data source.
+struct SourceIntervals {
+    network: Interval,
+    ccusage: Interval,
+    history: Interval,
+}
+
+impl SourceIntervals {
+    fn from_profile(profile: &MonitorProfile) -> Self {
+        Self {
+            network: polling_interval(profile.network_interval_secs),
+            ccusage: polling_interval(profile.ccusage_interval_secs),
+            history: polling_interval(profile.history_interval_secs),
+        }
+    }
+
+    fn set(&mut self, source: DataSource, interval_secs: u64) {
+        let interval = polling_interval(interval_secs);
+        match source {
+            DataSource::Network => self.network = interval,
+            DataSource::Ccusage => self.ccusage = interval,
+            DataSource::History => self.history = interval,
+        }
+    }
+
+    fn period(&self, source: DataSource) -> Duration {
+        match source {
+            DataSource::Network => self.network.period(),
+            DataSource::Ccusage => self.ccusage.period(),
+            DataSource::History => self.history.period(),
+        }
+    }
+}
+
+/// State shared between the individual source updates of the worker.
+struct WorkerState {
+    network_monitor: NetworkMonitor,
+    ccusage_monitor: Arc<Mutex<CcusageMonitor>>,
+    data: MonitorData,
+    last_network_status: Option<bool>,
+    alert_engine: AlertEngine,
+    reset_notifier: BlockResetNotifier,
+    digest_scheduler: DigestScheduler,
+    budget_settings: BudgetSettings,
+    budget_alerter: BudgetAlerter,
+    history: UsageHistory,
+    email_digests: Vec<EmailDigestSchedule>,
+    report_schedule: ReportSchedule,
+    report_directory: PathBuf,
+    /// Result of the last network update, recorded to the latency log on publish
+    latency_sample: Option<LatencySample>,
+    last_latency_record: Option<chrono::DateTime<chrono::Local>>,
+}
+
+/// What a history refresh produced: the full usage history, or only the
+/// daily costs when ccusage's JSON output isn't available.
+enum HistoryUpdate {
+    Usage(Vec<DayUsage>, Vec<BlockUsage>),
+    DailyCosts(HashMap<String, DailyCost>),
+}
+
+fn email_digest_schedules(sinks: &[SinkConfig]) -> Vec<EmailDigestSchedule> {
+    let now = chrono::Local::now();
+    email::email_configs(sinks)
+        .into_iter()
+        .map(|config| EmailDigestSchedule::new(config, now))
+        .collect()
+}
+
 /// The main async worker that handles monitoring tasks.
-async fn async_worker(mut receiver: UnboundedReceiver<MonitorRequest>) -> Result<()> {
+async fn async_worker(mut receiver: UnboundedReceiver<(u64, MonitorRequest)>) -> Result<()> {
     log!("Monitor async worker started");
-    
-    let network_monitor = NetworkMonitor::new();
-    let ccusage_monitor = Arc::new(Mutex::new(CcusageMonitor::new()));
+
+    let prefs = Preferences::load().unwrap_or_default();
+    let mut intervals = SourceIntervals::from_profile(&prefs.active_profile());
     let mut monitoring_active = false;
-    let mut interval = tokio::time::interval(Duration::from_secs(3));
-    let mut last_network_status: Option<bool> = None;
-    let mut last_notification_time = std::time::Instant::now();
-    let notification_interval = Duration::from_secs(3600); // 1 hour
+    let mut paused = false;
+    let mut resume_at: Option<Instant> = None;
+    let mut notification_flush = polling_interval(NOTIFICATION_FLUSH_SECS);
+    let mut state = WorkerState::new(prefs);
+    tokio::task::spawn_blocking(|| {
+        if let Err(e) = LatencyLog::prune() {
+            log!("Failed to prune the latency log: {}", e);
+        }
+    });
 
     loop {
+        let polling = monitoring_active && !paused;
+
         tokio::select! {
             // Handle incoming requests
-            Some(request) = receiver.recv() => {
-                match request {
+            Some((id, request)) = receiver.recv() => {
+                let name = request.name();
+                let ack = match request {
                     MonitorRequest::StartMonitoring { interval_secs } => {
-                        log!("Starting monitoring with interval: {} seconds", interval_secs);
+                        log!("Starting monitoring with interval: {:?} seconds", interval_secs);
                         monitoring_active = true;
-                        interval = tokio::time::interval(Duration::from_secs(interval_secs));
+                        if let Some(interval_secs) = interval_secs {
+                            intervals.set(DataSource::Network, interval_secs);
+                            intervals.set(DataSource::Ccusage, interval_secs);
+                        }
+                        RequestAck::ok(name, None)
                     }
                     MonitorRequest::StopMonitoring => {
                         log!("Stopping monitoring");
                         monitoring_active = false;
+                        RequestAck::ok(name, None)
                     }
                     MonitorRequest::ForceUpdate => {
                         log!("Forcing immediate update");
-                        perform_monitor_update(&network_monitor, &ccusage_monitor, &mut last_network_status, &mut last_notification_time, notification_interval).await;
+                        state.update_network(&intervals).await;
+                        state.update_ccusage(&intervals).await;
+                        state.refresh_history(&intervals).await;
+                        state.publish();
+                        RequestAck::ok(name, None)
+                    }
+                    MonitorRequest::SetInterval { source, interval_secs } => {
+                        log!("Setting {:?} interval to {} seconds", source, interval_secs);
+                        intervals.set(source, interval_secs);
+                        RequestAck::ok(name, Some(format!("{:?}: {}s", source, interval_secs.max(1))))
+                    }
+                    MonitorRequest::Pause { duration_secs } => {
+                        log!("Pausing monitoring for {:?} seconds", duration_secs);
+                        paused = true;
+                        resume_at = duration_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
+                        RequestAck::ok(name, duration_secs.map(|secs| format!("{}s", secs)))
+                    }
+                    MonitorRequest::Resume => {
+                        log!("Resuming monitoring");
+                        paused = false;
+                        resume_at = None;
+                        RequestAck::ok(name, None)
                     }
+                    MonitorRequest::RefreshHistory => {
+                        state.refresh_history(&intervals).await;
+                        state.publish();
+                        RequestAck::ok(name, None)
+                    }
+                    MonitorRequest::ImportHistory { paths } => {
+                        match state.import_history(paths).await {
+                            Ok(summary) => {
+                                state.publish();
+                                RequestAck::ok(name, Some(format!("{} new days, {} new blocks", summary.new_days, summary.new_blocks)))
+                            }
+                            Err(e) => RequestAck::failed(name, e.to_string()),
+                        }
+                    }
+                    MonitorRequest::ReloadPreferences => {
+                        match Preferences::load() {
+                            Ok(prefs) => {
+                                i18n::set_language(prefs.get_language());
+                                intervals = SourceIntervals::from_profile(&prefs.active_profile());
+                                state.digest_scheduler.configure(&prefs.digest_schedule);
+                                state.reset_notifier.configure(prefs.reset_marks_minutes, prefs.notify_window_available, prefs.plan_token_limit);
+                                state.alert_engine.set_rules(prefs.alert_rules, prefs.plan_token_limit);
+                                state.data.plan_token_limit = prefs.plan_token_limit;
+                                state.email_digests = email_digest_schedules(&prefs.notification_sinks);
+                                state.report_directory = prefs.report.directory(&prefs.export);
+                                state.report_schedule.configure(prefs.report);
+                                state.budget_settings = prefs.budgets;
+                                notifications::reload_policy(prefs.notification_policy);
+                                notifications::reload_sinks(&prefs.notification_sinks);
+                                RequestAck::ok(name, Some(prefs.active_profile))
+                            }
+                            Err(e) => RequestAck::failed(name, e.to_string()),
+                        }
+                    }
+                    MonitorRequest::SwitchProfile { name: profile_name } => {
+                        match switch_profile(&profile_name) {
+                            Ok(profile) => {
+                                log!("Switched to profile: {}", profile_name);
+                                intervals = SourceIntervals::from_profile(&profile);
+                                RequestAck::ok(name, Some(profile_name))
+                            }
+                            Err(e) => RequestAck::failed(name, e.to_string()),
+                        }
+                    }
+                    MonitorRequest::Show => RequestAck::ok(name, None),
+                    MonitorRequest::Shutdown => {
+                        log!("Monitor worker shutting down");
+                        enqueue_monitor_update(MonitorUpdate::RequestAck(RequestAck::ok(name, None).with_id(id)));
+                        break;
+                    }
+                };
+                enqueue_monitor_update(MonitorUpdate::RequestAck(ack.with_id(id)));
+            }
+
+            // Automatic resume after a timed pause
+            _ = tokio::time::sleep_until(resume_at.unwrap_or_else(Instant::now)), if paused && resume_at.is_some() => {
+                log!("Pause expired, resuming monitoring");
+                paused = false;
+                resume_at = None;
+                enqueue_monitor_update(MonitorUpdate::RequestAck(RequestAck::ok("Resume", None)));
+            }
+
+            // Also while paused, so the quiet hours digest isn't held back until polling resumes
+            _ = notification_flush.tick(), if !receiver.is_closed() => {
+                tokio::task::spawn_blocking(|| {
+                    // Deliver notifications held back during quiet hours once they end
+                    notifications::flush_digest();
+                    // Retry failed webhook deliveries
+                    notifications::flush_sinks();
+                });
+            }
+
+            // Periodic monitoring ticks, one per data source. Sources that keep
+            // failing are skipped until their backoff delay has passed.
+            _ = intervals.network.tick(), if polling => {
+                if state.data.health.network.should_poll() {
+                    state.update_network(&intervals).await;
+                    state.publish();
+                }
+            }
+            _ = intervals.ccusage.tick(), if polling => {
+                if state.data.health.ccusage.should_poll() {
+                    state.update_ccusage(&intervals).await;
+                    state.publish();
                 }
             }
-            
-            // Periodic monitoring tick
-            _ = interval.tick(), if monitoring_active => {
-                perform_monitor_update(&network_monitor, &ccusage_monitor, &mut last_network_status, &mut last_notification_time, notification_interval).await;
+            _ = intervals.history.tick(), if polling => {
+                if state.data.health.history.should_poll() {
+                    state.refresh_history(&intervals).await;
+                    state.publish();
+                }
             }
-            
+
             // If channel is closed, exit
             else => {
                 log!("Monitor worker channel closed, exiting");
@@ -104,116 +435,308 @@ async fn async_worker(mut receiver: UnboundedReceiver<MonitorRequest>) -> Result
     Ok(())
 }
 
-/// Perform a single monitoring update.
-async fn perform_monitor_update(
-    network_monitor: &NetworkMonitor,
-    ccusage_monitor: &Arc<Mutex<CcusageMonitor>>,
-    last_network_status: &mut Option<bool>,
-    last_notification_time: &mut std::time::Instant,
-    notification_interval: Duration,
-) {
-    log!("Performing monitor update");
-
-    // Run network monitoring in blocking task
-    let (ping_success, latency) = tokio::task::spawn_blocking({
-        let monitor = network_monitor.clone();
-        move || monitor.ping_google()
-    }).await.unwrap_or((false, None));
-
-    let (conn_success, speed, _) = tokio::task::spawn_blocking({
-        let monitor = network_monitor.clone();
-        move || monitor.check_connection()
-    }).await.unwrap_or((false, None, None));
-
-    log!("Network status - ping: {}, conn: {}, latency: {:?}, speed: {:?}", 
-         ping_success, conn_success, latency, speed);
-
-    // Run ccusage monitoring in blocking task
-    let ccusage_data = tokio::task::spawn_blocking({
-        let monitor = ccusage_monitor.clone();
-        move || {
-            let mut monitor = monitor.lock().unwrap();
-            monitor.get_ccusage_info()
-        }
-    }).await.unwrap_or_else(|_| CcusageData::default());
-
-    log!("Ccusage data: session={}, tokens={}, cost={}", 
-         ccusage_data.latest_session, ccusage_data.tokens, ccusage_data.cost);
-
-    let daily_costs = tokio::task::spawn_blocking({
-        let monitor = ccusage_monitor.clone();
-        move || {
-            let monitor = monitor.lock().unwrap();
-            monitor.analyze_daily_costs()
-        }
-    }).await.unwrap_or_default();
-
-    // Check for network status changes
-    let network_connected = ping_success || conn_success;
-    if let Some(last_status) = last_network_status {
-        if !*last_status && network_connected {
-            // Network restored notification
-            let _ = tokio::task::spawn_blocking(|| {
-                show_notification(
-                    &i18n::get(i18n::keys::NOTIF_TITLE),
-                    &i18n::get(i18n::keys::NOTIF_NETWORK_RESTORED)
-                );
-            });
-        } else if *last_status && !network_connected {
-            // Network lost notification
-            let _ = tokio::task::spawn_blocking(|| {
-                show_notification(
-                    &i18n::get(i18n::keys::NOTIF_TITLE),
-                    &i18n::get(i18n::keys::NOTIF_NETWORK_LOST)
-                );
-            });
+/// Gather a single snapshot without starting the worker, for one-shot use
+/// from the command line. Daily costs come from the stored usage history
+/// unless `refresh_history` is set. No alerts are evaluated and nothing is
+/// sent to the UI.
+pub fn collect_snapshot(refresh_history: bool) -> MonitorData {
+    tokio_runtime().block_on(async {
+        let prefs = Preferences::load().unwrap_or_default();
+        let intervals = SourceIntervals::from_profile(&prefs.active_profile());
+        let mut state = WorkerState::new(prefs);
+
+        state.update_network(&intervals).await;
+        state.update_ccusage(&intervals).await;
+        if refresh_history || state.history.is_empty() {
+            state.refresh_history(&intervals).await;
+        } else {
+            state.data.daily_costs = state.history.daily_costs();
+        }
+        state.data.last_update = chrono::Local::now();
+        state.data.budgets = budget_statuses(&state.budget_settings, &state.data.daily_costs);
+        state.data
+    })
+}
+
+/// Persist a profile switch and return the newly selected profile.
+fn switch_profile(name: &str) -> Result<MonitorProfile> {
+    let mut prefs = Preferences::load()?;
+    let profile = prefs.switch_profile(name)?;
+    prefs.save()?;
+    Ok(profile)
+}
+
+impl WorkerState {
+    fn new(prefs: Preferences) -> Self {
+        Self {
+            network_monitor: NetworkMonitor::new(),
+            ccusage_monitor: Arc::new(Mutex::new(CcusageMonitor::new())),
+            data: MonitorData {
+                plan_token_limit: prefs.plan_token_limit,
+                ..MonitorData::default()
+            },
+            last_network_status: None,
+            digest_scheduler: DigestScheduler::new(&prefs.digest_schedule),
+            reset_notifier: BlockResetNotifier::new(prefs.reset_marks_minutes, prefs.notify_window_available, prefs.plan_token_limit),
+            alert_engine: AlertEngine::new(prefs.alert_rules, prefs.plan_token_limit),
+            email_digests: email_digest_schedules(&prefs.notification_sinks),
+            report_directory: prefs.report.directory(&prefs.export),
+            report_schedule: ReportSchedule::new(prefs.report, chrono::Local::now()),
+            budget_settings: prefs.budgets,
+            budget_alerter: BudgetAlerter::load().unwrap_or_else(|e| {
+                log!("Failed to load fired budget alerts: {}", e);
+                BudgetAlerter::default()
+            }),
+            history: UsageHistory::load().unwrap_or_else(|e| {
+                log!("Failed to load usage history: {}", e);
+                UsageHistory::default()
+            }),
+            latency_sample: None,
+            last_latency_record: None,
         }
     }
-    *last_network_status = Some(network_connected);
-
-    // Create monitor data
-    let monitor_data = MonitorData {
-        network_connected,
-        latency,
-        connection_speed: speed,
-        ccusage_data: ccusage_data.clone(),
-        daily_costs,
-        last_update: chrono::Local::now(),
-    };
-
-    // Check if we should send periodic notification
-    let now = std::time::Instant::now();
-    if now.duration_since(*last_notification_time) >= notification_interval {
-        *last_notification_time = now;
-        
-        // Calculate remaining time
-        let remaining_time = if let Some(reset_time) = ccusage_data.reset_time {
-            let now = chrono::Local::now();
-            if reset_time > now {
-                let duration = reset_time - now;
-                let hours = duration.num_hours();
-                let minutes = duration.num_minutes() % 60;
-                format!("{} {} {} {}", 
-                    hours, i18n::get(i18n::keys::COMMON_HOUR),
-                    minutes, i18n::get(i18n::keys::COMMON_MINUTE))
-            } else {
-                i18n::get(i18n::keys::USAGE_EXPIRED)
+
+    /// Probe the network and notify on connectivity changes.
+    async fn update_network(&mut self, intervals: &SourceIntervals) {
+        let cycle = Cycle::start(DataSource::Network);
+        // Run network monitoring in blocking task
+        let probes = tokio::task::spawn_blocking({
+            let monitor = self.network_monitor.clone();
+            let cycle = cycle.clone();
+            move || (timed(&cycle, "ping", || monitor.ping_google()), timed(&cycle, "connection", || monitor.check_connection()))
+        }).await;
+
+        let ((ping_success, latency), (conn_success, speed, connect_ms)) = match probes {
+            Ok(probes) => probes,
+            Err(e) => {
+                cycle.end(Some(&e.to_string()));
+                self.data.health.network.record_failure(e.to_string(), intervals.period(DataSource::Network));
+                return;
             }
-        } else {
-            i18n::get(i18n::keys::COMMON_UNKNOWN)
         };
-        
-        // Send usage notification
-        let _ = tokio::task::spawn_blocking({
-            let tokens = ccusage_data.tokens_num;
-            let cost = ccusage_data.cost_num;
-            let remaining = remaining_time.clone();
+        let network_connected = ping_success || conn_success;
+        let error = (!network_connected).then_some("Network unreachable");
+        cycle.end(error);
+        match error {
+            None => self.data.health.network.record_success(),
+            Some(error) => {
+                self.data.health.network.record_failure(error, intervals.period(DataSource::Network));
+            }
+        }
+
+        log!("Network status - ping: {}, conn: {}, latency: {:?}, speed: {:?}",
+             ping_success, conn_success, latency, speed);
+
+        // Check for network status changes
+        if let Some(last_status) = self.last_network_status {
+            if !last_status && network_connected {
+                // Network restored notification
+                tokio::task::spawn_blocking(|| {
+                    dispatch(NotificationEvent::new(
+                        NotificationKind::Network,
+                        Severity::Info,
+                        i18n::get(i18n::keys::NOTIF_TITLE),
+                        i18n::get(i18n::keys::NOTIF_NETWORK_RESTORED),
+                    ));
+                });
+            } else if last_status && !network_connected {
+                // Network lost notification
+                tokio::task::spawn_blocking(|| {
+                    dispatch(NotificationEvent::new(
+                        NotificationKind::Network,
+                        Severity::Warning,
+                        i18n::get(i18n::keys::NOTIF_TITLE),
+                        i18n::get(i18n::keys::NOTIF_NETWORK_LOST),
+                    ));
+                });
+            }
+        }
+        self.last_network_status = Some(network_connected);
+
+        self.latency_sample = Some(LatencySample {
+            time: chrono::Local::now(),
+            connected: network_connected,
+            ping_ms: LatencySample::parse_ping(latency.as_deref()),
+            connect_ms,
+        });
+        self.data.network_connected = network_connected;
+        self.data.latency = latency;
+        self.data.connection_speed = speed;
+    }
+
+    /// Fetch the current ccusage block.
+    /// On failure the previous data is kept and the source health is updated.
+    async fn update_ccusage(&mut self, intervals: &SourceIntervals) {
+        let cycle = Cycle::start(DataSource::Ccusage);
+        // Run ccusage monitoring in blocking task
+        let result = tokio::task::spawn_blocking({
+            let monitor = self.ccusage_monitor.clone();
+            let cycle = cycle.clone();
             move || {
-                show_usage_notification(tokens, cost, &remaining);
+                let mut monitor = monitor.lock().unwrap();
+                timed(&cycle, "ccusage", || monitor.get_ccusage_info())
+            }
+        }).await.map_err(anyhow::Error::from).and_then(|result| result);
+        cycle.end(result.error().as_deref());
+
+        let ccusage_data = match result {
+            Ok(data) => {
+                self.data.health.ccusage.record_success();
+                data
+            }
+            Err(e) => {
+                log!("ccusage update failed: {}", e);
+                let previous = self.data.health.ccusage.state;
+                let state = self.data.health.ccusage.record_failure(e.to_string(), intervals.period(DataSource::Ccusage));
+                if state == HealthState::Failing && previous != HealthState::Failing {
+                    // Refresh the ccusage package once when the source starts failing
+                    let monitor = self.ccusage_monitor.clone();
+                    tokio::task::spawn_blocking(move || monitor.lock().unwrap().update_ccusage());
+                }
+                return;
+            }
+        };
+
+        log!("Ccusage data: session={}, tokens={}, cost={}",
+             ccusage_data.latest_session, ccusage_data.tokens, ccusage_data.cost);
+
+        self.data.ccusage_data = ccusage_data;
+    }
+
+    /// Reload the usage history and daily costs, keeping the previous history on failure.
+    async fn refresh_history(&mut self, intervals: &SourceIntervals) {
+        let cycle = Cycle::start(DataSource::History);
+        let result = tokio::task::spawn_blocking({
+            let monitor = self.ccusage_monitor.clone();
+            let cycle = cycle.clone();
+            move || {
+                let mut monitor = monitor.lock().unwrap();
+                match timed(&cycle, "history", || monitor.fetch_usage_history()) {
+                    Ok((days, blocks)) => Ok(HistoryUpdate::Usage(days, blocks)),
+                    Err(e) => {
+                        // Older ccusage versions only have the daily table
+                        log!("Usage history unavailable, falling back to the daily table: {}", e);
+                        monitor.analyze_daily_costs().map(HistoryUpdate::DailyCosts)
+                    }
+                }
+            }
+        }).await.map_err(anyhow::Error::from).and_then(|result| result);
+        cycle.end(result.error().as_deref());
+
+        match result {
+            Ok(HistoryUpdate::Usage(days, blocks)) => {
+                self.data.health.history.record_success();
+                self.history.merge_days(days);
+                self.history.merge_blocks(blocks);
+                if let Err(e) = self.history.save() {
+                    log!("Failed to save usage history: {}", e);
+                }
+                self.data.daily_costs = self.history.daily_costs();
+            }
+            Ok(HistoryUpdate::DailyCosts(daily_costs)) => {
+                self.data.health.history.record_success();
+                self.data.daily_costs = daily_costs;
+            }
+            Err(e) => {
+                log!("History refresh failed: {}", e);
+                self.data.health.history.record_failure(e.to_string(), intervals.period(DataSource::History));
+            }
+        }
+    }
+
+    /// Append the last network result to the latency log, at most once per
+    /// sample interval.
+    fn record_latency(&mut self) {
+        let now = chrono::Local::now();
+        let due = self.last_latency_record
+            .is_none_or(|last| (now - last).num_seconds() >= latency::SAMPLE_INTERVAL_SECS);
+        if !due {
+            return;
+        }
+        if let Some(sample) = self.latency_sample.take() {
+            self.last_latency_record = Some(now);
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = LatencyLog::append(&sample) {
+                    log!("Failed to record latency: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Email the daily and weekly digests that are due, skipping periods without activity.
+    fn send_email_digests(&mut self) {
+        let now = chrono::Local::now();
+        for schedule in self.email_digests.iter_mut() {
+            for period in schedule.due(now) {
+                let (from, to) = period.range(now.date_naive());
+                let summary = self.history.summarize(from, to);
+                if !summary.has_activity() {
+                    continue;
+                }
+                let (subject, body) = email::digest_message(period, from, to, &summary, &self.data.budgets);
+                let config = schedule.config.clone();
+                tokio::task::spawn_blocking(move || {
+                    if let Err(e) = config.send(&subject, &body) {
+                        log!("Failed to send email digest: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Read the import files and merge them into the history, so the next
+    /// refresh doesn't overwrite the stored file without them.
+    async fn import_history(&mut self, paths: Vec<PathBuf>) -> Result<ImportSummary> {
+        let import = tokio::task::spawn_blocking(move || Import::read(&paths)).await??;
+        let summary = import.apply(&mut self.history);
+        self.history.save()?;
+        self.data.daily_costs = self.history.daily_costs();
+        log!("Imported {} new days and {} new blocks", summary.new_days, summary.new_blocks);
+        Ok(summary)
+    }
+
+    /// Write the weekly and monthly reports that are due into the report folder.
+    /// They are generated and rendered off the worker from a copy of the history.
+    fn write_scheduled_reports(&mut self) {
+        let now = chrono::Local::now();
+        let format = self.report_schedule.format();
+        for period in self.report_schedule.due(now) {
+            let (from, to) = period.range(now.date_naive());
+            let history = self.history.clone();
+            let budgets = self.budget_settings.clone();
+            let path = self.report_directory.join(report::file_name(format, from, to));
+            tokio::task::spawn_blocking(move || {
+                match report::generate(&history, &budgets, from, to).write(format, &path) {
+                    Ok(()) => log!("Wrote usage report to {}", path.display()),
+                    Err(e) => log!("Failed to write usage report: {}", e),
+                }
+            });
+        }
+    }
+
+    /// Evaluate the alert rules, block reset marks, usage digests and budgets, then enqueue the
+    /// latest combined data for the UI.
+    fn publish(&mut self) {
+        self.data.last_update = chrono::Local::now();
+        self.data.budgets = budget_statuses(&self.budget_settings, &self.data.daily_costs);
+
+        let mut fired = self.alert_engine.evaluate(&self.data);
+        fired.extend(self.reset_notifier.evaluate(&self.data));
+        fired.extend(self.digest_scheduler.evaluate(&self.data));
+        fired.extend(self.budget_alerter.evaluate(&self.data.budgets));
+        tokio::task::spawn_blocking(move || {
+            for alert in fired {
+                log!("Alert rule fired: {}", alert.rule);
+                dispatch(alert.into_event());
             }
         });
+        self.send_email_digests();
+        self.write_scheduled_reports();
+        self.record_latency();
+
+        #[cfg(feature = "server")]
+        crate::server::metrics::update(&self.data);
+        telemetry::update(&self.data);
+        enqueue_monitor_update(MonitorUpdate::DataUpdate(Box::new(self.data.clone())));
     }
-    
-    // Enqueue update for UI
-    enqueue_monitor_update(MonitorUpdate::DataUpdate(monitor_data));
-}
\ No newline at end of file
+}
@@ -0,0 +1,362 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use anyhow::Result;
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::FiredAlert;
+use crate::monitor::DailyCost;
+use crate::utils::notifications::{NotificationKind, Severity};
+use crate::i18n;
+use crate::monitor_log as log;
+
+fn default_billing_cycle_start_day() -> u32 {
+    1
+}
+
+fn default_warning_percent() -> f64 {
+    80.0
+}
+
+fn default_critical_percent() -> f64 {
+    100.0
+}
+
+/// Dollar budgets and the thresholds at which to notify, stored in the preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetSettings {
+    #[serde(default)]
+    pub daily: Option<f64>,
+    #[serde(default)]
+    pub weekly: Option<f64>,
+    #[serde(default)]
+    pub monthly: Option<f64>,
+    /// Day of the month on which the billing month starts (1-28)
+    #[serde(default = "default_billing_cycle_start_day")]
+    pub billing_cycle_start_day: u32,
+    #[serde(default = "default_warning_percent")]
+    pub warning_percent: f64,
+    #[serde(default = "default_critical_percent")]
+    pub critical_percent: f64,
+}
+
+impl Default for BudgetSettings {
+    fn default() -> Self {
+        Self {
+            daily: None,
+            weekly: None,
+            monthly: None,
+            billing_cycle_start_day: default_billing_cycle_start_day(),
+            warning_percent: default_warning_percent(),
+            critical_percent: default_critical_percent(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Daily,
+    /// Monday to Sunday
+    Weekly,
+    /// Starting on the billing cycle start day
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn label(&self) -> String {
+        match self {
+            BudgetPeriod::Daily => i18n::get(i18n::keys::BUDGET_DAILY),
+            BudgetPeriod::Weekly => i18n::get(i18n::keys::BUDGET_WEEKLY),
+            BudgetPeriod::Monthly => i18n::get(i18n::keys::BUDGET_MONTHLY),
+        }
+    }
+
+    /// The first day of the period containing `today`.
+    fn start(&self, today: NaiveDate, billing_cycle_start_day: u32) -> NaiveDate {
+        match self {
+            BudgetPeriod::Daily => today,
+            BudgetPeriod::Weekly => today - Days::new(today.weekday().num_days_from_monday() as u64),
+            BudgetPeriod::Monthly => {
+                let start_day = billing_cycle_start_day.clamp(1, 28);
+                let this_month = today.with_day(start_day).unwrap_or(today);
+                if today.day() >= start_day {
+                    this_month
+                } else {
+                    this_month - Months::new(1)
+                }
+            }
+        }
+    }
+}
+
+/// Spend against one budget for the current period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub period: BudgetPeriod,
+    pub limit: f64,
+    pub spent: f64,
+    pub period_start: NaiveDate,
+    pub warning_percent: f64,
+    pub critical_percent: f64,
+}
+
+impl BudgetStatus {
+    pub fn percent(&self) -> f64 {
+        if self.limit > 0.0 {
+            self.spent / self.limit * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn level(&self) -> Option<Severity> {
+        let percent = self.percent();
+        if percent >= self.critical_percent {
+            Some(Severity::Critical)
+        } else if percent >= self.warning_percent {
+            Some(Severity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// Computes the spend of every configured budget from the daily cost history.
+pub fn budget_statuses(settings: &BudgetSettings, daily_costs: &HashMap<String, DailyCost>) -> Vec<BudgetStatus> {
+    budget_statuses_on(settings, daily_costs, Local::now().date_naive())
+}
+
+fn budget_statuses_on(settings: &BudgetSettings, daily_costs: &HashMap<String, DailyCost>, today: NaiveDate) -> Vec<BudgetStatus> {
+    let days: Vec<(NaiveDate, f64)> = daily_costs.values()
+        .filter_map(|dc| dc.day(today).map(|day| (day, dc.cost)))
+        .collect();
+
+    [
+        (BudgetPeriod::Daily, settings.daily),
+        (BudgetPeriod::Weekly, settings.weekly),
+        (BudgetPeriod::Monthly, settings.monthly),
+    ]
+    .into_iter()
+    .filter_map(|(period, limit)| {
+        let limit = limit.filter(|limit| *limit > 0.0)?;
+        let period_start = period.start(today, settings.billing_cycle_start_day);
+        let spent = days.iter()
+            .filter(|(day, _)| *day >= period_start && *day <= today)
+            .map(|(_, cost)| cost)
+            .sum();
+        Some(BudgetStatus {
+            period,
+            limit,
+            spent,
+            period_start,
+            warning_percent: settings.warning_percent,
+            critical_percent: settings.critical_percent,
+        })
+    })
+    .collect()
+}
+
+/// Fires each budget level at most once per budget period. When loaded, the
+/// fired levels are kept next to the usage history so a restart doesn't
+/// repeat them.
+#[derive(Debug, Default)]
+pub struct BudgetAlerter {
+    fired: HashSet<(BudgetPeriod, NaiveDate, Severity)>,
+    /// File the fired levels are saved to; not saved when unset
+    path: Option<PathBuf>,
+}
+
+impl BudgetAlerter {
+    /// The alerter with the levels fired before the last restart.
+    pub fn load() -> Result<Self> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+        Self::load_from(config_dir.join("cc-monitor-rs").join("budget_alerts.json"))
+    }
+
+    fn load_from(path: PathBuf) -> Result<Self> {
+        let fired = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            HashSet::new()
+        };
+        Ok(Self { fired, path: Some(path) })
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(&self.fired)?)?;
+        Ok(())
+    }
+
+    pub fn evaluate(&mut self, statuses: &[BudgetStatus]) -> Vec<FiredAlert> {
+        let mut alerts = Vec::new();
+        let fired = self.fired.len();
+
+        // Forget levels fired in periods that have ended
+        self.fired.retain(|(period, start, _)| {
+            statuses.iter().any(|status| status.period == *period && status.period_start == *start)
+        });
+        let mut changed = self.fired.len() != fired;
+
+        for status in statuses {
+            let Some(level) = status.level() else {
+                continue;
+            };
+            if !self.fired.insert((status.period, status.period_start, level)) {
+                continue;
+            }
+            changed = true;
+            // Reaching critical first also covers the warning level
+            if level == Severity::Critical {
+                self.fired.insert((status.period, status.period_start, Severity::Warning));
+            }
+
+            let key = if level == Severity::Critical {
+                i18n::keys::NOTIF_BUDGET_CRITICAL
+            } else {
+                i18n::keys::NOTIF_BUDGET_WARNING
+            };
+            alerts.push(FiredAlert {
+                rule: "budget".to_string(),
+                kind: NotificationKind::Budget,
+                severity: level,
+                title: i18n::get(i18n::keys::NOTIF_TITLE),
+                message: i18n::format(key, &[
+                    &status.period.label(),
+                    &format!("{:.0}", status.percent()),
+                    &format!("{:.2}", status.spent),
+                    &format!("{:.2}", status.limit),
+                ]),
+            });
+        }
+
+        if changed && let Err(e) = self.save() {
+            log!("Failed to save fired budget alerts: {}", e);
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn costs(days: &[(&str, f64)]) -> HashMap<String, DailyCost> {
+        days.iter()
+            .map(|(date, cost)| (date.to_string(), DailyCost { date: date.to_string(), cost: *cost, sessions: 1 }))
+            .collect()
+    }
+
+    fn status(period: BudgetPeriod, limit: f64, spent: f64, period_start: NaiveDate) -> BudgetStatus {
+        BudgetStatus {
+            period,
+            limit,
+            spent,
+            period_start,
+            warning_percent: default_warning_percent(),
+            critical_percent: default_critical_percent(),
+        }
+    }
+
+    #[test]
+    fn period_starts() {
+        // A Wednesday
+        let today = date(2024, 5, 15);
+        assert_eq!(BudgetPeriod::Daily.start(today, 1), today);
+        assert_eq!(BudgetPeriod::Weekly.start(today, 1), date(2024, 5, 13));
+        assert_eq!(BudgetPeriod::Monthly.start(today, 1), date(2024, 5, 1));
+        assert_eq!(BudgetPeriod::Monthly.start(today, 15), date(2024, 5, 15));
+        assert_eq!(BudgetPeriod::Monthly.start(today, 20), date(2024, 4, 20));
+        // Across the year and clamped to the 28th
+        assert_eq!(BudgetPeriod::Monthly.start(date(2024, 1, 3), 10), date(2023, 12, 10));
+        assert_eq!(BudgetPeriod::Monthly.start(date(2024, 3, 30), 31), date(2024, 3, 28));
+    }
+
+    #[test]
+    fn spend_is_summed_over_the_current_period() {
+        let settings = BudgetSettings {
+            daily: Some(10.0),
+            weekly: Some(50.0),
+            monthly: Some(100.0),
+            billing_cycle_start_day: 14,
+            ..BudgetSettings::default()
+        };
+        let daily_costs = costs(&[("05-12", 1.0), ("05-13", 2.0), ("05-14", 4.0), ("05-15", 8.0), ("04-30", 16.0)]);
+
+        let statuses = budget_statuses_on(&settings, &daily_costs, date(2024, 5, 15));
+        let spent: Vec<(BudgetPeriod, f64)> = statuses.iter().map(|status| (status.period, status.spent)).collect();
+        assert_eq!(spent, vec![
+            (BudgetPeriod::Daily, 8.0),
+            (BudgetPeriod::Weekly, 14.0),
+            (BudgetPeriod::Monthly, 12.0),
+        ]);
+    }
+
+    #[test]
+    fn unset_budgets_are_skipped() {
+        let settings = BudgetSettings { daily: Some(0.0), weekly: Some(5.0), ..BudgetSettings::default() };
+        let statuses = budget_statuses_on(&settings, &HashMap::new(), date(2024, 5, 15));
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].period, BudgetPeriod::Weekly);
+    }
+
+    #[test]
+    fn levels_fire_once_per_period() {
+        let mut alerter = BudgetAlerter::default();
+        let day = date(2024, 5, 15);
+
+        assert!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 5.0, day)]).is_empty());
+        let fired = alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 8.0, day)]);
+        assert_eq!(fired.iter().map(|alert| alert.severity).collect::<Vec<_>>(), vec![Severity::Warning]);
+        assert!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 9.0, day)]).is_empty());
+        let fired = alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 10.0, day)]);
+        assert_eq!(fired.iter().map(|alert| alert.severity).collect::<Vec<_>>(), vec![Severity::Critical]);
+        assert!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 12.0, day)]).is_empty());
+    }
+
+    #[test]
+    fn critical_first_covers_warning() {
+        let mut alerter = BudgetAlerter::default();
+        let day = date(2024, 5, 15);
+
+        assert_eq!(alerter.evaluate(&[status(BudgetPeriod::Weekly, 10.0, 11.0, day)]).len(), 1);
+        assert!(alerter.evaluate(&[status(BudgetPeriod::Weekly, 10.0, 11.0, day)]).is_empty());
+    }
+
+    #[test]
+    fn fired_levels_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("cc-monitor-budget-alerts-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let day = date(2024, 5, 15);
+
+        let mut alerter = BudgetAlerter::load_from(path.clone()).unwrap();
+        assert_eq!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 9.0, day)]).len(), 1);
+
+        let mut restarted = BudgetAlerter::load_from(path.clone()).unwrap();
+        assert!(restarted.evaluate(&[status(BudgetPeriod::Daily, 10.0, 9.0, day)]).is_empty());
+        assert_eq!(restarted.evaluate(&[status(BudgetPeriod::Daily, 10.0, 10.0, day)]).len(), 1);
+        assert_eq!(restarted.evaluate(&[status(BudgetPeriod::Daily, 10.0, 9.0, date(2024, 5, 16))]).len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn levels_fire_again_after_rollover() {
+        let mut alerter = BudgetAlerter::default();
+
+        assert_eq!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 9.0, date(2024, 5, 15))]).len(), 1);
+        assert!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 1.0, date(2024, 5, 16))]).is_empty());
+        assert_eq!(alerter.evaluate(&[status(BudgetPeriod::Daily, 10.0, 9.0, date(2024, 5, 16))]).len(), 1);
+    }
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+
+use crate::instance::{self, ControlCommand};
+
+/// Send a command to the running instance, print its answer and return the
+/// process exit code.
+pub fn run(command: ControlCommand) -> Result<i32> {
+    let response = instance::send(&command)?;
+    match (response.ok, response.message) {
+        (true, Some(message)) => println!("{}", message),
+        (true, None) => {}
+        (false, message) => {
+            eprintln!("Error: {}", message.unwrap_or_else(|| "Command failed".to_string()));
+            return Ok(1);
+        }
+    }
+    Ok(0)
+}
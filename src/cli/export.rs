@@ -0,0 +1,86 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+
+use crate::export::{self, Dataset, ExportFormat};
+use crate::monitor::UsageHistory;
+use crate::utils::logging;
+use crate::utils::preferences::Preferences;
+
+/// Days exported when no `--from` is given.
+pub const DEFAULT_EXPORT_DAYS: i64 = 30;
+
+/// One dataset, or every dataset into its own file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    Dataset(Dataset),
+    All,
+}
+
+impl FromStr for ExportTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "all" => Ok(ExportTarget::All),
+            _ => s.parse().map(ExportTarget::Dataset),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub target: ExportTarget,
+    pub format: ExportFormat,
+    /// First day, `DEFAULT_EXPORT_DAYS` before `to` when unset
+    pub from: Option<NaiveDate>,
+    /// Last day, today when unset
+    pub to: Option<NaiveDate>,
+    /// File for a single dataset (stdout when unset), or folder for all of
+    /// them (the export folder of the preferences when unset)
+    pub output: Option<PathBuf>,
+}
+
+/// Export the stored history and return the process exit code.
+pub fn run(options: ExportOptions) -> Result<i32> {
+    // Keep stdout clean for the export itself
+    logging::disable();
+
+    let to = options.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = options.from.unwrap_or(to - chrono::Duration::days(DEFAULT_EXPORT_DAYS - 1));
+    if from > to {
+        anyhow::bail!("--from is after --to");
+    }
+
+    match options.target {
+        ExportTarget::Dataset(dataset) => {
+            let history = UsageHistory::load()?;
+            match &options.output {
+                Some(path) => {
+                    let file = File::create(path)
+                        .with_context(|| format!("Failed to create {}", path.display()))?;
+                    let mut out = BufWriter::new(file);
+                    let rows = export::write(dataset, options.format, &history, from, to, &mut out)?;
+                    out.flush()?;
+                    eprintln!("Exported {} rows to {}", rows, path.display());
+                }
+                None => {
+                    let mut out = std::io::stdout().lock();
+                    export::write(dataset, options.format, &history, from, to, &mut out)?;
+                    out.flush()?;
+                }
+            }
+        }
+        ExportTarget::All => {
+            let directory = options.output
+                .unwrap_or_else(|| Preferences::load().unwrap_or_default().export.directory());
+            for (path, rows) in export::export_all(&directory, options.format, from, to)? {
+                eprintln!("Exported {} rows to {}", rows, path.display());
+            }
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+
+use crate::import::{Import, ImportSummary};
+use crate::monitor::UsageHistory;
+use crate::utils::logging;
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// ccusage `daily --json` / `blocks --json` dumps and export files
+    pub paths: Vec<PathBuf>,
+    /// Only print what would be imported
+    pub dry_run: bool,
+}
+
+fn print_summary(summary: &ImportSummary) {
+    let range = match (summary.first_day, summary.last_day) {
+        (Some(first), Some(last)) => format!(", {} to {}", first, last),
+        _ => String::new(),
+    };
+    println!(
+        "{} days ({} new, {} duplicates), {} blocks ({} new, {} duplicates){}, ${:.2}",
+        summary.days,
+        summary.new_days,
+        summary.duplicate_days(),
+        summary.blocks,
+        summary.new_blocks,
+        summary.duplicate_blocks(),
+        range,
+        summary.cost,
+    );
+}
+
+/// Import the files into the usage history and return the process exit code.
+/// A running instance imports them itself, so its next history refresh
+/// keeps them.
+pub fn run(options: ImportOptions) -> Result<i32> {
+    logging::disable();
+
+    // A running instance resolves paths from its own working directory
+    let paths = options.paths.iter()
+        .map(|path| path.canonicalize().with_context(|| format!("Failed to read {}", path.display())))
+        .collect::<Result<Vec<_>>>()?;
+    let import = Import::read(&paths)?;
+    for file in &import.files {
+        println!("{}: {}, {} days, {} blocks", file.path.display(), file.source.name(), file.days, file.blocks);
+    }
+
+    let mut history = UsageHistory::load()?;
+    let summary = import.preview(&history);
+    print_summary(&summary);
+    if options.dry_run || summary.is_empty() {
+        return Ok(0);
+    }
+
+    #[cfg(unix)]
+    if let Ok(response) = crate::instance::send(&crate::instance::ControlCommand::Import { paths }) {
+        if !response.ok {
+            eprintln!("Error: {}", response.message.unwrap_or_else(|| "Import failed".to_string()));
+            return Ok(1);
+        }
+        println!("Imported by the running instance");
+        return Ok(0);
+    }
+
+    import.apply(&mut history);
+    history.save()?;
+    println!("Imported");
+    Ok(0)
+}
@@ -0,0 +1,11 @@
+#[cfg(unix)]
+pub mod control;
+pub mod snapshot;
+pub mod export;
+pub mod import;
+pub mod report;
+
+pub use snapshot::{SnapshotFormat, SnapshotOptions};
+pub use export::{ExportOptions, ExportTarget};
+pub use import::ImportOptions;
+pub use report::ReportOptions;
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+
+use crate::report::{self, ReportFormat};
+use crate::utils::logging;
+use crate::utils::preferences::Preferences;
+
+/// Days reported when no `--from` is given, one two-week sprint.
+pub const DEFAULT_REPORT_DAYS: i64 = 14;
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    /// The format of the preferences when unset
+    pub format: Option<ReportFormat>,
+    /// First day, `DEFAULT_REPORT_DAYS` before `to` when unset
+    pub from: Option<NaiveDate>,
+    /// Last day, today when unset
+    pub to: Option<NaiveDate>,
+    /// Report file, in the report folder of the preferences when unset
+    pub output: Option<PathBuf>,
+}
+
+/// Write a report of the stored history, print its path and return the
+/// process exit code.
+pub fn run(options: ReportOptions) -> Result<i32> {
+    logging::disable();
+
+    let to = options.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = options.from.unwrap_or(to - chrono::Duration::days(DEFAULT_REPORT_DAYS - 1));
+    if from > to {
+        anyhow::bail!("--from is after --to");
+    }
+
+    let prefs = Preferences::load().unwrap_or_default();
+    let format = options.format.unwrap_or(prefs.report.format);
+    let path = options.output.unwrap_or_else(|| {
+        prefs.report.directory(&prefs.export).join(report::file_name(format, from, to))
+    });
+    report::write(&prefs.budgets, format, from, to, &path)?;
+    println!("{}", path.display());
+    Ok(0)
+}
@@ -0,0 +1,186 @@
+use anyhow::Result;
+use serde_json::json;
+
+use crate::background::collect_snapshot;
+#[cfg(unix)]
+use crate::instance::{self, ControlCommand};
+use crate::monitor::MonitorSnapshot;
+use crate::utils::logging;
+use crate::utils::notifications::{format_number, Severity};
+use crate::utils::preferences::Preferences;
+use crate::i18n;
+
+/// Template used when no `--format` is given.
+pub const DEFAULT_TEMPLATE: &str = "{tokens} {cost} {remaining}";
+
+/// Exit code when the network is down.
+pub const EXIT_OFFLINE: i32 = 2;
+/// Exit code when ccusage could not be read.
+pub const EXIT_NO_DATA: i32 = 3;
+/// Exit code when there is no active block.
+pub const EXIT_NO_ACTIVE_BLOCK: i32 = 4;
+
+/// Plan usage from which the waybar class turns to warning and critical.
+const PLAN_WARNING_PERCENT: f64 = 80.0;
+const PLAN_CRITICAL_PERCENT: f64 = 100.0;
+
+#[derive(Debug, Clone)]
+pub enum SnapshotFormat {
+    /// The full snapshot as JSON
+    Json,
+    /// A single line from a template such as `{tokens} {cost} {remaining}`
+    Template(String),
+    /// waybar's custom module JSON, with the text rendered from a template
+    Waybar(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    pub format: SnapshotFormat,
+    /// Fetch the usage history from ccusage instead of using the stored one.
+    /// Always polls ccusage, even when an instance is running.
+    pub refresh_history: bool,
+}
+
+/// Print one snapshot and return the process exit code.
+pub fn run(options: SnapshotOptions) -> Result<i32> {
+    // Keep stdout clean for the snapshot itself
+    logging::disable();
+
+    let running = if options.refresh_history { None } else { running_snapshot() };
+    let snapshot = match running {
+        Some(snapshot) => snapshot,
+        None => {
+            let prefs = Preferences::load().unwrap_or_default();
+            MonitorSnapshot::new(&collect_snapshot(options.refresh_history), prefs.plan_token_limit)
+        }
+    };
+    let has_data = snapshot.health.ccusage.last_success.is_some();
+
+    let output = match &options.format {
+        SnapshotFormat::Json => serde_json::to_string_pretty(&snapshot)?,
+        SnapshotFormat::Template(template) => render_template(template, &snapshot),
+        SnapshotFormat::Waybar(template) => waybar_json(template, &snapshot, has_data).to_string(),
+    };
+    println!("{}", output);
+
+    Ok(if !snapshot.network.connected {
+        EXIT_OFFLINE
+    } else if !has_data {
+        EXIT_NO_DATA
+    } else if !snapshot.block.active {
+        EXIT_NO_ACTIVE_BLOCK
+    } else {
+        0
+    })
+}
+
+/// The latest data of a running instance, which saves polling ccusage again.
+#[cfg(unix)]
+fn running_snapshot() -> Option<MonitorSnapshot> {
+    instance::send(&ControlCommand::Snapshot).ok().and_then(|response| response.snapshot)
+}
+
+#[cfg(not(unix))]
+fn running_snapshot() -> Option<MonitorSnapshot> {
+    None
+}
+
+fn format_remaining(secs: Option<i64>) -> String {
+    match secs {
+        Some(secs) => format!("{}{}{}{}",
+            secs / 3600, i18n::get(i18n::keys::COMMON_HOUR),
+            secs % 3600 / 60, i18n::get(i18n::keys::COMMON_MINUTE)),
+        None => "--".to_string(),
+    }
+}
+
+fn optional(value: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    value.map(format).unwrap_or_else(|| "--".to_string())
+}
+
+/// Replace the `{placeholder}`s of a template with snapshot values. Unknown
+/// placeholders are left as they are.
+pub fn render_template(template: &str, snapshot: &MonitorSnapshot) -> String {
+    let block = &snapshot.block;
+    let values = [
+        ("tokens", format_number(block.tokens)),
+        ("tokens_raw", block.tokens.to_string()),
+        ("cost", format!("${:.2}", block.cost)),
+        ("cost_raw", format!("{:.4}", block.cost)),
+        ("remaining", format_remaining(block.remaining_secs)),
+        ("remaining_secs", block.remaining_secs.map_or_else(|| "--".to_string(), |secs| secs.to_string())),
+        ("reset", block.reset_time.map_or_else(|| "--".to_string(), |time| time.format("%H:%M").to_string())),
+        ("status", block.status.clone()),
+        ("model", block.model.clone()),
+        ("burn_rate", optional(block.burn_rate_tokens_per_min, |rate| format!("{}/min", format_number(rate as i64)))),
+        ("cost_rate", optional(block.burn_rate_cost_per_hour, |rate| format!("${:.2}/h", rate))),
+        ("plan_percent", optional(block.plan_percent, |percent| format!("{:.0}%", percent))),
+        ("today_cost", optional(snapshot.today_cost, |cost| format!("${:.2}", cost))),
+        ("network", if snapshot.network.connected { "online" } else { "offline" }.to_string()),
+        ("latency", snapshot.network.latency.clone().unwrap_or_else(|| "--".to_string())),
+    ];
+
+    let mut output = template.to_string();
+    for (name, value) in values {
+        output = output.replace(&format!("{{{}}}", name), &value);
+    }
+    output
+}
+
+/// waybar's custom module protocol: `text`, `tooltip`, `class`, `alt` and `percentage`.
+fn waybar_json(template: &str, snapshot: &MonitorSnapshot, has_data: bool) -> serde_json::Value {
+    let block = &snapshot.block;
+    let state = if !snapshot.network.connected {
+        "offline"
+    } else if !has_data {
+        "no-data"
+    } else if block.active {
+        "active"
+    } else {
+        "idle"
+    };
+
+    let mut classes = vec![state];
+    let budget_level = snapshot.budgets.iter().filter_map(|budget| budget.status.level()).max();
+    let plan_percent = block.plan_percent.unwrap_or(0.0);
+    if budget_level == Some(Severity::Critical) || plan_percent >= PLAN_CRITICAL_PERCENT {
+        classes.push("critical");
+    } else if budget_level.is_some() || plan_percent >= PLAN_WARNING_PERCENT {
+        classes.push("warning");
+    }
+
+    let mut tooltip = vec![
+        format!("{}: {}", i18n::get(i18n::keys::USAGE_MODEL), block.model),
+        format!("{}: {}", i18n::get(i18n::keys::USAGE_TOKENS), format_number(block.tokens)),
+        format!("{}: ${:.2}", i18n::get(i18n::keys::USAGE_COST), block.cost),
+        format!("{}: {}", i18n::get(i18n::keys::USAGE_REMAINING), format_remaining(block.remaining_secs)),
+    ];
+    if let Some(rate) = block.burn_rate_tokens_per_min {
+        tooltip.push(format!("{}: {}/min", i18n::get(i18n::keys::USAGE_BURN_RATE), format_number(rate as i64)));
+    }
+    if let Some(cost) = snapshot.today_cost {
+        tooltip.push(format!("{}: ${:.2}", i18n::get(i18n::keys::USAGE_TODAY_COST), cost));
+    }
+    for budget in &snapshot.budgets {
+        tooltip.push(i18n::format(i18n::keys::BUDGET_PROGRESS, &[
+            &budget.status.period.label(),
+            &format!("{:.2}", budget.status.spent),
+            &format!("{:.2}", budget.status.limit),
+            &format!("{:.0}", budget.percent),
+        ]));
+    }
+    tooltip.push(format!("{}: {}", i18n::get(i18n::keys::NETWORK_STATUS), if snapshot.network.connected {
+        i18n::get(i18n::keys::NETWORK_CONNECTED)
+    } else {
+        i18n::get(i18n::keys::NETWORK_DISCONNECTED)
+    }));
+
+    json!({
+        "text": render_template(template, snapshot),
+        "tooltip": tooltip.join("\n"),
+        "class": classes,
+        "alt": state,
+        "percentage": block.plan_percent.map(|percent| percent.round().clamp(0.0, 100.0) as u8),
+    })
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use anyhow::Result;
+
+use crate::background::{start_monitor_tokio, stop_monitor_worker, submit_monitor_request, tokio_runtime, MonitorRequest};
+use crate::ui_updates::{dequeue_monitor_updates, MonitorUpdate};
+use crate::utils::logging;
+use crate::utils::notifications;
+use crate::monitor_log as log;
+
+/// How often queued monitor updates are drained.
+const UPDATE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the worker may take to finish its current update on shutdown.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Options of the headless run mode.
+#[derive(Debug, Clone, Default)]
+pub struct DaemonOptions {
+    /// Append log lines to this file instead of stdout
+    pub log_file: Option<PathBuf>,
+}
+
+/// Run the background worker, alerting and notification sinks without the
+/// Makepad UI or the tray, until SIGTERM or Ctrl+C is received.
+pub fn run(options: DaemonOptions) -> Result<()> {
+    if let Some(path) = &options.log_file {
+        logging::log_to_file(path)?;
+    }
+    // There is usually no desktop session; the other sinks and the log still
+    // receive every notification
+    notifications::set_desktop_enabled(false);
+
+    start_monitor_tokio()?;
+    submit_monitor_request(MonitorRequest::ForceUpdate)?;
+    log!("cc-monitor-rs {} running headless", env!("CARGO_PKG_VERSION"));
+
+    tokio_runtime().block_on(async {
+        let mut poll = tokio::time::interval(UPDATE_POLL_INTERVAL);
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = poll.tick() => {
+                    if handle_updates() {
+                        log!("Quit requested, shutting down");
+                        break;
+                    }
+                }
+                signal = &mut shutdown => {
+                    log!("Received {}, shutting down", signal?);
+                    break;
+                }
+            }
+        }
+
+        if let Err(e) = stop_monitor_worker(SHUTDOWN_TIMEOUT).await {
+            log!("Failed to stop monitor worker: {}", e);
+        }
+        // Give webhooks a last chance to deliver queued notifications
+        let _ = tokio::task::spawn_blocking(notifications::flush_sinks).await;
+        let _ = handle_updates();
+        log!("Stopped");
+        Ok::<_, anyhow::Error>(())
+    })
+}
+
+/// Log the updates the UI would otherwise display. Data updates are already
+/// logged by the worker. Returns whether the worker was shut down, e.g. by
+/// `cc-monitor-rs quit`.
+fn handle_updates() -> bool {
+    let mut stopped = false;
+    for update in dequeue_monitor_updates() {
+        match update {
+            MonitorUpdate::DataUpdate(_) => {}
+            MonitorUpdate::StatusMessage(message) => log!("{}", message),
+            MonitorUpdate::ErrorMessage(message) => log!("Error: {}", message),
+            MonitorUpdate::RequestAck(ack) => {
+                stopped |= ack.request == "Shutdown";
+                if !ack.success {
+                    log!("{} failed: {}", ack.request, ack.message.unwrap_or_default());
+                }
+            }
+            MonitorUpdate::NotificationRecorded(record) => {
+                log!("Notification [{:?}]{} {}: {}",
+                    record.event.severity,
+                    if record.delivered { "" } else { " (held back)" },
+                    record.event.title,
+                    record.event.body.replace('\n', " | "));
+            }
+        }
+    }
+    stopped
+}
+
+/// Resolves with the name of the signal that asked the daemon to stop.
+#[cfg(unix)]
+async fn shutdown_signal() -> Result<&'static str> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        _ = terminate.recv() => Ok("SIGTERM"),
+        result = tokio::signal::ctrl_c() => result.map(|_| "SIGINT").map_err(Into::into),
+    }
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() -> Result<&'static str> {
+    tokio::signal::ctrl_c().await?;
+    Ok("Ctrl+C")
+}
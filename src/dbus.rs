@@ -0,0 +1,399 @@
+use std::time::Duration;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast::error::RecvError;
+use zbus::object_server::{InterfaceRef, SignalEmitter};
+use zbus::{connection, fdo, interface};
+
+use crate::background::{submit_monitor_request_and_wait, MonitorRequest};
+use crate::monitor::{MonitorData, MonitorSnapshot};
+use crate::ui_updates::{latest_monitor_data, subscribe_monitor_updates, MonitorUpdate};
+use crate::utils::notifications::NotificationRecord;
+use crate::monitor_log as log;
+
+pub const BUS_NAME: &str = "io.github.zhanghandong.CcMonitor";
+pub const OBJECT_PATH: &str = "/io/github/zhanghandong/CcMonitor";
+/// How long a method call waits for the worker; a refresh runs ccusage.
+const ACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The values behind the D-Bus properties. D-Bus has no optional values, so
+/// unknown numbers are -1 (0 for timestamps).
+#[derive(Debug, Clone, PartialEq)]
+struct UsageProperties {
+    active: bool,
+    status: String,
+    model: String,
+    tokens: i64,
+    cost: f64,
+    reset_time: i64,
+    plan_percent: f64,
+    today_cost: f64,
+    network_connected: bool,
+    latency: String,
+}
+
+impl Default for UsageProperties {
+    fn default() -> Self {
+        Self {
+            active: false,
+            status: String::new(),
+            model: String::new(),
+            tokens: 0,
+            cost: 0.0,
+            reset_time: 0,
+            plan_percent: -1.0,
+            today_cost: -1.0,
+            network_connected: false,
+            latency: String::new(),
+        }
+    }
+}
+
+impl UsageProperties {
+    fn new(snapshot: &MonitorSnapshot) -> Self {
+        let block = &snapshot.block;
+        Self {
+            active: block.active,
+            status: block.status.clone(),
+            model: block.model.clone(),
+            tokens: block.tokens,
+            cost: block.cost,
+            reset_time: block.reset_time.map_or(0, |time| time.timestamp()),
+            plan_percent: block.plan_percent.unwrap_or(-1.0),
+            today_cost: snapshot.today_cost.unwrap_or(-1.0),
+            network_connected: snapshot.network.connected,
+            latency: snapshot.network.latency.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// The `io.github.zhanghandong.CcMonitor` interface, kept in sync with the
+/// monitor updates by `run`.
+#[derive(Default)]
+struct MonitorService {
+    properties: UsageProperties,
+    paused: bool,
+    /// Reset time of the last active block, to signal when it resets
+    block_reset: Option<DateTime<Local>>,
+}
+
+/// Submit a request for a method call, failing the call when the worker does.
+async fn call(request: MonitorRequest) -> fdo::Result<()> {
+    let ack = submit_monitor_request_and_wait(request, ACK_TIMEOUT)
+        .await
+        .map_err(|e| fdo::Error::Failed(e.to_string()))?;
+    if ack.success {
+        Ok(())
+    } else {
+        Err(fdo::Error::Failed(ack.message.unwrap_or_default()))
+    }
+}
+
+#[interface(name = "io.github.zhanghandong.CcMonitor")]
+impl MonitorService {
+    /// Update now
+    async fn refresh(&self) -> fdo::Result<()> {
+        call(MonitorRequest::ForceUpdate).await
+    }
+
+    /// Pause polling; 0 pauses until `Resume`
+    async fn pause(&self, duration_secs: u64) -> fdo::Result<()> {
+        call(MonitorRequest::Pause { duration_secs: (duration_secs > 0).then_some(duration_secs) }).await
+    }
+
+    async fn resume(&self) -> fdo::Result<()> {
+        call(MonitorRequest::Resume).await
+    }
+
+    async fn refresh_history(&self) -> fdo::Result<()> {
+        call(MonitorRequest::RefreshHistory).await
+    }
+
+    async fn switch_profile(&self, name: String) -> fdo::Result<()> {
+        call(MonitorRequest::SwitchProfile { name }).await
+    }
+
+    /// Bring the desktop window to the front
+    async fn show(&self) -> fdo::Result<()> {
+        call(MonitorRequest::Show).await
+    }
+
+    async fn quit(&self) -> fdo::Result<()> {
+        call(MonitorRequest::Shutdown).await
+    }
+
+    /// The latest data in the JSON format of `cc-monitor-rs snapshot --json`
+    fn get_snapshot(&self) -> fdo::Result<String> {
+        let data = latest_monitor_data().ok_or_else(|| fdo::Error::Failed("No data yet".to_string()))?;
+        serde_json::to_string(&snapshot(&data)).map_err(|e| fdo::Error::Failed(e.to_string()))
+    }
+
+    #[zbus(property)]
+    fn active(&self) -> bool {
+        self.properties.active
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> String {
+        self.properties.status.clone()
+    }
+
+    #[zbus(property)]
+    fn model(&self) -> String {
+        self.properties.model.clone()
+    }
+
+    #[zbus(property)]
+    fn tokens(&self) -> i64 {
+        self.properties.tokens
+    }
+
+    /// USD
+    #[zbus(property)]
+    fn cost(&self) -> f64 {
+        self.properties.cost
+    }
+
+    /// Unix time at which the block resets; clients count down from it
+    #[zbus(property)]
+    fn reset_time(&self) -> i64 {
+        self.properties.reset_time
+    }
+
+    #[zbus(property)]
+    fn plan_percent(&self) -> f64 {
+        self.properties.plan_percent
+    }
+
+    #[zbus(property)]
+    fn today_cost(&self) -> f64 {
+        self.properties.today_cost
+    }
+
+    #[zbus(property)]
+    fn network_connected(&self) -> bool {
+        self.properties.network_connected
+    }
+
+    #[zbus(property)]
+    fn latency(&self) -> String {
+        self.properties.latency.clone()
+    }
+
+    #[zbus(property)]
+    fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// A notification was emitted, or held back during quiet hours
+    #[zbus(signal)]
+    async fn alert(emitter: &SignalEmitter<'_>, kind: &str, severity: &str, title: &str, body: &str) -> zbus::Result<()>;
+
+    /// The active block reached its reset time
+    #[zbus(signal)]
+    async fn block_reset(emitter: &SignalEmitter<'_>, reset_time: i64) -> zbus::Result<()>;
+}
+
+fn snapshot(data: &MonitorData) -> MonitorSnapshot {
+    MonitorSnapshot::new(data, data.plan_token_limit)
+}
+
+/// The lowercase name serde gives an enum value, e.g. `block_reset`.
+fn variant_name(value: impl serde::Serialize) -> String {
+    serde_json::to_value(value).ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Update the properties from new data, emitting `PropertiesChanged` for the
+/// ones that changed and `BlockReset` when the active block has reset.
+async fn publish_data(service: &InterfaceRef<MonitorService>, data: &MonitorData) -> zbus::Result<()> {
+    let snapshot = snapshot(data);
+    let emitter = service.signal_emitter();
+    let mut service = service.get_mut().await;
+
+    let old = std::mem::replace(&mut service.properties, UsageProperties::new(&snapshot));
+    let new = service.properties.clone();
+    if old.active != new.active { service.active_changed(emitter).await?; }
+    if old.status != new.status { service.status_changed(emitter).await?; }
+    if old.model != new.model { service.model_changed(emitter).await?; }
+    if old.tokens != new.tokens { service.tokens_changed(emitter).await?; }
+    if old.cost != new.cost { service.cost_changed(emitter).await?; }
+    if old.reset_time != new.reset_time { service.reset_time_changed(emitter).await?; }
+    if old.plan_percent != new.plan_percent { service.plan_percent_changed(emitter).await?; }
+    if old.today_cost != new.today_cost { service.today_cost_changed(emitter).await?; }
+    if old.network_connected != new.network_connected { service.network_connected_changed(emitter).await?; }
+    if old.latency != new.latency { service.latency_changed(emitter).await?; }
+
+    let current = snapshot.block.reset_time.filter(|_| snapshot.block.active);
+    match service.block_reset {
+        Some(previous) if current != Some(previous) && Local::now() >= previous => {
+            MonitorService::block_reset(emitter, previous.timestamp()).await?;
+            service.block_reset = current;
+        }
+        _ if current.is_some() => service.block_reset = current,
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn publish_alert(service: &InterfaceRef<MonitorService>, record: &NotificationRecord) -> zbus::Result<()> {
+    let event = &record.event;
+    MonitorService::alert(
+        service.signal_emitter(),
+        &variant_name(event.kind),
+        &variant_name(event.severity),
+        &event.title,
+        &event.body,
+    ).await
+}
+
+async fn set_paused(service: &InterfaceRef<MonitorService>, paused: bool) -> zbus::Result<()> {
+    let mut interface = service.get_mut().await;
+    if interface.paused != paused {
+        interface.paused = paused;
+        interface.paused_changed(service.signal_emitter()).await?;
+    }
+    Ok(())
+}
+
+/// Own the bus name and mirror every monitor update until the worker stops.
+async fn serve() -> Result<()> {
+    let connection = connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, MonitorService::default())?
+        .build()
+        .await?;
+    let service = connection.object_server().interface::<_, MonitorService>(OBJECT_PATH).await?;
+    log!("Serving {} on the session bus", BUS_NAME);
+
+    let mut updates = subscribe_monitor_updates();
+    if let Some(data) = latest_monitor_data() {
+        publish_data(&service, &data).await?;
+    }
+    loop {
+        let result = match updates.recv().await {
+            Ok(MonitorUpdate::DataUpdate(data)) => publish_data(&service, &data).await,
+            Ok(MonitorUpdate::NotificationRecorded(record)) => publish_alert(&service, &record).await,
+            Ok(MonitorUpdate::RequestAck(ack)) => match (ack.request, ack.success) {
+                ("Pause", true) => set_paused(&service, true).await,
+                ("Resume", true) => set_paused(&service, false).await,
+                _ => Ok(()),
+            },
+            Ok(_) | Err(RecvError::Lagged(_)) => Ok(()),
+            Err(RecvError::Closed) => break,
+        };
+        if let Err(e) = result {
+            log!("Failed to emit D-Bus signal: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Start the D-Bus service on the background runtime. Without a session bus
+/// the monitor keeps running and only logs why.
+pub fn spawn(runtime: &Runtime) {
+    runtime.spawn(async {
+        if let Err(e) = serve().await {
+            log!("D-Bus service stopped: {:#}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::io::{BufRead, BufReader};
+    use std::pin::Pin;
+    use std::process::{Child, Command, Stdio};
+    use zbus::export::futures_core::Stream;
+    use zbus::names::InterfaceName;
+    use zbus::zvariant::OwnedValue;
+
+    const INTERFACE: &str = "io.github.zhanghandong.CcMonitor";
+
+    /// A private bus, stopped when dropped.
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Bus {
+        /// `None` when dbus-daemon is not installed.
+        fn start() -> Option<Self> {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take()?).read_line(&mut address).ok()?;
+            Some(Self { daemon, address: address.trim().to_string() })
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        let next = std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx));
+        tokio::time::timeout(Duration::from_secs(5), next).await.ok()?
+    }
+
+    fn data(tokens: i64) -> MonitorData {
+        let mut data = MonitorData { plan_token_limit: Some(1000), ..MonitorData::default() };
+        data.ccusage_data.status = "ACTIVE".to_string();
+        data.ccusage_data.tokens_num = tokens;
+        data.ccusage_data.reset_time = Some(Local::now() + chrono::Duration::hours(1));
+        data
+    }
+
+    #[tokio::test]
+    async fn publishes_properties_on_a_private_bus() -> Result<()> {
+        let Some(bus) = Bus::start() else {
+            eprintln!("dbus-daemon not available, skipping");
+            return Ok(());
+        };
+        let server = connection::Builder::address(bus.address.as_str())?
+            .name(BUS_NAME)?
+            .serve_at(OBJECT_PATH, MonitorService::default())?
+            .build()
+            .await?;
+        let service = server.object_server().interface::<_, MonitorService>(OBJECT_PATH).await?;
+        let client = connection::Builder::address(bus.address.as_str())?.build().await?;
+        let properties = fdo::PropertiesProxy::builder(&client)
+            .destination(BUS_NAME)?
+            .path(OBJECT_PATH)?
+            .build()
+            .await?;
+        let interface = InterfaceName::try_from(INTERFACE)?;
+        let get = async |name: &str| -> Result<OwnedValue> { Ok(properties.get(interface.clone(), name).await?) };
+
+        let first = data(250);
+        publish_data(&service, &first).await?;
+        assert_eq!(i64::try_from(get("Tokens").await?)?, 250);
+        assert_eq!(f64::try_from(get("PlanPercent").await?)?, 25.0);
+        let reset_time = first.ccusage_data.reset_time.map(|time| time.timestamp());
+        assert_eq!(Some(i64::try_from(get("ResetTime").await?)?), reset_time);
+
+        // Unchanged data emits nothing, so the first signal is the token change
+        let mut changes = properties.receive_properties_changed().await?;
+        publish_data(&service, &first).await?;
+        let mut second = first.clone();
+        second.ccusage_data.tokens_num = 300;
+        publish_data(&service, &second).await?;
+
+        let signal = next(&mut changes).await.expect("no PropertiesChanged signal");
+        let args = signal.args()?;
+        let changed: HashSet<_> = args.changed_properties().keys().copied().collect();
+        assert_eq!(changed, HashSet::from(["Tokens"]));
+        Ok(())
+    }
+}
@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::history::{BlockUsage, TokenCounts};
+use crate::monitor::{LatencyLog, LatencySample, UsageHistory};
+
+/// Where exports from the window go and imports come from, stored in the preferences.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportSettings {
+    /// Folder the export files are written to; the downloads folder when unset
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// Folder the window imports files from; `import` in the export folder when unset
+    #[serde(default)]
+    pub import_directory: Option<PathBuf>,
+}
+
+impl ExportSettings {
+    pub fn directory(&self) -> PathBuf {
+        self.directory.clone().unwrap_or_else(|| {
+            dirs::download_dir()
+                .or_else(dirs::home_dir)
+                .unwrap_or_default()
+                .join("cc-monitor-rs")
+        })
+    }
+
+    pub fn import_directory(&self) -> PathBuf {
+        self.import_directory.clone().unwrap_or_else(|| self.directory().join("import"))
+    }
+}
+
+/// A table that can be exported. The names are part of the file names and
+/// the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dataset {
+    Blocks,
+    Daily,
+    Weekly,
+    Monthly,
+    Models,
+    Projects,
+    Latency,
+}
+
+impl Dataset {
+    pub const ALL: [Dataset; 7] = [
+        Dataset::Blocks,
+        Dataset::Daily,
+        Dataset::Weekly,
+        Dataset::Monthly,
+        Dataset::Models,
+        Dataset::Projects,
+        Dataset::Latency,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dataset::Blocks => "blocks",
+            Dataset::Daily => "daily",
+            Dataset::Weekly => "weekly",
+            Dataset::Monthly => "monthly",
+            Dataset::Models => "models",
+            Dataset::Projects => "projects",
+            Dataset::Latency => "latency",
+        }
+    }
+
+    /// The columns of the dataset's CSV header and JSON objects.
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Dataset::Blocks => BlockRow::COLUMNS,
+            Dataset::Daily | Dataset::Weekly | Dataset::Monthly => PeriodRow::COLUMNS,
+            Dataset::Models => ModelRow::COLUMNS,
+            Dataset::Projects => ProjectRow::COLUMNS,
+            Dataset::Latency => LatencySample::COLUMNS,
+        }
+    }
+
+    /// The dataset exported with these columns, in any order. Daily, weekly
+    /// and monthly exports share their columns and are reported as `Daily`.
+    pub fn from_columns<'a>(columns: impl IntoIterator<Item = &'a str>) -> Option<Dataset> {
+        let mut columns: Vec<&str> = columns.into_iter().collect();
+        columns.sort();
+        Dataset::ALL.into_iter().find(|dataset| {
+            let mut expected = dataset.columns().to_vec();
+            expected.sort();
+            expected == columns
+        })
+    }
+}
+
+impl FromStr for Dataset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Dataset::ALL.into_iter()
+            .find(|dataset| dataset.name() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown dataset `{}`", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => anyhow::bail!("Unknown format `{}`, expected `csv` or `json`", s),
+        }
+    }
+}
+
+/// A row type with its columns, in the order the fields serialize. CSV files
+/// always start with this header, even when there are no rows.
+trait Row: Serialize {
+    const COLUMNS: &'static [&'static str];
+}
+
+/// One 5-hour billing block.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockRow {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub actual_end: Option<DateTime<Local>>,
+    pub active: bool,
+    /// Models used in the block, separated by `;`
+    pub models: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub tokens_per_minute: Option<f64>,
+}
+
+impl Row for BlockRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "start", "end", "actual_end", "active", "models",
+        "input_tokens", "output_tokens", "cache_creation_tokens", "cache_read_tokens", "total_tokens",
+        "cost_usd", "tokens_per_minute",
+    ];
+}
+
+impl BlockRow {
+    fn new(block: &BlockUsage) -> Self {
+        Self {
+            start: block.start,
+            end: block.end,
+            actual_end: block.actual_end,
+            active: block.is_active,
+            models: block.models.join(";"),
+            input_tokens: block.tokens.input,
+            output_tokens: block.tokens.output,
+            cache_creation_tokens: block.tokens.cache_creation,
+            cache_read_tokens: block.tokens.cache_read,
+            total_tokens: block.tokens.total(),
+            cost_usd: block.cost,
+            tokens_per_minute: block.tokens_per_minute(),
+        }
+    }
+}
+
+/// Usage of one day, ISO week or month. `start` and `end` are clipped to the
+/// exported range, so a partial week only covers the exported days.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodRow {
+    /// `2024-05-17`, `2024-W20` or `2024-05`
+    pub period: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub blocks: usize,
+    /// Days with any usage
+    pub active_days: usize,
+}
+
+impl PeriodRow {
+    fn add_tokens(&mut self, tokens: &TokenCounts) {
+        self.input_tokens += tokens.input;
+        self.output_tokens += tokens.output;
+        self.cache_creation_tokens += tokens.cache_creation;
+        self.cache_read_tokens += tokens.cache_read;
+        self.total_tokens += tokens.total();
+    }
+}
+
+impl Row for PeriodRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "period", "start", "end",
+        "input_tokens", "output_tokens", "cache_creation_tokens", "cache_read_tokens", "total_tokens",
+        "cost_usd", "blocks", "active_days",
+    ];
+}
+
+/// Spend of one model on one day.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelRow {
+    pub date: NaiveDate,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl Row for ModelRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "date", "model",
+        "input_tokens", "output_tokens", "cache_creation_tokens", "cache_read_tokens", "total_tokens",
+        "cost_usd",
+    ];
+}
+
+/// Spend of one project on one day.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectRow {
+    pub date: NaiveDate,
+    pub project: String,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl Row for ProjectRow {
+    const COLUMNS: &'static [&'static str] = &["date", "project", "total_tokens", "cost_usd"];
+}
+
+impl Row for LatencySample {
+    const COLUMNS: &'static [&'static str] = &["time", "connected", "ping_ms", "connect_ms"];
+}
+
+/// How days are grouped into `PeriodRow`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    Day,
+    Week,
+    Month,
+}
+
+impl Grouping {
+    /// The label and first day of the period containing `date`.
+    fn period(&self, date: NaiveDate) -> (String, NaiveDate) {
+        match self {
+            Grouping::Day => (date.to_string(), date),
+            Grouping::Week => {
+                let week = date.iso_week();
+                let monday = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+                (format!("{}-W{:02}", week.year(), week.week()), monday)
+            }
+            Grouping::Month => (
+                format!("{}-{:02}", date.year(), date.month()),
+                date.with_day(1).unwrap_or(date),
+            ),
+        }
+    }
+}
+
+/// Usage per day, week or month from `from` to `to`. Periods without usage
+/// are included with zeros, so the rows cover the whole range.
+pub fn period_rows(history: &UsageHistory, grouping: Grouping, from: NaiveDate, to: NaiveDate) -> Vec<PeriodRow> {
+    let mut periods: BTreeMap<NaiveDate, PeriodRow> = BTreeMap::new();
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let (label, first) = grouping.period(date);
+        let row = periods.entry(first).or_insert_with(|| PeriodRow {
+            period: label,
+            start: date,
+            end: date,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: 0,
+            cost_usd: 0.0,
+            blocks: 0,
+            active_days: 0,
+        });
+        row.end = date;
+    }
+
+    for day in history.days(from, to) {
+        let (_, first) = grouping.period(day.date);
+        if let Some(row) = periods.get_mut(&first) {
+            row.add_tokens(&day.tokens);
+            row.cost_usd += day.cost;
+            if day.tokens.total() > 0 || day.cost > 0.0 {
+                row.active_days += 1;
+            }
+        }
+    }
+    for block in history.blocks(from, to) {
+        let (_, first) = grouping.period(block.start.date_naive());
+        if let Some(row) = periods.get_mut(&first) {
+            row.blocks += 1;
+        }
+    }
+    periods.into_values().collect()
+}
+
+pub fn block_rows(history: &UsageHistory, from: NaiveDate, to: NaiveDate) -> Vec<BlockRow> {
+    history.blocks(from, to).map(BlockRow::new).collect()
+}
+
+pub fn model_rows(history: &UsageHistory, from: NaiveDate, to: NaiveDate) -> Vec<ModelRow> {
+    history.days(from, to)
+        .flat_map(|day| day.models.into_iter().map(move |model| ModelRow {
+            date: day.date,
+            model: model.model,
+            input_tokens: model.tokens.input,
+            output_tokens: model.tokens.output,
+            cache_creation_tokens: model.tokens.cache_creation,
+            cache_read_tokens: model.tokens.cache_read,
+            total_tokens: model.tokens.total(),
+            cost_usd: model.cost,
+        }))
+        .collect()
+}
+
+pub fn project_rows(history: &UsageHistory, from: NaiveDate, to: NaiveDate) -> Vec<ProjectRow> {
+    history.days(from, to)
+        .flat_map(|day| day.projects.into_iter().map(move |project| ProjectRow {
+            date: day.date,
+            project: project.project,
+            total_tokens: project.tokens,
+            cost_usd: project.cost,
+        }))
+        .collect()
+}
+
+fn write_rows<T: Row>(rows: &[T], format: ExportFormat, out: impl Write) -> Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(out);
+            writer.write_record(T::COLUMNS)?;
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::Json => {
+            let mut out = out;
+            serde_json::to_writer_pretty(&mut out, rows)?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write one dataset for the days from `from` to `to`, returning the number of rows.
+pub fn write(
+    dataset: Dataset,
+    format: ExportFormat,
+    history: &UsageHistory,
+    from: NaiveDate,
+    to: NaiveDate,
+    out: impl Write,
+) -> Result<usize> {
+    match dataset {
+        Dataset::Blocks => {
+            let rows = block_rows(history, from, to);
+            write_rows(&rows, format, out).map(|_| rows.len())
+        }
+        Dataset::Daily | Dataset::Weekly | Dataset::Monthly => {
+            let grouping = match dataset {
+                Dataset::Daily => Grouping::Day,
+                Dataset::Weekly => Grouping::Week,
+                _ => Grouping::Month,
+            };
+            let rows = period_rows(history, grouping, from, to);
+            write_rows(&rows, format, out).map(|_| rows.len())
+        }
+        Dataset::Models => {
+            let rows = model_rows(history, from, to);
+            write_rows(&rows, format, out).map(|_| rows.len())
+        }
+        Dataset::Projects => {
+            let rows = project_rows(history, from, to);
+            write_rows(&rows, format, out).map(|_| rows.len())
+        }
+        Dataset::Latency => {
+            let rows = LatencyLog::samples(from, to)?;
+            write_rows(&rows, format, out).map(|_| rows.len())
+        }
+    }
+}
+
+/// The file name of a dataset export, e.g. `cc-monitor-daily-2024-05-01-2024-05-31.csv`.
+pub fn file_name(dataset: Dataset, format: ExportFormat, from: NaiveDate, to: NaiveDate) -> String {
+    format!("cc-monitor-{}-{}-{}.{}", dataset.name(), from, to, format.extension())
+}
+
+/// Write every dataset to its own file in `directory`, returning the paths
+/// with the number of rows written to each.
+pub fn export_all(directory: &Path, format: ExportFormat, from: NaiveDate, to: NaiveDate) -> Result<Vec<(PathBuf, usize)>> {
+    fs::create_dir_all(directory)?;
+    let history = UsageHistory::load()?;
+    let mut paths = Vec::new();
+    for dataset in Dataset::ALL {
+        let path = directory.join(file_name(dataset, format, from, to));
+        let mut out = BufWriter::new(File::create(&path)?);
+        let rows = write(dataset, format, &history, from, to, &mut out)?;
+        out.flush()?;
+        paths.push((path, rows));
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+    use crate::monitor::history::parse_daily_json;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn history() -> UsageHistory {
+        let mut history = UsageHistory::default();
+        history.merge_days(parse_daily_json(r#"{"daily": [
+            {"date": "2024-12-27", "inputTokens": 10, "outputTokens": 10, "totalCost": 0.1},
+            {"date": "2024-12-31", "inputTokens": 100, "outputTokens": 50, "totalCost": 1.5,
+             "modelBreakdowns": [{"modelName": "sonnet", "inputTokens": 100, "outputTokens": 50, "cost": 1.5}]},
+            {"date": "2025-01-02", "inputTokens": 200, "outputTokens": 100, "totalCost": 3.0}
+        ]}"#).unwrap());
+        let start = Local.with_ymd_and_hms(2024, 12, 31, 10, 0, 0).unwrap();
+        history.merge_blocks(vec![BlockUsage {
+            start,
+            end: start + chrono::Duration::hours(5),
+            actual_end: None,
+            is_active: false,
+            tokens: TokenCounts { input: 100, output: 50, ..TokenCounts::default() },
+            cost: 1.5,
+            models: vec!["sonnet".to_string(), "opus".to_string()],
+            burn_rate_tokens_per_min: None,
+            imported: false,
+        }]);
+        history
+    }
+
+    /// The header serde derives for `rows` and the keys of their JSON objects.
+    fn serialized_columns<T: Row>(rows: &[T]) -> (Vec<String>, Vec<String>) {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.serialize(&rows[0]).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let header = csv.lines().next().unwrap().split(',').map(String::from).collect();
+
+        let json = serde_json::to_value(&rows[0]).unwrap();
+        let mut keys: Vec<String> = json.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        (header, keys)
+    }
+
+    fn assert_columns<T: Row>(rows: &[T]) {
+        let (header, keys) = serialized_columns(rows);
+        assert_eq!(header, T::COLUMNS);
+        let mut columns = T::COLUMNS.to_vec();
+        columns.sort();
+        assert_eq!(keys, columns);
+    }
+
+    #[test]
+    fn columns_match_the_serialized_rows() {
+        let history = history();
+        let (from, to) = (date("2024-12-01"), date("2025-01-31"));
+        assert_columns(&block_rows(&history, from, to));
+        assert_columns(&period_rows(&history, Grouping::Day, from, to));
+        assert_columns(&model_rows(&history, from, to));
+    }
+
+    #[test]
+    fn csv_starts_with_the_columns() {
+        let history = history();
+        let (from, to) = (date("2024-12-31"), date("2024-12-31"));
+        let mut out = Vec::new();
+        assert_eq!(write(Dataset::Blocks, ExportFormat::Csv, &history, from, to, &mut out).unwrap(), 1);
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(csv.lines().next().unwrap(), BlockRow::COLUMNS.join(","));
+        assert!(csv.lines().nth(1).unwrap().contains("sonnet;opus"));
+    }
+
+    #[test]
+    fn weeks_cross_the_year_boundary() {
+        let rows = period_rows(&history(), Grouping::Week, date("2024-12-28"), date("2025-01-06"));
+        let periods: Vec<(&str, NaiveDate, NaiveDate)> = rows.iter()
+            .map(|row| (row.period.as_str(), row.start, row.end))
+            .collect();
+        assert_eq!(periods, vec![
+            ("2024-W52", date("2024-12-28"), date("2024-12-29")),
+            ("2025-W01", date("2024-12-30"), date("2025-01-05")),
+            ("2025-W02", date("2025-01-06"), date("2025-01-06")),
+        ]);
+        assert_eq!(rows[1].total_tokens, 450);
+        assert_eq!(rows[1].active_days, 2);
+        assert_eq!(rows[1].blocks, 1);
+    }
+
+    #[test]
+    fn periods_without_usage_are_zero() {
+        let rows = period_rows(&history(), Grouping::Day, date("2024-12-30"), date("2025-01-01"));
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].period, "2024-12-30");
+        assert_eq!((rows[0].total_tokens, rows[0].cost_usd, rows[0].active_days), (0, 0.0, 0));
+        assert_eq!(rows[1].total_tokens, 150);
+        assert_eq!(rows[2].total_tokens, 0);
+    }
+
+    #[test]
+    fn periods_are_clipped_to_the_range() {
+        let rows = period_rows(&history(), Grouping::Month, date("2024-12-28"), date("2025-01-01"));
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].period.as_str(), rows[0].start, rows[0].end), ("2024-12", date("2024-12-28"), date("2024-12-31")));
+        // 2024-12-27 is in the month but outside the range
+        assert_eq!(rows[0].total_tokens, 150);
+        assert_eq!((rows[1].period.as_str(), rows[1].start, rows[1].end), ("2025-01", date("2025-01-01"), date("2025-01-01")));
+        assert_eq!(rows[1].total_tokens, 0);
+    }
+}
@@ -36,6 +36,9 @@ impl Translations for EnglishTranslations {
             "usage.inactive" => "⏸️  Inactive",
             "usage.expired" => "Expired",
             "usage.reset" => "Reset",
+            "usage.tokens" => "🎫 Tokens",
+            "usage.burn_rate" => "🔥 Burn rate",
+            "usage.today_cost" => "📅 Today's cost",
             
             // Historical statistics
             "history.title" => "📊 Historical Billing Statistics (Token Based)",
@@ -57,12 +60,145 @@ impl Translations for EnglishTranslations {
             "tray.network_disconnected" => "🔴 Network: Disconnected",
             "tray.status_active" => "✅ Status: Active",
             "tray.status_completed" => "⏸️ Status: Completed",
+            "tray.refresh" => "🔄 Refresh Now",
+            "tray.pause" => "⏸️ Pause for 30 Minutes",
+            "tray.resume" => "▶️ Resume Monitoring",
+            "tray.snooze" => "🔕 Snooze Notifications for {0} Minutes",
+            "tray.unsnooze" => "🔔 Unsnooze Notifications",
+            "tray.notifications" => "🔔 Notifications: {0} unread",
             
             // Notifications
             "notification.title" => "Claude Code Monitor",
             "notification.network_restored" => "🎉 Network Connection Restored",
             "notification.network_lost" => "🚨 Network Connection Lost",
             "notification.usage_status" => "Claude Code Usage Status",
+            "notification.alert_title" => "⚠️ Alert: {0}",
+            "notification.block_expiring" => "⏰ Current block resets in {0} min (at {1})",
+            "notification.window_available" => "🟢 A new 5-hour window is available",
+            "notification.budget_warning" => "💸 {0} budget at {1}% (${2} of ${3})",
+            "notification.budget_critical" => "🚨 {0} budget reached: {1}% (${2} of ${3})",
+            "notification.quiet_digest" => "🔕 {0} notifications while you were away",
+            "notification.digest_period" => "🕐 {0} - {1}",
+            "notification.digest_blocks" => "🧱 Blocks: {0}",
+            "notification.digest_peak_burn_rate" => "🔥 Peak burn rate: {0} tokens/min",
+            
+            // Data source health
+            "health.title" => "🩺 Data Sources",
+            "health.source_network" => "🌐 Network probes",
+            "health.source_ccusage" => "🤖 ccusage",
+            "health.source_history" => "📊 History",
+            "health.healthy" => "✅ Healthy",
+            "health.degraded" => "⚠️ Degraded",
+            "health.failing" => "❌ Failing",
+            "health.disabled" => "⛔ Disabled",
+            "health.last_success" => "Last success",
+            "health.last_error" => "Last error",
+            "health.retry_in" => "Retry in",
+            
+            // Alert rules
+            "alert.metric_block_tokens" => "Block tokens",
+            "alert.metric_block_cost" => "Block cost",
+            "alert.metric_plan_percent" => "Plan usage",
+            "alert.metric_burn_rate" => "Burn rate",
+            "alert.metric_remaining_minutes" => "Time remaining",
+            "alert.metric_daily_cost" => "Today's cost",
+            "alert.metric_network" => "Network",
+            "alert.above" => "threshold ≥",
+            "alert.below" => "threshold ≤",
+            
+            // Budgets
+            "budget.daily" => "Daily",
+            "budget.weekly" => "Weekly",
+            "budget.monthly" => "Monthly",
+            "budget.progress" => "💰 {0} budget: ${1} / ${2} ({3}%)",
+            
+            // Notification center
+            "notification_center.title" => "🔔 Notification Center",
+            "notification_center.unread" => "{0} unread",
+            "notification_center.filter_all" => "Show: All",
+            "notification_center.filter_warnings" => "Show: Warnings",
+            "notification_center.filter_critical" => "Show: Critical",
+            "notification_center.mark_read" => "Mark all read",
+            "notification_center.clear" => "Clear",
+            "notification_center.empty" => "No notifications yet",
+            "notification_center.held_back" => "(held back)",
+            
+            // Export
+            "export.title" => "📤 Export",
+            "export.range_last_7_days" => "Last 7 days",
+            "export.range_last_30_days" => "Last 30 days",
+            "export.range_this_month" => "This month",
+            "export.range_last_month" => "Last month",
+            "export.button" => "Export",
+            "export.done" => "Exported {0} files to {1}",
+            "export.failed" => "Export failed: {0}",
+            
+            // Reports
+            "report.title" => "📊 Claude Code Usage Report",
+            "report.period" => "{0} to {1}",
+            "report.generated" => "Generated {0}",
+            "report.summary" => "📋 Summary",
+            "report.active_days" => "📆 Active days",
+            "report.average_cost" => "📊 Average per active day",
+            "report.daily_cost" => "📅 Daily Cost",
+            "report.models" => "🤖 Models",
+            "report.projects" => "📁 Projects",
+            "report.peak_blocks" => "🔥 Peak Blocks",
+            "report.date" => "Date",
+            "report.cost" => "Cost",
+            "report.tokens" => "Tokens",
+            "report.blocks" => "Blocks",
+            "report.model" => "Model",
+            "report.project" => "Project",
+            "report.share" => "Share",
+            "report.start" => "Start",
+            "report.models_used" => "Models",
+            "report.tokens_per_minute" => "Tokens/min",
+            "report.budget" => "Budget",
+            "report.since" => "Since",
+            "report.spent" => "Spent",
+            "report.limit" => "Limit",
+            "report.used" => "Used",
+            "report.button" => "Report",
+            "report.done" => "Report written to {0}",
+            "report.failed" => "Report failed: {0}",
+            
+            // Import
+            "import.title" => "📥 Import",
+            "import.preview_button" => "Preview",
+            "import.button" => "Import",
+            "import.empty" => "Put ccusage daily/blocks --json dumps or exported files into {0}, then preview them",
+            "import.file" => "{0}: {1}, {2} days, {3} blocks",
+            "import.skipped" => "{0}: skipped, {1}",
+            "import.nothing" => "Nothing to import",
+            "import.preview" => "{0} days ({1} new, {2} duplicates) and {3} blocks ({4} new, {5} duplicates), ${6}",
+            "import.done" => "Imported {0} new days and {1} new blocks",
+            "import.failed" => "Import failed: {0}",
+            
+            // Email digests
+            "email.daily_subject" => "📊 Claude Code usage for {0}",
+            "email.weekly_subject" => "📊 Claude Code usage for {0} to {1}",
+            "email.total_cost" => "💰 Total cost",
+            "email.total_tokens" => "📊 Total tokens",
+            "email.blocks" => "🧱 Blocks",
+            "email.peak_burn_rate" => "🔥 Peak burn rate",
+            "email.top_projects" => "📁 Top projects",
+            "email.model_spend" => "🤖 Spend per model",
+            "email.budgets" => "🎯 Budgets",
+            
+            // Terminal UI
+            "tui.daily_cost" => "📊 Daily Cost",
+            "tui.alerts" => "🔔 Recent Alerts",
+            "tui.waiting" => "Waiting for data...",
+            "tui.paused" => "⏸️ Paused",
+            "tui.help" => "q Quit  r Refresh  p Pause  l Language  m Mark read  Tab Focus  ←→↑↓ Select",
+            
+            // Web dashboard
+            "web.live" => "Live",
+            "web.reconnecting" => "Reconnecting...",
+            "web.token_prompt" => "Enter the API token from the preferences",
+            "web.token_invalid" => "Invalid token",
+            "web.connect" => "Connect",
             
             // Common
             "common.unknown" => "Unknown",
@@ -36,6 +36,9 @@ impl Translations for JapaneseTranslations {
             "usage.inactive" => "⏸️  非アクティブ",
             "usage.expired" => "期限切れ",
             "usage.reset" => "リセット",
+            "usage.tokens" => "🎫 トークン",
+            "usage.burn_rate" => "🔥 消費レート",
+            "usage.today_cost" => "📅 本日のコスト",
             
             // Historical statistics
             "history.title" => "📊 履歴請求統計（トークンベース）",
@@ -57,12 +60,145 @@ impl Translations for JapaneseTranslations {
             "tray.network_disconnected" => "🔴 ネットワーク: 未接続",
             "tray.status_active" => "✅ ステータス: アクティブ",
             "tray.status_completed" => "⏸️ ステータス: 完了",
+            "tray.refresh" => "🔄 今すぐ更新",
+            "tray.pause" => "⏸️ 30 分間一時停止",
+            "tray.resume" => "▶️ 監視を再開",
+            "tray.snooze" => "🔕 通知を {0} 分間スヌーズ",
+            "tray.unsnooze" => "🔔 スヌーズを解除",
+            "tray.notifications" => "🔔 通知：未読 {0} 件",
             
             // Notifications
             "notification.title" => "Claude Code モニター",
             "notification.network_restored" => "🎉 ネットワーク接続が復元されました",
             "notification.network_lost" => "🚨 ネットワーク接続が切断されました",
             "notification.usage_status" => "Claude Code 使用状況",
+            "notification.alert_title" => "⚠️ アラート: {0}",
+            "notification.block_expiring" => "⏰ 現在のブロックは {0} 分後にリセットされます（{1}）",
+            "notification.window_available" => "🟢 新しい 5 時間枠が利用可能になりました",
+            "notification.budget_warning" => "💸 {0}の予算が {1}% に達しました（${2} / ${3}）",
+            "notification.budget_critical" => "🚨 {0}の予算上限に到達：{1}%（${2} / ${3}）",
+            "notification.quiet_digest" => "🔕 おやすみモード中の通知 {0} 件",
+            "notification.digest_period" => "🕐 {0} - {1}",
+            "notification.digest_blocks" => "🧱 ブロック数：{0}",
+            "notification.digest_peak_burn_rate" => "🔥 ピーク消費レート：{0} トークン/分",
+            
+            // Data source health
+            "health.title" => "🩺 データソース",
+            "health.source_network" => "🌐 ネットワーク検査",
+            "health.source_ccusage" => "🤖 ccusage",
+            "health.source_history" => "📊 履歴",
+            "health.healthy" => "✅ 正常",
+            "health.degraded" => "⚠️ 低下",
+            "health.failing" => "❌ 失敗中",
+            "health.disabled" => "⛔ 無効",
+            "health.last_success" => "最終成功",
+            "health.last_error" => "最終エラー",
+            "health.retry_in" => "再試行まで",
+            
+            // Alert rules
+            "alert.metric_block_tokens" => "ブロックのトークン",
+            "alert.metric_block_cost" => "ブロックのコスト",
+            "alert.metric_plan_percent" => "プラン使用率",
+            "alert.metric_burn_rate" => "消費レート",
+            "alert.metric_remaining_minutes" => "残り時間",
+            "alert.metric_daily_cost" => "本日のコスト",
+            "alert.metric_network" => "ネットワーク",
+            "alert.above" => "しきい値 ≥",
+            "alert.below" => "しきい値 ≤",
+            
+            // Budgets
+            "budget.daily" => "日次",
+            "budget.weekly" => "週次",
+            "budget.monthly" => "月次",
+            "budget.progress" => "💰 {0}予算: ${1} / ${2} ({3}%)",
+            
+            // Notification center
+            "notification_center.title" => "🔔 通知センター",
+            "notification_center.unread" => "未読 {0} 件",
+            "notification_center.filter_all" => "表示：すべて",
+            "notification_center.filter_warnings" => "表示：警告",
+            "notification_center.filter_critical" => "表示：重大",
+            "notification_center.mark_read" => "すべて既読",
+            "notification_center.clear" => "クリア",
+            "notification_center.empty" => "通知はまだありません",
+            "notification_center.held_back" => "（保留）",
+            
+            // Export
+            "export.title" => "📤 エクスポート",
+            "export.range_last_7_days" => "過去 7 日間",
+            "export.range_last_30_days" => "過去 30 日間",
+            "export.range_this_month" => "今月",
+            "export.range_last_month" => "先月",
+            "export.button" => "エクスポート",
+            "export.done" => "{0} 個のファイルを {1} にエクスポートしました",
+            "export.failed" => "エクスポートに失敗しました：{0}",
+            
+            // Reports
+            "report.title" => "📊 Claude Code 使用状況レポート",
+            "report.period" => "{0} 〜 {1}",
+            "report.generated" => "{0} に作成",
+            "report.summary" => "📋 概要",
+            "report.active_days" => "📆 利用日数",
+            "report.average_cost" => "📊 利用日あたりの平均",
+            "report.daily_cost" => "📅 日別コスト",
+            "report.models" => "🤖 モデル",
+            "report.projects" => "📁 プロジェクト",
+            "report.peak_blocks" => "🔥 ピークブロック",
+            "report.date" => "日付",
+            "report.cost" => "コスト",
+            "report.tokens" => "トークン",
+            "report.blocks" => "ブロック",
+            "report.model" => "モデル",
+            "report.project" => "プロジェクト",
+            "report.share" => "割合",
+            "report.start" => "開始",
+            "report.models_used" => "モデル",
+            "report.tokens_per_minute" => "トークン/分",
+            "report.budget" => "予算",
+            "report.since" => "開始日",
+            "report.spent" => "使用額",
+            "report.limit" => "上限",
+            "report.used" => "使用率",
+            "report.button" => "レポート",
+            "report.done" => "レポートを {0} に保存しました",
+            "report.failed" => "レポートの作成に失敗しました：{0}",
+            
+            // Import
+            "import.title" => "📥 インポート",
+            "import.preview_button" => "プレビュー",
+            "import.button" => "インポート",
+            "import.empty" => "ccusage daily/blocks --json の出力やエクスポートしたファイルを {0} に置いてからプレビューしてください",
+            "import.file" => "{0}：{1}、{2} 日、{3} ブロック",
+            "import.skipped" => "{0}：スキップ、{1}",
+            "import.nothing" => "インポートするデータがありません",
+            "import.preview" => "{0} 日（新規 {1}、重複 {2}）と {3} ブロック（新規 {4}、重複 {5}）、${6}",
+            "import.done" => "新しい {0} 日分と {1} ブロックをインポートしました",
+            "import.failed" => "インポートに失敗しました：{0}",
+            
+            // Email digests
+            "email.daily_subject" => "📊 Claude Code 使用状況：{0}",
+            "email.weekly_subject" => "📊 Claude Code 使用状況：{0}〜{1}",
+            "email.total_cost" => "💰 合計コスト",
+            "email.total_tokens" => "📊 合計トークン",
+            "email.blocks" => "🧱 ブロック数",
+            "email.peak_burn_rate" => "🔥 ピーク消費レート",
+            "email.top_projects" => "📁 主なプロジェクト",
+            "email.model_spend" => "🤖 モデル別コスト",
+            "email.budgets" => "🎯 予算",
+            
+            // Terminal UI
+            "tui.daily_cost" => "📊 日別コスト",
+            "tui.alerts" => "🔔 最近のアラート",
+            "tui.waiting" => "データを待っています...",
+            "tui.paused" => "⏸️ 一時停止中",
+            "tui.help" => "q 終了  r 更新  p 一時停止  l 言語  m 既読にする  Tab 切替  ←→↑↓ 選択",
+            
+            // Web dashboard
+            "web.live" => "ライブ",
+            "web.reconnecting" => "再接続中...",
+            "web.token_prompt" => "設定ファイルの API トークンを入力してください",
+            "web.token_invalid" => "無効なトークン",
+            "web.connect" => "接続",
             
             // Common
             "common.unknown" => "不明",
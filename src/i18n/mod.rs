@@ -157,6 +157,9 @@ pub mod keys {
     pub const USAGE_INACTIVE: &str = "usage.inactive";
     pub const USAGE_EXPIRED: &str = "usage.expired";
     pub const USAGE_RESET: &str = "usage.reset";
+    pub const USAGE_TOKENS: &str = "usage.tokens";
+    pub const USAGE_BURN_RATE: &str = "usage.burn_rate";
+    pub const USAGE_TODAY_COST: &str = "usage.today_cost";
     
     // Historical statistics
     pub const HISTORY_TITLE: &str = "history.title";
@@ -165,6 +168,25 @@ pub mod keys {
     pub const HISTORY_SESSIONS_TOTAL: &str = "history.sessions_total";
     pub const HISTORY_SESSIONS_ACTIVE: &str = "history.sessions_active";
     
+    // Data source health
+    pub const HEALTH_TITLE: &str = "health.title";
+    pub const HEALTH_SOURCE_NETWORK: &str = "health.source_network";
+    pub const HEALTH_SOURCE_CCUSAGE: &str = "health.source_ccusage";
+    pub const HEALTH_SOURCE_HISTORY: &str = "health.source_history";
+    pub const HEALTH_HEALTHY: &str = "health.healthy";
+    pub const HEALTH_DEGRADED: &str = "health.degraded";
+    pub const HEALTH_FAILING: &str = "health.failing";
+    pub const HEALTH_DISABLED: &str = "health.disabled";
+    pub const HEALTH_LAST_SUCCESS: &str = "health.last_success";
+    pub const HEALTH_LAST_ERROR: &str = "health.last_error";
+    pub const HEALTH_RETRY_IN: &str = "health.retry_in";
+    
+    // Budgets
+    pub const BUDGET_DAILY: &str = "budget.daily";
+    pub const BUDGET_WEEKLY: &str = "budget.weekly";
+    pub const BUDGET_MONTHLY: &str = "budget.monthly";
+    pub const BUDGET_PROGRESS: &str = "budget.progress";
+    
     // Tray menu
     pub const TRAY_NETWORK: &str = "tray.network";
     pub const TRAY_USAGE: &str = "tray.usage";
@@ -178,12 +200,126 @@ pub mod keys {
     pub const TRAY_NETWORK_DISCONNECTED: &str = "tray.network_disconnected";
     pub const TRAY_STATUS_ACTIVE: &str = "tray.status_active";
     pub const TRAY_STATUS_COMPLETED: &str = "tray.status_completed";
+    pub const TRAY_REFRESH: &str = "tray.refresh";
+    pub const TRAY_PAUSE: &str = "tray.pause";
+    pub const TRAY_RESUME: &str = "tray.resume";
+    pub const TRAY_SNOOZE: &str = "tray.snooze";
+    pub const TRAY_UNSNOOZE: &str = "tray.unsnooze";
+    pub const TRAY_NOTIFICATIONS: &str = "tray.notifications";
     
     // Notifications
     pub const NOTIF_TITLE: &str = "notification.title";
     pub const NOTIF_NETWORK_RESTORED: &str = "notification.network_restored";
     pub const NOTIF_NETWORK_LOST: &str = "notification.network_lost";
     pub const NOTIF_USAGE_STATUS: &str = "notification.usage_status";
+    pub const NOTIF_ALERT_TITLE: &str = "notification.alert_title";
+    pub const NOTIF_BLOCK_EXPIRING: &str = "notification.block_expiring";
+    pub const NOTIF_WINDOW_AVAILABLE: &str = "notification.window_available";
+    pub const NOTIF_BUDGET_WARNING: &str = "notification.budget_warning";
+    pub const NOTIF_BUDGET_CRITICAL: &str = "notification.budget_critical";
+    pub const NOTIF_QUIET_DIGEST: &str = "notification.quiet_digest";
+    pub const NOTIF_DIGEST_PERIOD: &str = "notification.digest_period";
+    pub const NOTIF_DIGEST_BLOCKS: &str = "notification.digest_blocks";
+    pub const NOTIF_DIGEST_PEAK_BURN_RATE: &str = "notification.digest_peak_burn_rate";
+    
+    // Notification center
+    pub const NOTIF_CENTER_TITLE: &str = "notification_center.title";
+    pub const NOTIF_CENTER_UNREAD: &str = "notification_center.unread";
+    pub const NOTIF_CENTER_FILTER_ALL: &str = "notification_center.filter_all";
+    pub const NOTIF_CENTER_FILTER_WARNINGS: &str = "notification_center.filter_warnings";
+    pub const NOTIF_CENTER_FILTER_CRITICAL: &str = "notification_center.filter_critical";
+    pub const NOTIF_CENTER_MARK_READ: &str = "notification_center.mark_read";
+    pub const NOTIF_CENTER_CLEAR: &str = "notification_center.clear";
+    pub const NOTIF_CENTER_EMPTY: &str = "notification_center.empty";
+    pub const NOTIF_CENTER_HELD_BACK: &str = "notification_center.held_back";
+    
+    // Export
+    pub const EXPORT_TITLE: &str = "export.title";
+    pub const EXPORT_RANGE_LAST_7_DAYS: &str = "export.range_last_7_days";
+    pub const EXPORT_RANGE_LAST_30_DAYS: &str = "export.range_last_30_days";
+    pub const EXPORT_RANGE_THIS_MONTH: &str = "export.range_this_month";
+    pub const EXPORT_RANGE_LAST_MONTH: &str = "export.range_last_month";
+    pub const EXPORT_BUTTON: &str = "export.button";
+    pub const EXPORT_DONE: &str = "export.done";
+    pub const EXPORT_FAILED: &str = "export.failed";
+    
+    // Reports
+    pub const REPORT_TITLE: &str = "report.title";
+    pub const REPORT_PERIOD: &str = "report.period";
+    pub const REPORT_GENERATED: &str = "report.generated";
+    pub const REPORT_SUMMARY: &str = "report.summary";
+    pub const REPORT_ACTIVE_DAYS: &str = "report.active_days";
+    pub const REPORT_AVERAGE_COST: &str = "report.average_cost";
+    pub const REPORT_DAILY_COST: &str = "report.daily_cost";
+    pub const REPORT_MODELS: &str = "report.models";
+    pub const REPORT_PROJECTS: &str = "report.projects";
+    pub const REPORT_PEAK_BLOCKS: &str = "report.peak_blocks";
+    pub const REPORT_DATE: &str = "report.date";
+    pub const REPORT_COST: &str = "report.cost";
+    pub const REPORT_TOKENS: &str = "report.tokens";
+    pub const REPORT_BLOCKS: &str = "report.blocks";
+    pub const REPORT_MODEL: &str = "report.model";
+    pub const REPORT_PROJECT: &str = "report.project";
+    pub const REPORT_SHARE: &str = "report.share";
+    pub const REPORT_START: &str = "report.start";
+    pub const REPORT_MODELS_USED: &str = "report.models_used";
+    pub const REPORT_TOKENS_PER_MINUTE: &str = "report.tokens_per_minute";
+    pub const REPORT_BUDGET: &str = "report.budget";
+    pub const REPORT_SINCE: &str = "report.since";
+    pub const REPORT_SPENT: &str = "report.spent";
+    pub const REPORT_LIMIT: &str = "report.limit";
+    pub const REPORT_USED: &str = "report.used";
+    pub const REPORT_BUTTON: &str = "report.button";
+    pub const REPORT_DONE: &str = "report.done";
+    pub const REPORT_FAILED: &str = "report.failed";
+    
+    // Import
+    pub const IMPORT_TITLE: &str = "import.title";
+    pub const IMPORT_PREVIEW_BUTTON: &str = "import.preview_button";
+    pub const IMPORT_BUTTON: &str = "import.button";
+    pub const IMPORT_EMPTY: &str = "import.empty";
+    pub const IMPORT_FILE: &str = "import.file";
+    pub const IMPORT_SKIPPED: &str = "import.skipped";
+    pub const IMPORT_NOTHING: &str = "import.nothing";
+    pub const IMPORT_PREVIEW: &str = "import.preview";
+    pub const IMPORT_DONE: &str = "import.done";
+    pub const IMPORT_FAILED: &str = "import.failed";
+    
+    // Email digests
+    pub const EMAIL_DAILY_SUBJECT: &str = "email.daily_subject";
+    pub const EMAIL_WEEKLY_SUBJECT: &str = "email.weekly_subject";
+    pub const EMAIL_TOTAL_COST: &str = "email.total_cost";
+    pub const EMAIL_TOTAL_TOKENS: &str = "email.total_tokens";
+    pub const EMAIL_BLOCKS: &str = "email.blocks";
+    pub const EMAIL_PEAK_BURN_RATE: &str = "email.peak_burn_rate";
+    pub const EMAIL_TOP_PROJECTS: &str = "email.top_projects";
+    pub const EMAIL_MODEL_SPEND: &str = "email.model_spend";
+    pub const EMAIL_BUDGETS: &str = "email.budgets";
+    
+    // Alert rules
+    pub const ALERT_METRIC_BLOCK_TOKENS: &str = "alert.metric_block_tokens";
+    pub const ALERT_METRIC_BLOCK_COST: &str = "alert.metric_block_cost";
+    pub const ALERT_METRIC_PLAN_PERCENT: &str = "alert.metric_plan_percent";
+    pub const ALERT_METRIC_BURN_RATE: &str = "alert.metric_burn_rate";
+    pub const ALERT_METRIC_REMAINING_MINUTES: &str = "alert.metric_remaining_minutes";
+    pub const ALERT_METRIC_DAILY_COST: &str = "alert.metric_daily_cost";
+    pub const ALERT_METRIC_NETWORK: &str = "alert.metric_network";
+    pub const ALERT_ABOVE: &str = "alert.above";
+    pub const ALERT_BELOW: &str = "alert.below";
+    
+    // Terminal UI
+    pub const TUI_DAILY_COST: &str = "tui.daily_cost";
+    pub const TUI_ALERTS: &str = "tui.alerts";
+    pub const TUI_WAITING: &str = "tui.waiting";
+    pub const TUI_PAUSED: &str = "tui.paused";
+    pub const TUI_HELP: &str = "tui.help";
+    
+    // Web dashboard
+    pub const WEB_LIVE: &str = "web.live";
+    pub const WEB_RECONNECTING: &str = "web.reconnecting";
+    pub const WEB_TOKEN_PROMPT: &str = "web.token_prompt";
+    pub const WEB_TOKEN_INVALID: &str = "web.token_invalid";
+    pub const WEB_CONNECT: &str = "web.connect";
     
     // Common
     pub const COMMON_UNKNOWN: &str = "common.unknown";
@@ -36,6 +36,9 @@ impl Translations for ChineseTranslations {
             "usage.inactive" => "⏸️  未活动",
             "usage.expired" => "已过期",
             "usage.reset" => "重置",
+            "usage.tokens" => "🎫 Token",
+            "usage.burn_rate" => "🔥 消耗速率",
+            "usage.today_cost" => "📅 今日费用",
             
             // Historical statistics
             "history.title" => "📊 历史账单统计 (基于 Token 计算)",
@@ -57,12 +60,145 @@ impl Translations for ChineseTranslations {
             "tray.network_disconnected" => "🔴 网络: 未连接",
             "tray.status_active" => "✅ 状态: 活跃中",
             "tray.status_completed" => "⏸️ 状态: 已完成",
+            "tray.refresh" => "🔄 立即刷新",
+            "tray.pause" => "⏸️ 暂停 30 分钟",
+            "tray.resume" => "▶️ 恢复监控",
+            "tray.snooze" => "🔕 通知静音 {0} 分钟",
+            "tray.unsnooze" => "🔔 取消通知静音",
+            "tray.notifications" => "🔔 通知：{0} 条未读",
             
             // Notifications
             "notification.title" => "Claude Code 监测器",
             "notification.network_restored" => "🎉 网络连接已恢复",
             "notification.network_lost" => "🚨 网络连接中断",
             "notification.usage_status" => "Claude Code 使用状态",
+            "notification.alert_title" => "⚠️ 提醒: {0}",
+            "notification.block_expiring" => "⏰ 当前区块将在 {0} 分钟后重置（{1}）",
+            "notification.window_available" => "🟢 新的 5 小时窗口已可用",
+            "notification.budget_warning" => "💸 {0}预算已用 {1}%（${2} / ${3}）",
+            "notification.budget_critical" => "🚨 {0}预算已达上限：{1}%（${2} / ${3}）",
+            "notification.quiet_digest" => "🔕 免打扰期间的 {0} 条通知",
+            "notification.digest_period" => "🕐 {0} - {1}",
+            "notification.digest_blocks" => "🧱 区块数：{0}",
+            "notification.digest_peak_burn_rate" => "🔥 峰值消耗速率：{0} Token/分钟",
+            
+            // Data source health
+            "health.title" => "🩺 数据源状态",
+            "health.source_network" => "🌐 网络探测",
+            "health.source_ccusage" => "🤖 ccusage",
+            "health.source_history" => "📊 历史数据",
+            "health.healthy" => "✅ 正常",
+            "health.degraded" => "⚠️ 降级",
+            "health.failing" => "❌ 失败",
+            "health.disabled" => "⛔ 已停用",
+            "health.last_success" => "上次成功",
+            "health.last_error" => "上次错误",
+            "health.retry_in" => "重试倒计时",
+            
+            // Alert rules
+            "alert.metric_block_tokens" => "当前区块 Token",
+            "alert.metric_block_cost" => "当前区块费用",
+            "alert.metric_plan_percent" => "套餐用量",
+            "alert.metric_burn_rate" => "消耗速率",
+            "alert.metric_remaining_minutes" => "剩余时间",
+            "alert.metric_daily_cost" => "今日费用",
+            "alert.metric_network" => "网络",
+            "alert.above" => "阈值 ≥",
+            "alert.below" => "阈值 ≤",
+            
+            // Budgets
+            "budget.daily" => "每日",
+            "budget.weekly" => "每周",
+            "budget.monthly" => "每月",
+            "budget.progress" => "💰 {0}预算: ${1} / ${2} ({3}%)",
+            
+            // Notification center
+            "notification_center.title" => "🔔 通知中心",
+            "notification_center.unread" => "{0} 条未读",
+            "notification_center.filter_all" => "显示：全部",
+            "notification_center.filter_warnings" => "显示：警告",
+            "notification_center.filter_critical" => "显示：严重",
+            "notification_center.mark_read" => "全部已读",
+            "notification_center.clear" => "清空",
+            "notification_center.empty" => "暂无通知",
+            "notification_center.held_back" => "（已暂缓）",
+            
+            // Export
+            "export.title" => "📤 数据导出",
+            "export.range_last_7_days" => "最近 7 天",
+            "export.range_last_30_days" => "最近 30 天",
+            "export.range_this_month" => "本月",
+            "export.range_last_month" => "上月",
+            "export.button" => "导出",
+            "export.done" => "已导出 {0} 个文件到 {1}",
+            "export.failed" => "导出失败：{0}",
+            
+            // Reports
+            "report.title" => "📊 Claude Code 使用报告",
+            "report.period" => "{0} 至 {1}",
+            "report.generated" => "生成于 {0}",
+            "report.summary" => "📋 概览",
+            "report.active_days" => "📆 活跃天数",
+            "report.average_cost" => "📊 活跃日平均花费",
+            "report.daily_cost" => "📅 每日花费",
+            "report.models" => "🤖 模型",
+            "report.projects" => "📁 项目",
+            "report.peak_blocks" => "🔥 高峰区块",
+            "report.date" => "日期",
+            "report.cost" => "花费",
+            "report.tokens" => "Token",
+            "report.blocks" => "区块",
+            "report.model" => "模型",
+            "report.project" => "项目",
+            "report.share" => "占比",
+            "report.start" => "开始时间",
+            "report.models_used" => "模型",
+            "report.tokens_per_minute" => "Token/分钟",
+            "report.budget" => "预算",
+            "report.since" => "起始日",
+            "report.spent" => "已用",
+            "report.limit" => "额度",
+            "report.used" => "使用率",
+            "report.button" => "生成报告",
+            "report.done" => "报告已保存到 {0}",
+            "report.failed" => "生成报告失败：{0}",
+            
+            // Import
+            "import.title" => "📥 数据导入",
+            "import.preview_button" => "预览",
+            "import.button" => "导入",
+            "import.empty" => "将 ccusage daily/blocks --json 的输出或导出的文件放入 {0}，然后预览",
+            "import.file" => "{0}：{1}，{2} 天，{3} 个区块",
+            "import.skipped" => "{0}：已跳过，{1}",
+            "import.nothing" => "没有可导入的数据",
+            "import.preview" => "{0} 天（新增 {1}，重复 {2}）和 {3} 个区块（新增 {4}，重复 {5}），${6}",
+            "import.done" => "已导入 {0} 个新日期和 {1} 个新区块",
+            "import.failed" => "导入失败：{0}",
+            
+            // Email digests
+            "email.daily_subject" => "📊 Claude Code 使用情况：{0}",
+            "email.weekly_subject" => "📊 Claude Code 使用情况：{0} 至 {1}",
+            "email.total_cost" => "💰 总花费",
+            "email.total_tokens" => "📊 总 Token",
+            "email.blocks" => "🧱 区块数",
+            "email.peak_burn_rate" => "🔥 峰值消耗速率",
+            "email.top_projects" => "📁 主要项目",
+            "email.model_spend" => "🤖 各模型花费",
+            "email.budgets" => "🎯 预算",
+            
+            // Terminal UI
+            "tui.daily_cost" => "📊 每日花费",
+            "tui.alerts" => "🔔 最近提醒",
+            "tui.waiting" => "正在等待数据...",
+            "tui.paused" => "⏸️ 已暂停",
+            "tui.help" => "q 退出  r 刷新  p 暂停  l 语言  m 全部已读  Tab 切换面板  ←→↑↓ 选择",
+            
+            // Web dashboard
+            "web.live" => "实时",
+            "web.reconnecting" => "正在重新连接...",
+            "web.token_prompt" => "请输入偏好设置中的 API 令牌",
+            "web.token_invalid" => "令牌无效",
+            "web.connect" => "连接",
             
             // Common
             "common.unknown" => "未知",
@@ -0,0 +1,461 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::export::{BlockRow, Dataset, ModelRow, PeriodRow, ProjectRow};
+use crate::monitor::history::{self, BlockUsage, DayUsage, ModelUsage, ProjectUsage, TokenCounts};
+use crate::monitor::UsageHistory;
+
+/// What an import file was recognized as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    /// `ccusage daily --json`
+    CcusageDaily,
+    /// `ccusage blocks --json`
+    CcusageBlocks,
+    /// A CSV or JSON file written by `cc-monitor-rs export`
+    Export(Dataset),
+}
+
+impl ImportSource {
+    pub fn name(&self) -> String {
+        match self {
+            ImportSource::CcusageDaily => "ccusage daily".to_string(),
+            ImportSource::CcusageBlocks => "ccusage blocks".to_string(),
+            ImportSource::Export(dataset) => format!("{} export", dataset.name()),
+        }
+    }
+}
+
+/// One file read into an `Import`.
+#[derive(Debug, Clone)]
+pub struct ImportFile {
+    pub path: PathBuf,
+    pub source: ImportSource,
+    /// Days the file has usage for
+    pub days: usize,
+    pub blocks: usize,
+}
+
+/// What an import adds to the usage history, or would add for a preview.
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub days: usize,
+    /// Days added to the history; the others were imported before or are
+    /// the usage ccusage reported on this machine
+    pub new_days: usize,
+    pub blocks: usize,
+    /// Blocks not in the history yet; the others were imported before or
+    /// ccusage reported them on this machine
+    pub new_blocks: usize,
+    pub first_day: Option<NaiveDate>,
+    pub last_day: Option<NaiveDate>,
+    pub cost: f64,
+}
+
+impl ImportSummary {
+    pub fn duplicate_days(&self) -> usize {
+        self.days - self.new_days
+    }
+
+    pub fn duplicate_blocks(&self) -> usize {
+        self.blocks - self.new_blocks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days == 0 && self.blocks == 0
+    }
+}
+
+/// Usage read from ccusage dumps and exports, possibly of other machines,
+/// ready to be merged into the local history. Daily, model and project
+/// exports of the same range combine into days with their breakdowns.
+#[derive(Debug, Default)]
+pub struct Import {
+    pub files: Vec<ImportFile>,
+    days: BTreeMap<NaiveDate, DayUsage>,
+    /// Days whose totals came from a daily table rather than the breakdowns
+    totals: HashSet<NaiveDate>,
+    blocks: Vec<BlockUsage>,
+}
+
+impl Import {
+    /// Read all `paths`, failing on the first file that can't be imported.
+    pub fn read(paths: &[PathBuf]) -> Result<Self> {
+        let mut import = Self::default();
+        for path in paths {
+            import.add_file(path)?;
+        }
+        Ok(import)
+    }
+
+    /// Read one file, recognizing ccusage JSON and export CSV or JSON by its content.
+    pub fn add_file(&mut self, path: &Path) -> Result<&ImportFile> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let (source, days, blocks) = self.add_content(&content)
+            .with_context(|| format!("Failed to import {}", path.display()))?;
+        self.files.push(ImportFile {
+            path: path.to_path_buf(),
+            source,
+            days,
+            blocks,
+        });
+        Ok(&self.files[self.files.len() - 1])
+    }
+
+    fn add_content(&mut self, content: &str) -> Result<(ImportSource, usize, usize)> {
+        match serde_json::from_str::<Value>(content) {
+            Ok(Value::Object(object)) if object.contains_key("blocks") => {
+                let blocks = history::parse_blocks_json(content)?;
+                Ok((ImportSource::CcusageBlocks, 0, self.add_blocks(blocks)))
+            }
+            Ok(Value::Object(_)) => {
+                let days = history::parse_daily_json(content)?;
+                let count = days.len();
+                for day in days {
+                    self.totals.insert(day.date);
+                    self.days.insert(day.date, day);
+                }
+                Ok((ImportSource::CcusageDaily, count, 0))
+            }
+            Ok(Value::Array(rows)) => {
+                let Some(Value::Object(first)) = rows.first() else {
+                    anyhow::bail!("Nothing to import");
+                };
+                let dataset = Dataset::from_columns(first.keys().map(String::as_str))
+                    .ok_or_else(|| anyhow::anyhow!("Unrecognized JSON export"))?;
+                self.add_rows(dataset, ExportRows::Json(&rows))
+            }
+            Ok(_) => anyhow::bail!("Unrecognized JSON"),
+            Err(_) => {
+                let mut reader = csv::Reader::from_reader(content.as_bytes());
+                let dataset = Dataset::from_columns(reader.headers()?.iter())
+                    .ok_or_else(|| anyhow::anyhow!("Unrecognized file, expected ccusage JSON or an export"))?;
+                self.add_rows(dataset, ExportRows::Csv(reader))
+            }
+        }
+    }
+
+    fn add_rows(&mut self, dataset: Dataset, rows: ExportRows) -> Result<(ImportSource, usize, usize)> {
+        let source = ImportSource::Export(dataset);
+        match dataset {
+            Dataset::Blocks => {
+                let blocks = rows.read::<BlockRow>()?.into_iter().map(block_usage).collect();
+                Ok((source, 0, self.add_blocks(blocks)))
+            }
+            Dataset::Daily | Dataset::Weekly | Dataset::Monthly => {
+                let periods: Vec<PeriodRow> = rows.read()?;
+                if periods.iter().any(|period| period.start != period.end || period.period != period.start.to_string()) {
+                    anyhow::bail!("Only daily exports can be imported, weekly and monthly totals can't be split into days");
+                }
+                let mut count = 0;
+                // Exports include days without usage, which must not replace stored days
+                for period in periods.into_iter().filter(|period| period.total_tokens > 0 || period.cost_usd > 0.0) {
+                    let day = self.day(period.start);
+                    day.tokens = TokenCounts {
+                        input: period.input_tokens,
+                        output: period.output_tokens,
+                        cache_creation: period.cache_creation_tokens,
+                        cache_read: period.cache_read_tokens,
+                    };
+                    day.cost = period.cost_usd;
+                    self.totals.insert(period.start);
+                    count += 1;
+                }
+                Ok((ImportSource::Export(Dataset::Daily), count, 0))
+            }
+            Dataset::Models => {
+                let models: Vec<ModelRow> = rows.read()?;
+                let dates: HashSet<NaiveDate> = models.iter().map(|model| model.date).collect();
+                for model in models {
+                    let usage = ModelUsage {
+                        model: model.model,
+                        tokens: TokenCounts {
+                            input: model.input_tokens,
+                            output: model.output_tokens,
+                            cache_creation: model.cache_creation_tokens,
+                            cache_read: model.cache_read_tokens,
+                        },
+                        cost: model.cost_usd,
+                    };
+                    let day = self.day(model.date);
+                    match day.models.iter_mut().find(|existing| existing.model == usage.model) {
+                        Some(existing) => *existing = usage,
+                        None => day.models.push(usage),
+                    }
+                }
+                Ok((source, dates.len(), 0))
+            }
+            Dataset::Projects => {
+                let projects: Vec<ProjectRow> = rows.read()?;
+                let dates: HashSet<NaiveDate> = projects.iter().map(|project| project.date).collect();
+                for project in projects {
+                    let usage = ProjectUsage {
+                        project: project.project,
+                        tokens: project.total_tokens,
+                        cost: project.cost_usd,
+                    };
+                    let day = self.day(project.date);
+                    match day.projects.iter_mut().find(|existing| existing.project == usage.project) {
+                        Some(existing) => *existing = usage,
+                        None => day.projects.push(usage),
+                    }
+                }
+                Ok((source, dates.len(), 0))
+            }
+            Dataset::Latency => anyhow::bail!("Latency samples are not part of the usage history"),
+        }
+    }
+
+    fn day(&mut self, date: NaiveDate) -> &mut DayUsage {
+        self.days.entry(date).or_insert_with(|| DayUsage::new(date))
+    }
+
+    /// Imported blocks are never active, they are only a record of past usage.
+    fn add_blocks(&mut self, blocks: Vec<BlockUsage>) -> usize {
+        let count = blocks.len();
+        self.blocks.extend(blocks.into_iter().map(|block| BlockUsage { is_active: false, imported: true, ..block }));
+        count
+    }
+
+    /// The imported days, with the totals of days that only have breakdowns
+    /// summed from their models, or from their projects when there are none.
+    fn days(&self) -> Vec<DayUsage> {
+        self.days.values()
+            .map(|day| {
+                let mut day = day.clone();
+                if !self.totals.contains(&day.date) {
+                    if day.models.is_empty() {
+                        day.cost = day.projects.iter().map(|project| project.cost).sum();
+                    } else {
+                        for model in &day.models {
+                            day.tokens.add(&model.tokens);
+                        }
+                        day.cost = day.models.iter().map(|model| model.cost).sum();
+                    }
+                }
+                day
+            })
+            .collect()
+    }
+
+    /// Merge into `history`: days add to the usage of their dates and blocks
+    /// are kept next to the local ones, skipping what was imported before.
+    pub fn apply(&self, history: &mut UsageHistory) -> ImportSummary {
+        let days = self.days();
+        let summary = ImportSummary {
+            days: days.len(),
+            new_days: 0,
+            blocks: self.blocks.len(),
+            new_blocks: 0,
+            first_day: days.first().map(|day| day.date),
+            last_day: days.last().map(|day| day.date),
+            cost: days.iter().map(|day| day.cost).sum(),
+        };
+        ImportSummary {
+            new_days: history.import_days(days),
+            new_blocks: history.merge_blocks(self.blocks.clone()),
+            ..summary
+        }
+    }
+
+    /// What `apply` would do to `history`, without changing it.
+    pub fn preview(&self, history: &UsageHistory) -> ImportSummary {
+        self.apply(&mut history.clone())
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|file| file.path.clone()).collect()
+    }
+}
+
+/// The rows of an export, before they are read into the row type of its dataset.
+enum ExportRows<'a> {
+    Json(&'a [Value]),
+    Csv(csv::Reader<&'a [u8]>),
+}
+
+impl ExportRows<'_> {
+    fn read<T: DeserializeOwned>(self) -> Result<Vec<T>> {
+        match self {
+            ExportRows::Json(rows) => rows.iter().map(|row| Ok(serde_json::from_value(row.clone())?)).collect(),
+            ExportRows::Csv(mut reader) => reader.deserialize().map(|row| Ok(row?)).collect(),
+        }
+    }
+}
+
+fn block_usage(row: BlockRow) -> BlockUsage {
+    BlockUsage {
+        start: row.start,
+        end: row.end,
+        actual_end: row.actual_end,
+        is_active: false,
+        tokens: TokenCounts {
+            input: row.input_tokens,
+            output: row.output_tokens,
+            cache_creation: row.cache_creation_tokens,
+            cache_read: row.cache_read_tokens,
+        },
+        cost: row.cost_usd,
+        models: row.models.split(';').filter(|model| !model.is_empty()).map(String::from).collect(),
+        burn_rate_tokens_per_min: None,
+        imported: true,
+    }
+}
+
+/// The `.json` and `.csv` files directly in `directory`, sorted by name.
+pub fn files_in(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("json" | "csv")))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAILY_JSON: &str = r#"{"daily": [{
+        "date": "2024-05-15", "inputTokens": 100, "outputTokens": 50, "totalCost": 1.5,
+        "modelBreakdowns": [{"modelName": "sonnet", "inputTokens": 100, "outputTokens": 50, "cost": 1.5}]
+    }]}"#;
+
+    const BLOCKS_JSON: &str = r#"{"blocks": [
+        {"startTime": "2024-05-15T10:00:00Z", "endTime": "2024-05-15T15:00:00Z", "isActive": true,
+         "tokenCounts": {"inputTokens": 80, "outputTokens": 20}, "costUSD": 0.5, "models": ["sonnet"]},
+        {"startTime": "2024-05-15T15:00:00Z", "endTime": "2024-05-15T20:00:00Z", "isGap": true}
+    ]}"#;
+
+    const DAILY_CSV: &str = "\
+period,start,end,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,total_tokens,cost_usd,blocks,active_days
+2024-05-15,2024-05-15,2024-05-15,100,50,0,0,150,1.5,1,1
+2024-05-16,2024-05-16,2024-05-16,0,0,0,0,0,0.0,0,0
+";
+
+    const BLOCKS_EXPORT_JSON: &str = r#"[{
+        "start": "2024-05-15T10:00:00+00:00", "end": "2024-05-15T15:00:00+00:00", "actual_end": null,
+        "active": true, "models": "sonnet;opus", "input_tokens": 80, "output_tokens": 20,
+        "cache_creation_tokens": 0, "cache_read_tokens": 0, "total_tokens": 100,
+        "cost_usd": 0.5, "tokens_per_minute": null
+    }]"#;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    fn read(contents: &[&str]) -> (Import, Vec<ImportSource>) {
+        let mut import = Import::default();
+        let sources = contents.iter()
+            .map(|content| import.add_content(content).unwrap().0)
+            .collect();
+        (import, sources)
+    }
+
+    fn blocks(history: &UsageHistory) -> Vec<&BlockUsage> {
+        history.blocks(NaiveDate::MIN, NaiveDate::MAX).collect()
+    }
+
+    #[test]
+    fn reads_ccusage_daily_json() {
+        let (import, sources) = read(&[DAILY_JSON]);
+        assert_eq!(sources, vec![ImportSource::CcusageDaily]);
+
+        let mut history = UsageHistory::default();
+        let summary = import.apply(&mut history);
+        assert_eq!((summary.days, summary.new_days, summary.blocks), (1, 1, 0));
+        let day = history.days(date(), date()).next().unwrap();
+        assert_eq!(day.tokens.total(), 150);
+        assert_eq!(day.cost, 1.5);
+        assert_eq!(day.models.len(), 1);
+    }
+
+    #[test]
+    fn reads_ccusage_blocks_json_without_gaps() {
+        let (import, sources) = read(&[BLOCKS_JSON]);
+        assert_eq!(sources, vec![ImportSource::CcusageBlocks]);
+
+        let mut history = UsageHistory::default();
+        let summary = import.apply(&mut history);
+        assert_eq!((summary.blocks, summary.new_blocks), (1, 1));
+        let block = blocks(&history)[0];
+        assert!(block.imported);
+        assert!(!block.is_active);
+        assert_eq!(block.tokens.total(), 100);
+    }
+
+    #[test]
+    fn reads_daily_csv_export_without_empty_days() {
+        let (import, sources) = read(&[DAILY_CSV]);
+        assert_eq!(sources, vec![ImportSource::Export(Dataset::Daily)]);
+
+        let mut history = UsageHistory::default();
+        let summary = import.apply(&mut history);
+        assert_eq!((summary.days, summary.new_days), (1, 1));
+        assert_eq!(summary.cost, 1.5);
+    }
+
+    #[test]
+    fn reads_blocks_json_export() {
+        let (import, sources) = read(&[BLOCKS_EXPORT_JSON]);
+        assert_eq!(sources, vec![ImportSource::Export(Dataset::Blocks)]);
+
+        let mut history = UsageHistory::default();
+        import.apply(&mut history);
+        let block = blocks(&history)[0];
+        assert_eq!(block.models, vec!["sonnet", "opus"]);
+        assert!(block.imported);
+        assert!(!block.is_active);
+    }
+
+    #[test]
+    fn importing_again_changes_nothing() {
+        let (import, _) = read(&[DAILY_JSON, BLOCKS_JSON]);
+        let mut history = UsageHistory::default();
+        import.apply(&mut history);
+
+        let summary = import.apply(&mut history);
+        assert_eq!((summary.new_days, summary.duplicate_days()), (0, 1));
+        assert_eq!((summary.new_blocks, summary.duplicate_blocks()), (0, 1));
+        assert_eq!(history.summarize(NaiveDate::MIN, NaiveDate::MAX).cost, 1.5);
+        assert_eq!(blocks(&history).len(), 1);
+    }
+
+    #[test]
+    fn import_survives_history_refresh() {
+        let (import, _) = read(&[DAILY_JSON, BLOCKS_JSON]);
+        let mut history = UsageHistory::default();
+        import.apply(&mut history);
+
+        // What `refresh_history` merges from this machine's ccusage, with a
+        // block of its own in the same hour as the imported one
+        let local_day = DayUsage {
+            tokens: TokenCounts { input: 40, ..TokenCounts::default() },
+            cost: 0.5,
+            ..DayUsage::new(date())
+        };
+        let local_block = BlockUsage {
+            is_active: true,
+            imported: false,
+            tokens: TokenCounts { input: 40, ..TokenCounts::default() },
+            ..blocks(&history)[0].clone()
+        };
+        for _ in 0..2 {
+            history.merge_days(vec![local_day.clone()]);
+            history.merge_blocks(vec![local_block.clone()]);
+        }
+
+        let day = history.days(date(), date()).next().unwrap();
+        assert_eq!(day.tokens.total(), 190);
+        assert_eq!(day.cost, 2.0);
+        assert_eq!(day.models.len(), 1);
+        assert_eq!(blocks(&history).len(), 2);
+    }
+}
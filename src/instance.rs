@@ -0,0 +1,216 @@
+use std::fs::{self, File, Permissions, TryLockError};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener as StdUnixListener, UnixStream as StdUnixStream};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::runtime::Runtime;
+
+use crate::background::{submit_monitor_request_and_wait, MonitorRequest};
+use crate::monitor::MonitorSnapshot;
+use crate::ui_updates::latest_monitor_data;
+use crate::monitor_log as log;
+
+const LOCK_FILE: &str = "instance.lock";
+const SOCKET_FILE: &str = "control.sock";
+/// How long a forwarded request may take; a forced update runs ccusage.
+const ACK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Slightly longer than `ACK_TIMEOUT` so the server can answer with its own error.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(65);
+/// Commands are a single short JSON line.
+const MAX_COMMAND_LENGTH: u64 = 4096;
+
+/// Held for the lifetime of the process once the instance lock is taken.
+static INSTANCE_LOCK: OnceLock<File> = OnceLock::new();
+
+/// A command sent to the running instance over the control socket, one JSON
+/// line such as `{"command":"pause","duration_secs":600}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Force an immediate update
+    Refresh,
+    /// Pause polling, until resumed when no duration is given
+    Pause {
+        #[serde(default)]
+        duration_secs: Option<u64>,
+    },
+    /// Resume polling after a pause
+    Resume,
+    /// Bring the desktop window to the front
+    Show,
+    /// Stop the running instance
+    Quit,
+    /// Return the latest data as a `MonitorSnapshot`
+    Snapshot,
+    /// Merge ccusage dumps and exports into the usage history
+    Import { paths: Vec<PathBuf> },
+}
+
+impl ControlCommand {
+    /// The worker request a command is forwarded as; `None` for commands
+    /// answered by the control socket itself.
+    fn request(&self) -> Option<MonitorRequest> {
+        match self {
+            ControlCommand::Refresh => Some(MonitorRequest::ForceUpdate),
+            ControlCommand::Pause { duration_secs } => Some(MonitorRequest::Pause { duration_secs: *duration_secs }),
+            ControlCommand::Resume => Some(MonitorRequest::Resume),
+            ControlCommand::Show => Some(MonitorRequest::Show),
+            ControlCommand::Quit => Some(MonitorRequest::Shutdown),
+            ControlCommand::Snapshot => None,
+            ControlCommand::Import { paths } => Some(MonitorRequest::ImportHistory { paths: paths.clone() }),
+        }
+    }
+}
+
+/// The answer to a `ControlCommand`, one JSON line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<MonitorSnapshot>,
+}
+
+impl ControlResponse {
+    fn failed(message: impl Into<String>) -> Self {
+        Self { ok: false, message: Some(message.into()), snapshot: None }
+    }
+}
+
+/// Per-user directory holding the instance lock and the control socket:
+/// `$XDG_RUNTIME_DIR/cc-monitor-rs`, or the cache directory where there is none.
+pub fn control_dir() -> PathBuf {
+    dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cc-monitor-rs")
+}
+
+pub fn socket_path() -> PathBuf {
+    control_dir().join(SOCKET_FILE)
+}
+
+/// Take the per-user instance lock for the rest of the process, unless it is
+/// already held. Fails when another instance holds it.
+pub fn lock() -> Result<()> {
+    if INSTANCE_LOCK.get().is_some() {
+        return Ok(());
+    }
+    let dir = control_dir();
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::set_permissions(&dir, Permissions::from_mode(0o700))?;
+
+    let mut lock = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE))?;
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => bail!(
+            "Another cc-monitor-rs instance is already running, use `cc-monitor-rs show` or `cc-monitor-rs quit`"
+        ),
+        Err(TryLockError::Error(e)) => return Err(e).context("Failed to take the instance lock"),
+    }
+    // The pid is only informative, the lock is what counts
+    lock.set_len(0)?;
+    writeln!(lock, "{}", std::process::id())?;
+    let _ = INSTANCE_LOCK.set(lock);
+    Ok(())
+}
+
+/// Take the instance lock if `lock` wasn't called yet and start answering on
+/// the control socket. Fails when another instance holds the lock.
+pub fn start(runtime: &Runtime) -> Result<()> {
+    lock()?;
+
+    // Holding the lock means a socket left behind is stale
+    let path = socket_path();
+    let _ = fs::remove_file(&path);
+    let listener = StdUnixListener::bind(&path)
+        .with_context(|| format!("Failed to listen on {}", path.display()))?;
+    listener.set_nonblocking(true)?;
+    log!("Listening for commands on {}", path.display());
+
+    runtime.spawn(async move {
+        if let Err(e) = serve(listener).await {
+            log!("Control socket stopped: {:#}", e);
+        }
+    });
+    Ok(())
+}
+
+async fn serve(listener: StdUnixListener) -> Result<()> {
+    let listener = UnixListener::from_std(listener)?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream).await {
+                log!("Control connection failed: {:#}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: UnixStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader).take(MAX_COMMAND_LENGTH).read_line(&mut line).await?;
+
+    let response = match serde_json::from_str::<ControlCommand>(&line) {
+        Ok(command) => {
+            log!("Control command: {:?}", command);
+            execute(command).await
+        }
+        Err(e) => ControlResponse::failed(format!("Invalid command: {}", e)),
+    };
+    let mut json = serde_json::to_vec(&response)?;
+    json.push(b'\n');
+    writer.write_all(&json).await?;
+    Ok(())
+}
+
+async fn execute(command: ControlCommand) -> ControlResponse {
+    if let Some(request) = command.request() {
+        return forward(request).await;
+    }
+    match latest_monitor_data() {
+        Some(data) => ControlResponse {
+            ok: true,
+            message: None,
+            snapshot: Some(MonitorSnapshot::new(&data, data.plan_token_limit)),
+        },
+        None => ControlResponse::failed("No data yet"),
+    }
+}
+
+async fn forward(request: MonitorRequest) -> ControlResponse {
+    match submit_monitor_request_and_wait(request, ACK_TIMEOUT).await {
+        Ok(ack) => ControlResponse { ok: ack.success, message: ack.message, snapshot: None },
+        Err(e) => ControlResponse::failed(e.to_string()),
+    }
+}
+
+/// Send a command to the running instance and wait for its answer. Fails when
+/// no instance is listening.
+pub fn send(command: &ControlCommand) -> Result<ControlResponse> {
+    let mut stream = StdUnixStream::connect(socket_path())
+        .context("cc-monitor-rs is not running")?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let mut json = serde_json::to_vec(command)?;
+    json.push(b'\n');
+    stream.write_all(&json)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    serde_json::from_str(&line).context("Invalid answer from the running instance")
+}
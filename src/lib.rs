@@ -1,8 +1,27 @@
+#[cfg(feature = "gui")]
 pub mod app;
 pub mod monitor;
+#[cfg(feature = "gui")]
 pub mod ui;
 pub mod utils;
 pub mod background;
 pub mod ui_updates;
+#[cfg(feature = "tray")]
 pub mod tray;
-pub mod i18n;
\ No newline at end of file
+pub mod i18n;
+pub mod alerts;
+pub mod budget;
+pub mod export;
+pub mod import;
+pub mod report;
+pub mod daemon;
+pub mod cli;
+#[cfg(unix)]
+pub mod instance;
+pub mod server;
+pub mod mqtt;
+pub mod telemetry;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+#[cfg(feature = "tui")]
+pub mod tui;
\ No newline at end of file
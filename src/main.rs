@@ -1,3 +1,219 @@
+use std::path::PathBuf;
+use clap::{Parser, Subcommand};
+use cc_monitor_rs::cli::snapshot::{self, SnapshotFormat, SnapshotOptions, DEFAULT_TEMPLATE};
+use cc_monitor_rs::cli::export::{self, ExportOptions, ExportTarget};
+use cc_monitor_rs::cli::report::{self, ReportOptions};
+use cc_monitor_rs::cli::import::{self, ImportOptions};
+use cc_monitor_rs::export::ExportFormat;
+use cc_monitor_rs::report::ReportFormat;
+use chrono::NaiveDate;
+#[cfg(unix)]
+use cc_monitor_rs::cli::control;
+use cc_monitor_rs::daemon::{self, DaemonOptions};
+#[cfg(unix)]
+use cc_monitor_rs::instance::ControlCommand;
+#[cfg(feature = "tui")]
+use cc_monitor_rs::tui::{self, TuiOptions};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the monitor headless, without a window or tray icon
+    Daemon {
+        /// Append log lines to this file instead of stdout
+        #[arg(long, env = "CC_MONITOR_LOG_FILE")]
+        log_file: Option<PathBuf>,
+    },
+    /// Print the current usage once, for scripts and status bars
+    Snapshot {
+        /// Print the full snapshot as JSON
+        #[arg(long, conflicts_with = "waybar")]
+        json: bool,
+        /// Print one line from a template, e.g. "{tokens} {cost} {remaining}"
+        #[arg(long, value_name = "TEMPLATE", conflicts_with = "json")]
+        format: Option<String>,
+        /// Print waybar custom module JSON; --format sets its text
+        #[arg(long)]
+        waybar: bool,
+        /// Fetch the usage history instead of using the stored one
+        #[arg(long)]
+        refresh_history: bool,
+    },
+    /// Export the stored usage history as CSV or JSON
+    Export {
+        /// blocks, daily, weekly, monthly, models, projects, latency, or all
+        dataset: ExportTarget,
+        /// csv or json
+        #[arg(long, default_value = "csv")]
+        format: ExportFormat,
+        /// First day (YYYY-MM-DD), 30 days before --to by default
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day (YYYY-MM-DD), today by default
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        /// Output file, or folder for `all`; stdout or the export folder by default
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Import ccusage `daily --json` / `blocks --json` dumps and exports into the usage history
+    Import {
+        /// Files to import; the kind of each is recognized from its content
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Only show what would be imported
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write a Markdown or HTML usage report and print its path
+    Report {
+        /// markdown or html; the format of the preferences by default
+        #[arg(long)]
+        format: Option<ReportFormat>,
+        /// First day (YYYY-MM-DD), 14 days before --to by default
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day (YYYY-MM-DD), today by default
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        /// Report file; in the report folder by default
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Run the monitor with a terminal UI
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Append log lines to this file; they are dropped otherwise
+        #[arg(long, env = "CC_MONITOR_LOG_FILE")]
+        log_file: Option<PathBuf>,
+    },
+    /// Ask the running instance to update now
+    #[cfg(unix)]
+    Refresh,
+    /// Pause polling of the running instance
+    #[cfg(unix)]
+    Pause {
+        /// Resume automatically after this many minutes; 0 pauses until `resume`
+        #[arg(long, default_value_t = 30)]
+        minutes: u64,
+    },
+    /// Resume polling of the running instance
+    #[cfg(unix)]
+    Resume,
+    /// Bring the window of the running instance to the front
+    #[cfg(unix)]
+    Show,
+    /// Stop the running instance
+    #[cfg(unix)]
+    Quit,
+}
+
+/// Forward a command to the running instance and exit with its result.
+#[cfg(unix)]
+fn forward(command: ControlCommand) -> ! {
+    match control::run(command) {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn main() {
-    cc_monitor_rs::app::app_main();
-}
\ No newline at end of file
+    let cli = Cli::parse();
+    match cli.command {
+        #[cfg(feature = "gui")]
+        None => {
+            // Before any window exists; a second launch brings the running
+            // window to the front instead
+            #[cfg(unix)]
+            if let Err(e) = cc_monitor_rs::instance::lock() {
+                let _ = cc_monitor_rs::instance::send(&ControlCommand::Show);
+                eprintln!("Error: {:#}", e);
+                std::process::exit(1);
+            }
+            cc_monitor_rs::app::app_main()
+        }
+        #[cfg(not(feature = "gui"))]
+        None => {
+            eprintln!("Built without the gui feature, run `cc-monitor-rs daemon` instead");
+            std::process::exit(2);
+        }
+        Some(Command::Daemon { log_file }) => {
+            if let Err(e) = daemon::run(DaemonOptions { log_file }) {
+                eprintln!("Error: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Snapshot { json, format, waybar, refresh_history }) => {
+            let template = format.unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
+            let format = if json {
+                SnapshotFormat::Json
+            } else if waybar {
+                SnapshotFormat::Waybar(template)
+            } else {
+                SnapshotFormat::Template(template)
+            };
+            match snapshot::run(SnapshotOptions { format, refresh_history }) {
+                Ok(code) => std::process::exit(code),
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Export { dataset, format, from, to, output }) => {
+            match export::run(ExportOptions { target: dataset, format, from, to, output }) {
+                Ok(code) => std::process::exit(code),
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Import { paths, dry_run }) => {
+            match import::run(ImportOptions { paths, dry_run }) {
+                Ok(code) => std::process::exit(code),
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::Report { format, from, to, output }) => {
+            match report::run(ReportOptions { format, from, to, output }) {
+                Ok(code) => std::process::exit(code),
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Tui { log_file }) => {
+            if let Err(e) = tui::run(TuiOptions { log_file }) {
+                eprintln!("Error: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(unix)]
+        Some(Command::Refresh) => forward(ControlCommand::Refresh),
+        #[cfg(unix)]
+        Some(Command::Pause { minutes }) => forward(ControlCommand::Pause {
+            duration_secs: (minutes > 0).then_some(minutes * 60),
+        }),
+        #[cfg(unix)]
+        Some(Command::Resume) => forward(ControlCommand::Resume),
+        #[cfg(unix)]
+        Some(Command::Show) => forward(ControlCommand::Show),
+        #[cfg(unix)]
+        Some(Command::Quit) => forward(ControlCommand::Quit),
+    }
+}
@@ -3,8 +3,11 @@ use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
 use regex::Regex;
 use std::collections::HashMap;
 use serde_json::Value;
+use anyhow::Result;
+use crate::monitor::history::{self, BlockUsage, DayUsage, TokenCounts};
 use crate::monitor::stats::DailyCost;
 use crate::utils::process::find_npx_path;
+use crate::monitor_log as log;
 
 #[derive(Clone, Debug, Default)]
 pub struct CcusageData {
@@ -19,14 +22,17 @@ pub struct CcusageData {
     // Numeric versions for calculations
     pub tokens_num: i64,
     pub cost_num: f64,
+    /// Tokens of the block split by type
+    pub token_counts: TokenCounts,
     pub reset_time: Option<chrono::DateTime<chrono::Local>>,
+    // Burn rate of the active block, if ccusage reported one
+    pub burn_rate_tokens_per_min: Option<f64>,
+    pub burn_rate_cost_per_hour: Option<f64>,
 }
 
 #[derive(Clone)]
 pub struct CcusageMonitor {
     npx_path: Option<String>,
-    failed_count: u32,
-    max_failures: u32,
 }
 
 impl Default for CcusageMonitor {
@@ -39,27 +45,22 @@ impl CcusageMonitor {
     pub fn new() -> Self {
         Self {
             npx_path: find_npx_path(),
-            failed_count: 0,
-            max_failures: 3,
         }
     }
     
-    pub fn get_ccusage_info(&mut self) -> CcusageData {
-        let npx_path = match &self.npx_path {
-            Some(path) => path.clone(),
-            None => {
-                self.npx_path = find_npx_path();
-                match &self.npx_path {
-                    Some(path) => path.clone(),
-                    None => {
-                        return CcusageData::default();
-                    }
-                }
-            }
-        };
+    /// Returns the npx path, searching again if it wasn't found before.
+    fn npx_path(&mut self) -> Result<String> {
+        if self.npx_path.is_none() {
+            self.npx_path = find_npx_path();
+        }
+        self.npx_path.clone().ok_or_else(|| anyhow::anyhow!("npx not found"))
+    }
+    
+    pub fn get_ccusage_info(&mut self) -> Result<CcusageData> {
+        let npx_path = self.npx_path()?;
         
         // Use default (cached) mode for faster updates, only use calculate mode periodically
-        let use_calculate_mode = self.failed_count == 0 && chrono::Local::now().timestamp() % 60 == 0; // Every minute
+        let use_calculate_mode = chrono::Local::now().timestamp() % 60 == 0; // Every minute
         
         let args = if use_calculate_mode {
             vec!["--yes", "ccusage@latest", "blocks", "--mode", "calculate", "--json"]
@@ -67,42 +68,14 @@ impl CcusageMonitor {
             vec!["--yes", "ccusage@latest", "blocks", "--json"]
         };
         
-        let start = std::time::Instant::now();
         let output = Command::new(&npx_path)
             .args(&args)
-            .output();
-        let elapsed = start.elapsed();
-        
-        if elapsed.as_millis() > 500 {
-            eprintln!("ccusage command took {}ms (mode: {})", 
-                elapsed.as_millis(), 
-                if use_calculate_mode { "calculate" } else { "cached" }
-            );
-        }
-            
-        match output {
-            Ok(output) if output.status.success() => {
-                self.failed_count = 0;
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                self.parse_ccusage_json(&stdout)
-            }
-            Ok(_output) => {
-                self.failed_count += 1;
-                if self.failed_count >= self.max_failures {
-                    self.update_ccusage();
-                    self.failed_count = 0;
-                }
-                CcusageData::default()
-            }
-            Err(_) => {
-                self.failed_count += 1;
-                if self.failed_count >= self.max_failures {
-                    self.update_ccusage();
-                    self.failed_count = 0;
-                }
-                CcusageData::default()
-            }
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!("ccusage blocks exited with {}", output.status);
         }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(self.parse_ccusage_json(&stdout))
     }
     
     fn parse_ccusage_json(&self, output: &str) -> CcusageData {
@@ -123,7 +96,7 @@ impl CcusageMonitor {
                 }
             }
             Err(e) => {
-                println!("Failed to parse JSON: {}", e);
+                log!("Failed to parse JSON: {}", e);
             }
         }
         
@@ -181,6 +154,8 @@ impl CcusageMonitor {
         };
         
         let reset_time = start_time.map(|st| st + chrono::Duration::hours(5));
+        let burn_rate_tokens_per_min = block["burnRate"]["tokensPerMinute"].as_f64();
+        let burn_rate_cost_per_hour = block["burnRate"]["costPerHour"].as_f64();
         
         CcusageData {
             latest_session: start_time.map(|dt| dt.format("%m/%d/%Y, %I:%M:%S %p").to_string())
@@ -194,7 +169,10 @@ impl CcusageMonitor {
             model: models,
             tokens_num: total_tokens as i64,
             cost_num: cost,
+            token_counts: TokenCounts::from_json(&block["tokenCounts"]),
             reset_time,
+            burn_rate_tokens_per_min,
+            burn_rate_cost_per_hour,
         }
     }
     
@@ -252,7 +230,10 @@ impl CcusageMonitor {
             model: models,
             tokens_num: total_tokens as i64,
             cost_num: cost,
+            token_counts: TokenCounts::from_json(&block["tokenCounts"]),
             reset_time: None,
+            burn_rate_tokens_per_min: None,
+            burn_rate_cost_per_hour: None,
         }
     }
     
@@ -379,6 +360,10 @@ impl CcusageMonitor {
             } else {
                 None
             },
+            burn_rate_tokens_per_min: None,
+            burn_rate_cost_per_hour: None,
+            // The table output has no per-type counts
+            token_counts: TokenCounts::default(),
         })
     }
     
@@ -422,7 +407,8 @@ impl CcusageMonitor {
         }
     }
     
-    fn update_ccusage(&mut self) {
+    /// Re-fetch the ccusage package, which often fixes a broken npx cache.
+    pub fn update_ccusage(&mut self) {
         if let Some(npx_path) = &self.npx_path {
             let _ = Command::new(npx_path)
                 .args(&["--yes", "ccusage@latest", "--version"])
@@ -430,36 +416,54 @@ impl CcusageMonitor {
         }
     }
     
-    pub fn analyze_daily_costs(&self) -> HashMap<String, DailyCost> {
+    /// Fetch the per-day usage with model and project breakdowns, and all blocks.
+    pub fn fetch_usage_history(&mut self) -> Result<(Vec<DayUsage>, Vec<BlockUsage>)> {
+        let npx_path = self.npx_path()?;
+        let run = |args: &[&str]| -> Result<String> {
+            let output = Command::new(&npx_path).args(args).output()?;
+            if !output.status.success() {
+                anyhow::bail!("ccusage {} exited with {}", args[2], output.status);
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        
+        let days = history::parse_daily_json(
+            &run(&["--yes", "ccusage@latest", "daily", "--json", "--breakdown", "--instances"])?)?;
+        let blocks = history::parse_blocks_json(
+            &run(&["--yes", "ccusage@latest", "blocks", "--json"])?)?;
+        Ok((days, blocks))
+    }
+    
+    pub fn analyze_daily_costs(&self) -> Result<HashMap<String, DailyCost>> {
         let mut daily_costs = HashMap::new();
         
-        if let Some(npx_path) = &self.npx_path {
-            // Try daily command first
-            if let Ok(output) = Command::new(npx_path)
-                .args(&["--yes", "ccusage@latest", "daily", "--order", "asc"])  // Remove calculate mode for speed
-                .output() 
-            {
-                if output.status.success() {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    self.parse_daily_output(&stdout, &mut daily_costs);
-                }
-            }
-            
-            // If no data from daily, try blocks command
-            if daily_costs.is_empty() {
-                if let Ok(output) = Command::new(npx_path)
-                    .args(&["--yes", "ccusage@latest", "blocks"])  // Remove calculate mode for speed
-                    .output()
-                {
-                    if output.status.success() {
-                        let stdout = String::from_utf8_lossy(&output.stdout);
-                        self.parse_blocks_output(&stdout, &mut daily_costs);
-                    }
-                }
+        let npx_path = self.npx_path.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("npx not found"))?;
+        
+        // Try daily command first
+        let daily_output = Command::new(npx_path)
+            .args(&["--yes", "ccusage@latest", "daily", "--order", "asc"])  // Remove calculate mode for speed
+            .output()?;
+        let daily_ok = daily_output.status.success();
+        if daily_ok {
+            let stdout = String::from_utf8_lossy(&daily_output.stdout);
+            self.parse_daily_output(&stdout, &mut daily_costs);
+        }
+        
+        // If no data from daily, try blocks command
+        if daily_costs.is_empty() {
+            let blocks_output = Command::new(npx_path)
+                .args(&["--yes", "ccusage@latest", "blocks"])  // Remove calculate mode for speed
+                .output()?;
+            if blocks_output.status.success() {
+                let stdout = String::from_utf8_lossy(&blocks_output.stdout);
+                self.parse_blocks_output(&stdout, &mut daily_costs);
+            } else if !daily_ok {
+                anyhow::bail!("ccusage daily exited with {}", daily_output.status);
             }
         }
         
-        daily_costs
+        Ok(daily_costs)
     }
     
     fn parse_daily_output(&self, output: &str, daily_costs: &mut HashMap<String, DailyCost>) {
@@ -0,0 +1,212 @@
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Consecutive failures after which a source is considered failing.
+const FAILING_AFTER: u32 = 3;
+/// Consecutive failures after which a source is disabled and only probed
+/// every `MAX_BACKOFF`.
+const DISABLE_AFTER: u32 = 10;
+/// Upper bound for the retry delay of a failing source, and the probe
+/// interval of a disabled one.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+/// Fraction of the retry delay that is randomized to spread out retries.
+const JITTER: f64 = 0.25;
+
+/// The data sources polled by the background worker, each on its own interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataSource {
+    /// Ping and TCP connection probes
+    Network,
+    /// The current ccusage block
+    Ccusage,
+    /// Daily cost history
+    History,
+}
+
+impl DataSource {
+    pub const ALL: [DataSource; 3] = [DataSource::Network, DataSource::Ccusage, DataSource::History];
+
+    /// Lowercase name used in logs and metric labels.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DataSource::Network => "network",
+            DataSource::Ccusage => "ccusage",
+            DataSource::History => "history",
+        }
+    }
+}
+
+/// Health of a single data source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    /// The last poll succeeded
+    #[default]
+    Healthy,
+    /// A few recent polls failed, retries are backed off
+    Degraded,
+    /// The source keeps failing, retries are backed off further
+    Failing,
+    /// The source failed too often and is only probed every `MAX_BACKOFF`
+    /// until it recovers or an update is forced
+    Disabled,
+}
+
+impl HealthState {
+    pub const ALL: [HealthState; 4] = [HealthState::Healthy, HealthState::Degraded, HealthState::Failing, HealthState::Disabled];
+
+    /// Lowercase name, as in the JSON output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HealthState::Healthy => "healthy",
+            HealthState::Degraded => "degraded",
+            HealthState::Failing => "failing",
+            HealthState::Disabled => "disabled",
+        }
+    }
+}
+
+/// Tracks failures of a data source and decides when it may be polled again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceHealth {
+    pub state: HealthState,
+    pub consecutive_failures: u32,
+    pub last_success: Option<DateTime<Local>>,
+    pub last_error: Option<DateTime<Local>>,
+    pub last_error_message: Option<String>,
+    /// Earliest time at which the source should be polled again
+    pub retry_at: Option<DateTime<Local>>,
+}
+
+impl SourceHealth {
+    /// Whether a regular (non-forced) poll should run now.
+    pub fn should_poll(&self) -> bool {
+        self.retry_at.is_none_or(|retry_at| Local::now() >= retry_at)
+    }
+
+    pub fn record_success(&mut self) {
+        self.state = HealthState::Healthy;
+        self.consecutive_failures = 0;
+        self.last_success = Some(Local::now());
+        self.retry_at = None;
+    }
+
+    /// Record a failed poll and schedule the next retry with exponential backoff
+    /// based on the regular polling interval. Returns the new state.
+    pub fn record_failure(&mut self, error: impl Into<String>, base_interval: Duration) -> HealthState {
+        let now = Local::now();
+        self.consecutive_failures += 1;
+        self.last_error = Some(now);
+        self.last_error_message = Some(error.into());
+
+        self.state = if self.consecutive_failures >= DISABLE_AFTER {
+            HealthState::Disabled
+        } else if self.consecutive_failures >= FAILING_AFTER {
+            HealthState::Failing
+        } else {
+            HealthState::Degraded
+        };
+
+        let delay = match self.state {
+            HealthState::Disabled => MAX_BACKOFF,
+            _ => backoff_delay(base_interval, self.consecutive_failures),
+        };
+        self.retry_at = chrono::Duration::from_std(delay).ok().map(|delay| now + delay);
+        self.state
+    }
+}
+
+/// `base * 2^failures`, capped at `MAX_BACKOFF`, with +/- `JITTER` randomization.
+fn backoff_delay(base: Duration, failures: u32) -> Duration {
+    with_jitter(capped_delay(base, failures), fastrand::f64())
+}
+
+fn capped_delay(base: Duration, failures: u32) -> Duration {
+    base.saturating_mul(1u32 << failures.min(16)).min(MAX_BACKOFF)
+}
+
+/// Scale `delay` by `1 - JITTER` for `random` 0 up to `1 + JITTER` for 1.
+fn with_jitter(delay: Duration, random: f64) -> Duration {
+    delay.mul_f64(1.0 + JITTER * (random * 2.0 - 1.0))
+}
+
+/// Health of every data source, as shown in the UI.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub network: SourceHealth,
+    pub ccusage: SourceHealth,
+    pub history: SourceHealth,
+}
+
+impl HealthReport {
+    pub fn get(&self, source: DataSource) -> &SourceHealth {
+        match source {
+            DataSource::Network => &self.network,
+            DataSource::Ccusage => &self.ccusage,
+            DataSource::History => &self.history,
+        }
+    }
+
+    pub fn get_mut(&mut self, source: DataSource) -> &mut SourceHealth {
+        match source {
+            DataSource::Network => &mut self.network,
+            DataSource::Ccusage => &mut self.ccusage,
+            DataSource::History => &mut self.history,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(10);
+
+    fn fail(health: &mut SourceHealth, times: u32) -> HealthState {
+        (0..times).map(|_| health.record_failure("unreachable", BASE)).last().unwrap()
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(capped_delay(BASE, 1), Duration::from_secs(20));
+        assert_eq!(capped_delay(BASE, 3), Duration::from_secs(80));
+        assert_eq!(capped_delay(BASE, 6), MAX_BACKOFF);
+        assert_eq!(capped_delay(BASE, 40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        assert_eq!(with_jitter(BASE, 0.0), BASE.mul_f64(1.0 - JITTER));
+        assert_eq!(with_jitter(BASE, 0.5), BASE);
+        assert_eq!(with_jitter(BASE, 1.0), BASE.mul_f64(1.0 + JITTER));
+        for _ in 0..100 {
+            let delay = backoff_delay(BASE, 2);
+            assert!(delay >= Duration::from_secs(30) && delay <= Duration::from_secs(50), "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn degrades_to_disabled_and_recovers() {
+        let mut health = SourceHealth::default();
+        assert_eq!(health.state, HealthState::Healthy);
+        assert!(health.should_poll());
+
+        assert_eq!(fail(&mut health, 1), HealthState::Degraded);
+        assert!(!health.should_poll());
+        assert_eq!(fail(&mut health, FAILING_AFTER - 1), HealthState::Failing);
+        assert_eq!(fail(&mut health, DISABLE_AFTER - FAILING_AFTER), HealthState::Disabled);
+
+        // Disabled sources are still probed every `MAX_BACKOFF`
+        let delay = health.retry_at.unwrap() - health.last_error.unwrap();
+        assert_eq!(delay.to_std().unwrap(), MAX_BACKOFF);
+        assert!(!health.should_poll());
+        health.retry_at = Some(Local::now() - chrono::Duration::seconds(1));
+        assert!(health.should_poll());
+
+        health.record_success();
+        assert_eq!(health.state, HealthState::Healthy);
+        assert_eq!(health.consecutive_failures, 0);
+        assert!(health.should_poll());
+    }
+}
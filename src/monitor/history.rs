@@ -0,0 +1,473 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::monitor::stats::DailyCost;
+
+/// Token counts split by type, as reported by ccusage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenCounts {
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+}
+
+impl TokenCounts {
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_creation + self.cache_read
+    }
+
+    pub fn add(&mut self, other: &TokenCounts) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+    }
+
+    /// Reads the counts from a ccusage entry, which names the cache fields
+    /// differently in `daily` (`cacheCreationTokens`) and `blocks`
+    /// (`cacheCreationInputTokens`) output.
+    pub(crate) fn from_json(value: &Value) -> Self {
+        let count = |keys: &[&str]| keys.iter().find_map(|key| value[*key].as_u64()).unwrap_or(0);
+        Self {
+            input: count(&["inputTokens"]),
+            output: count(&["outputTokens"]),
+            cache_creation: count(&["cacheCreationTokens", "cacheCreationInputTokens"]),
+            cache_read: count(&["cacheReadTokens", "cacheReadInputTokens"]),
+        }
+    }
+}
+
+/// Spend of one model on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub tokens: TokenCounts,
+    pub cost: f64,
+}
+
+/// Spend of one project on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectUsage {
+    pub project: String,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+/// Usage of one calendar day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayUsage {
+    pub date: NaiveDate,
+    pub tokens: TokenCounts,
+    pub cost: f64,
+    #[serde(default)]
+    pub models: Vec<ModelUsage>,
+    #[serde(default)]
+    pub projects: Vec<ProjectUsage>,
+}
+
+impl DayUsage {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            tokens: TokenCounts::default(),
+            cost: 0.0,
+            models: Vec::new(),
+            projects: Vec::new(),
+        }
+    }
+
+    /// Add the usage of `other`, combining the breakdowns of the same model or project.
+    pub fn add(&mut self, other: &DayUsage) {
+        self.tokens.add(&other.tokens);
+        self.cost += other.cost;
+        for usage in &other.models {
+            match self.models.iter_mut().find(|model| model.model == usage.model) {
+                Some(model) => {
+                    model.tokens.add(&usage.tokens);
+                    model.cost += usage.cost;
+                }
+                None => self.models.push(usage.clone()),
+            }
+        }
+        for usage in &other.projects {
+            match self.projects.iter_mut().find(|project| project.project == usage.project) {
+                Some(project) => {
+                    project.tokens += usage.tokens;
+                    project.cost += usage.cost;
+                }
+                None => self.projects.push(usage.clone()),
+            }
+        }
+    }
+}
+
+/// One 5-hour billing block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockUsage {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    /// Time of the last entry in the block
+    #[serde(default)]
+    pub actual_end: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub is_active: bool,
+    pub tokens: TokenCounts,
+    pub cost: f64,
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Burn rate reported by ccusage, only known for the active block
+    #[serde(default)]
+    pub burn_rate_tokens_per_min: Option<f64>,
+    /// Read from an import, possibly of another machine, rather than from
+    /// the local ccusage logs
+    #[serde(default)]
+    pub imported: bool,
+}
+
+impl BlockUsage {
+    /// The reported burn rate, or the average rate over the block's activity.
+    pub fn tokens_per_minute(&self) -> Option<f64> {
+        if let Some(rate) = self.burn_rate_tokens_per_min {
+            return Some(rate);
+        }
+        let minutes = (self.actual_end? - self.start).num_seconds() as f64 / 60.0;
+        (minutes >= 1.0).then(|| self.tokens.total() as f64 / minutes)
+    }
+
+    /// Local blocks are identified by their start time, so repeated fetches
+    /// replace each other, also after the active block started using another
+    /// model. Imported blocks may come from another machine that used the
+    /// same hours, so they also need the same models and tokens.
+    fn same_block(&self, other: &BlockUsage) -> bool {
+        if !self.imported && !other.imported {
+            return self.start == other.start;
+        }
+        let mut models = self.models.clone();
+        let mut other_models = other.models.clone();
+        models.sort();
+        other_models.sort();
+        self.start == other.start && models == other_models && self.tokens == other.tokens
+    }
+}
+
+/// Totals over a date range of the usage history.
+#[derive(Debug, Clone, Default)]
+pub struct UsageSummary {
+    pub tokens: TokenCounts,
+    pub cost: f64,
+    pub blocks: usize,
+    pub peak_burn_rate: Option<f64>,
+    /// Cost and tokens per model, most expensive first
+    pub models: Vec<(String, f64, u64)>,
+    /// Cost and tokens per project, most expensive first
+    pub projects: Vec<(String, f64, u64)>,
+}
+
+impl UsageSummary {
+    pub fn has_activity(&self) -> bool {
+        self.blocks > 0 || self.tokens.total() > 0 || self.cost > 0.0
+    }
+}
+
+/// Local record of the daily usage and blocks reported by ccusage, persisted
+/// next to the preferences so ranges older than ccusage's logs stay available.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageHistory {
+    days: BTreeMap<NaiveDate, DayUsage>,
+    /// Imported days, kept apart so refreshing the local days from ccusage
+    /// doesn't replace them; a date can hold the usage of several machines
+    #[serde(default)]
+    imported: BTreeMap<NaiveDate, Vec<DayUsage>>,
+    blocks: Vec<BlockUsage>,
+}
+
+impl UsageHistory {
+    pub fn load() -> Result<Self> {
+        let path = Self::get_history_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::get_history_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn get_history_path() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+        Ok(config_dir.join("cc-monitor-rs").join("history.json"))
+    }
+
+    /// Add or replace the days read from ccusage, returning how many were new.
+    pub fn merge_days(&mut self, days: Vec<DayUsage>) -> usize {
+        let mut added = 0;
+        for day in days {
+            if self.days.insert(day.date, day).is_none() {
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Add imported days to the usage of their dates, skipping days with the
+    /// same tokens as the local day or a day imported before, so importing
+    /// the same files again changes nothing. Returns how many were added.
+    pub fn import_days(&mut self, days: Vec<DayUsage>) -> usize {
+        let mut added = 0;
+        for day in days {
+            if self.days.get(&day.date).is_some_and(|local| local.tokens == day.tokens) {
+                continue;
+            }
+            let imported = self.imported.entry(day.date).or_default();
+            if imported.iter().all(|existing| existing.tokens != day.tokens) {
+                imported.push(day);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Add or replace blocks, deduplicated as described at `BlockUsage::same_block`.
+    /// An imported copy of a stored block is skipped. Returns how many were new.
+    pub fn merge_blocks(&mut self, blocks: Vec<BlockUsage>) -> usize {
+        let mut added = 0;
+        for block in blocks {
+            match self.blocks.iter_mut().find(|existing| existing.same_block(&block)) {
+                Some(existing) if !block.imported || existing.imported => *existing = block,
+                Some(_) => {}
+                None => {
+                    self.blocks.push(block);
+                    added += 1;
+                }
+            }
+        }
+        self.blocks.sort_by_key(|block| block.start);
+        added
+    }
+
+    /// The days with usage in the range, each the local usage plus the imported one.
+    pub fn days(&self, from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = DayUsage> + '_ {
+        let dates: BTreeSet<NaiveDate> = self.days.range(from..=to).map(|(date, _)| *date)
+            .chain(self.imported.range(from..=to).map(|(date, _)| *date))
+            .collect();
+        dates.into_iter().map(|date| self.day(date))
+    }
+
+    fn day(&self, date: NaiveDate) -> DayUsage {
+        let local = self.days.get(&date);
+        let mut day = local.cloned().unwrap_or_else(|| DayUsage::new(date));
+        for imported in self.imported.get(&date).into_iter().flatten() {
+            // Usage of this machine imported before ccusage reported it
+            if local.is_some_and(|local| local.tokens == imported.tokens) {
+                continue;
+            }
+            day.add(imported);
+        }
+        day
+    }
+
+    pub fn blocks(&self, from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = &BlockUsage> {
+        self.blocks.iter().filter(move |block| {
+            let day = block.start.date_naive();
+            day >= from && day <= to
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty() && self.imported.is_empty() && self.blocks.is_empty()
+    }
+
+    /// Daily costs in the `MM-DD` form used by the history chart.
+    pub fn daily_costs(&self) -> HashMap<String, DailyCost> {
+        self.days(NaiveDate::MIN, NaiveDate::MAX)
+            .map(|day| {
+                let date = day.date.format("%m-%d").to_string();
+                let sessions = self.blocks.iter()
+                    .filter(|block| block.start.date_naive() == day.date)
+                    .count()
+                    .max(1) as u32;
+                (date.clone(), DailyCost { date, cost: day.cost, sessions })
+            })
+            .collect()
+    }
+
+    pub fn summarize(&self, from: NaiveDate, to: NaiveDate) -> UsageSummary {
+        let mut summary = UsageSummary::default();
+        let mut models: HashMap<String, (f64, u64)> = HashMap::new();
+        let mut projects: HashMap<String, (f64, u64)> = HashMap::new();
+
+        for day in self.days(from, to) {
+            summary.tokens.add(&day.tokens);
+            summary.cost += day.cost;
+            for model in &day.models {
+                let entry = models.entry(model.model.clone()).or_default();
+                entry.0 += model.cost;
+                entry.1 += model.tokens.total();
+            }
+            for project in &day.projects {
+                let entry = projects.entry(project.project.clone()).or_default();
+                entry.0 += project.cost;
+                entry.1 += project.tokens;
+            }
+        }
+        for block in self.blocks(from, to) {
+            summary.blocks += 1;
+            if let Some(rate) = block.tokens_per_minute() {
+                summary.peak_burn_rate = Some(summary.peak_burn_rate.map_or(rate, |peak: f64| peak.max(rate)));
+            }
+        }
+
+        let sorted = |map: HashMap<String, (f64, u64)>| {
+            let mut entries: Vec<(String, f64, u64)> = map.into_iter()
+                .map(|(name, (cost, tokens))| (name, cost, tokens))
+                .collect();
+            entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+            entries
+        };
+        summary.models = sorted(models);
+        summary.projects = sorted(projects);
+        summary
+    }
+}
+
+/// Parses `ccusage daily --json`, with or without `--breakdown` and
+/// `--instances`. With `--instances` the days are grouped per project and
+/// are combined here into one entry per day.
+pub fn parse_daily_json(output: &str) -> Result<Vec<DayUsage>> {
+    let json: Value = serde_json::from_str(output)?;
+    let mut days: BTreeMap<NaiveDate, DayUsage> = BTreeMap::new();
+
+    let mut add_entry = |entry: &Value, project: Option<&str>| {
+        let Some(date) = entry["date"].as_str()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()) else {
+            return;
+        };
+        let tokens = TokenCounts::from_json(entry);
+        let cost = entry["totalCost"].as_f64().or_else(|| entry["costUSD"].as_f64()).unwrap_or(0.0);
+        let day = days.entry(date).or_insert_with(|| DayUsage::new(date));
+        day.tokens.add(&tokens);
+        day.cost += cost;
+
+        for breakdown in entry["modelBreakdowns"].as_array().into_iter().flatten() {
+            let Some(name) = breakdown["modelName"].as_str() else {
+                continue;
+            };
+            let model_tokens = TokenCounts::from_json(breakdown);
+            let model_cost = breakdown["cost"].as_f64().unwrap_or(0.0);
+            match day.models.iter_mut().find(|model| model.model == name) {
+                Some(model) => {
+                    model.tokens.add(&model_tokens);
+                    model.cost += model_cost;
+                }
+                None => day.models.push(ModelUsage {
+                    model: name.to_string(),
+                    tokens: model_tokens,
+                    cost: model_cost,
+                }),
+            }
+        }
+
+        if let Some(project) = project {
+            day.projects.push(ProjectUsage {
+                project: project.to_string(),
+                tokens: tokens.total(),
+                cost,
+            });
+        }
+    };
+
+    if let Some(projects) = json["projects"].as_object() {
+        for (project, entries) in projects {
+            for entry in entries.as_array().into_iter().flatten() {
+                add_entry(entry, Some(project));
+            }
+        }
+    } else if let Some(entries) = json["daily"].as_array() {
+        for entry in entries {
+            add_entry(entry, entry["project"].as_str());
+        }
+    } else {
+        anyhow::bail!("Unrecognized ccusage daily output");
+    }
+
+    Ok(days.into_values().collect())
+}
+
+/// Parses `ccusage blocks --json`, skipping the gaps between blocks.
+pub fn parse_blocks_json(output: &str) -> Result<Vec<BlockUsage>> {
+    let json: Value = serde_json::from_str(output)?;
+    let Some(blocks) = json["blocks"].as_array() else {
+        anyhow::bail!("Unrecognized ccusage blocks output");
+    };
+    let parse_time = |value: &Value| value.as_str()
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&Local));
+
+    Ok(blocks.iter()
+        .filter(|block| block["isGap"].as_bool() != Some(true))
+        .filter_map(|block| {
+            let start = parse_time(&block["startTime"])?;
+            Some(BlockUsage {
+                start,
+                end: parse_time(&block["endTime"]).unwrap_or(start + chrono::Duration::hours(5)),
+                actual_end: parse_time(&block["actualEndTime"]),
+                is_active: block["isActive"].as_bool().unwrap_or(false),
+                tokens: TokenCounts::from_json(&block["tokenCounts"]),
+                cost: block["costUSD"].as_f64().unwrap_or(0.0),
+                models: block["models"].as_array()
+                    .map(|models| models.iter().filter_map(|model| model.as_str().map(String::from)).collect())
+                    .unwrap_or_default(),
+                burn_rate_tokens_per_min: block["burnRate"]["tokensPerMinute"].as_f64(),
+                imported: false,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    fn block(hour: u32, models: &[&str], tokens: u64, cost: f64) -> BlockUsage {
+        let start = Local.with_ymd_and_hms(2024, 5, 15, hour, 0, 0).unwrap();
+        BlockUsage {
+            start,
+            end: start + chrono::Duration::hours(5),
+            actual_end: None,
+            is_active: true,
+            tokens: TokenCounts { input: tokens, ..TokenCounts::default() },
+            cost,
+            models: models.iter().map(|model| model.to_string()).collect(),
+            burn_rate_tokens_per_min: None,
+            imported: false,
+        }
+    }
+
+    #[test]
+    fn active_block_with_new_model_replaces_previous_copy() {
+        let mut history = UsageHistory::default();
+        assert_eq!(history.merge_blocks(vec![block(10, &["sonnet"], 100, 1.0)]), 1);
+        assert_eq!(history.merge_blocks(vec![block(10, &["sonnet", "opus"], 300, 4.0)]), 0);
+        assert_eq!(history.merge_blocks(vec![block(15, &["opus"], 50, 2.0)]), 1);
+
+        let day = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        assert_eq!(history.summarize(day, day).blocks, 2);
+        assert_eq!(history.blocks(day, day).map(|block| block.cost).sum::<f64>(), 6.0);
+        assert_eq!(history.blocks(day, day).next().unwrap().models, vec!["sonnet", "opus"]);
+    }
+}
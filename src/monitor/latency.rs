@@ -0,0 +1,89 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Samples older than this are dropped when the log is pruned.
+const RETENTION_DAYS: i64 = 60;
+/// At most one sample is kept per this many seconds, whatever the network interval.
+pub const SAMPLE_INTERVAL_SECS: i64 = 60;
+
+/// The result of one network probe round.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencySample {
+    pub time: DateTime<Local>,
+    pub connected: bool,
+    /// Ping round trip, when ping succeeded and reported a time
+    pub ping_ms: Option<f64>,
+    /// TCP connect time to the test address or proxy
+    pub connect_ms: Option<f64>,
+}
+
+impl LatencySample {
+    /// Parses the `23ms` form the network monitor reports the ping in.
+    pub fn parse_ping(latency: Option<&str>) -> Option<f64> {
+        latency?.trim().trim_end_matches("ms").trim().parse().ok()
+    }
+}
+
+/// Append-only log of latency samples, one JSON object per line next to the
+/// preferences, so recording a sample doesn't rewrite the whole history.
+pub struct LatencyLog;
+
+impl LatencyLog {
+    fn get_log_path() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+        Ok(config_dir.join("cc-monitor-rs").join("latency.jsonl"))
+    }
+
+    pub fn append(sample: &LatencySample) -> Result<()> {
+        let path = Self::get_log_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_vec(sample)?;
+        line.push(b'\n');
+        OpenOptions::new().create(true).append(true).open(path)?.write_all(&line)?;
+        Ok(())
+    }
+
+    /// All samples, oldest first. Lines that don't parse, e.g. one cut short
+    /// by a crash, are skipped.
+    pub fn load() -> Result<Vec<LatencySample>> {
+        let path = Self::get_log_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    /// Samples taken on the days from `from` to `to`, inclusive.
+    pub fn samples(from: NaiveDate, to: NaiveDate) -> Result<Vec<LatencySample>> {
+        let mut samples = Self::load()?;
+        samples.retain(|sample| {
+            let day = sample.time.date_naive();
+            day >= from && day <= to
+        });
+        Ok(samples)
+    }
+
+    /// Drop the samples older than the retention period.
+    pub fn prune() -> Result<()> {
+        let cutoff = Local::now() - chrono::Duration::days(RETENTION_DAYS);
+        let samples = Self::load()?;
+        if samples.first().is_none_or(|sample| sample.time >= cutoff) {
+            return Ok(());
+        }
+        let mut content = String::new();
+        for sample in samples.iter().filter(|sample| sample.time >= cutoff) {
+            content.push_str(&serde_json::to_string(sample)?);
+            content.push('\n');
+        }
+        fs::write(Self::get_log_path()?, content)?;
+        Ok(())
+    }
+}
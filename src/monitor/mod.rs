@@ -1,13 +1,22 @@
 pub mod network;
 pub mod ccusage;
 pub mod stats;
+pub mod health;
+pub mod history;
+pub mod snapshot;
+pub mod latency;
 
 pub use network::NetworkMonitor;
 pub use ccusage::{CcusageMonitor, CcusageData};
 pub use stats::DailyCost;
+pub use health::{DataSource, HealthReport, HealthState, SourceHealth};
+pub use history::{BlockUsage, DayUsage, UsageHistory, UsageSummary};
+pub use snapshot::MonitorSnapshot;
+pub use latency::{LatencyLog, LatencySample};
 
 use chrono::{DateTime, Local};
 use std::collections::HashMap;
+use crate::budget::BudgetStatus;
 
 #[derive(Clone, Debug, Default)]
 pub struct MonitorData {
@@ -22,6 +31,42 @@ pub struct MonitorData {
     // Historical data
     pub daily_costs: HashMap<String, DailyCost>,
     
+    // Spend against the configured budgets
+    pub budgets: Vec<BudgetStatus>,
+    
+    // Data source health
+    pub health: HealthReport,
+    
+    // Plan token limit from the preferences, kept current by the worker
+    pub plan_token_limit: Option<u64>,
+    
     // Update timestamp
     pub last_update: DateTime<Local>,
-}
\ No newline at end of file
+}
+
+impl MonitorData {
+    /// Cost recorded for today in the daily history, if any.
+    pub fn today_cost(&self) -> Option<f64> {
+        let today = Local::now();
+        [today.format("%m-%d").to_string(), today.format("%m/%d").to_string()]
+            .iter()
+            .find_map(|key| self.daily_costs.get(key))
+            .map(|dc| dc.cost)
+    }
+    
+    /// Share of the plan's token limit used by the current block.
+    pub fn plan_percent(&self, plan_token_limit: Option<u64>) -> Option<f64> {
+        plan_token_limit
+            .filter(|limit| *limit > 0)
+            .map(|limit| self.ccusage_data.tokens_num as f64 / limit as f64 * 100.0)
+    }
+    
+    /// Minutes left until the active block resets.
+    pub fn remaining_minutes(&self) -> Option<i64> {
+        if self.ccusage_data.status != "ACTIVE" {
+            return None;
+        }
+        self.ccusage_data.reset_time
+            .map(|reset_time| (reset_time - Local::now()).num_minutes().max(0))
+    }
+}
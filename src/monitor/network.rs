@@ -3,6 +3,7 @@ use std::time::{Duration, Instant};
 use std::net::{TcpStream, ToSocketAddrs};
 use std::env;
 use crate::i18n;
+use crate::monitor_log as log;
 
 #[derive(Clone)]
 pub struct NetworkMonitor;
@@ -152,7 +153,7 @@ impl NetworkMonitor {
             };
             
             // Log that we're using proxy
-            eprintln!("Using proxy: {}", addr);
+            log!("Using proxy: {}", addr);
             addr
         } else {
             // Use a reliable IP address instead of domain to avoid DNS issues  
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{HealthReport, MonitorData};
+use crate::budget::BudgetStatus;
+
+/// A point-in-time view of the monitor data with a stable JSON schema, used
+/// by the snapshot command and the HTTP API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSnapshot {
+    pub timestamp: DateTime<Local>,
+    pub network: NetworkSnapshot,
+    pub block: BlockSnapshot,
+    /// Cost of today in USD, if the history has an entry for today
+    pub today_cost: Option<f64>,
+    /// Oldest day first
+    pub daily_costs: Vec<DailyCostSnapshot>,
+    pub budgets: Vec<BudgetSnapshot>,
+    pub health: HealthReport,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    pub connected: bool,
+    pub latency: Option<String>,
+    pub speed: Option<String>,
+}
+
+/// The current (or most recent) 5-hour block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockSnapshot {
+    pub active: bool,
+    /// `active`, `completed`, ... or empty when ccusage returned no block
+    pub status: String,
+    pub model: String,
+    pub tokens: i64,
+    /// USD
+    pub cost: f64,
+    pub start: Option<DateTime<Local>>,
+    pub reset_time: Option<DateTime<Local>>,
+    pub remaining_secs: Option<i64>,
+    pub burn_rate_tokens_per_min: Option<f64>,
+    pub burn_rate_cost_per_hour: Option<f64>,
+    /// Tokens as a percentage of `plan_token_limit`, if one is configured
+    pub plan_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyCostSnapshot {
+    pub date: Option<NaiveDate>,
+    pub cost: f64,
+    pub sessions: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetSnapshot {
+    #[serde(flatten)]
+    pub status: BudgetStatus,
+    pub percent: f64,
+}
+
+impl MonitorSnapshot {
+    pub fn new(data: &MonitorData, plan_token_limit: Option<u64>) -> Self {
+        let ccusage = &data.ccusage_data;
+        let today = Local::now().date_naive();
+        let active = ccusage.status == "ACTIVE";
+
+        let mut daily_costs: Vec<_> = data.daily_costs.values()
+            .map(|day| DailyCostSnapshot {
+                date: day.day(today),
+                cost: day.cost,
+                sessions: day.sessions,
+            })
+            .collect();
+        daily_costs.sort_by_key(|day| day.date);
+
+        Self {
+            timestamp: data.last_update,
+            network: NetworkSnapshot {
+                connected: data.network_connected,
+                latency: data.latency.clone(),
+                speed: data.connection_speed.clone(),
+            },
+            block: BlockSnapshot {
+                active,
+                status: ccusage.status.to_lowercase(),
+                model: ccusage.model.clone(),
+                tokens: ccusage.tokens_num,
+                cost: ccusage.cost_num,
+                start: ccusage.reset_time.map(|reset_time| reset_time - chrono::Duration::hours(5)),
+                reset_time: ccusage.reset_time,
+                remaining_secs: ccusage.reset_time
+                    .filter(|_| active)
+                    .map(|reset_time| (reset_time - Local::now()).num_seconds().max(0)),
+                burn_rate_tokens_per_min: ccusage.burn_rate_tokens_per_min,
+                burn_rate_cost_per_hour: ccusage.burn_rate_cost_per_hour,
+                plan_percent: data.plan_percent(plan_token_limit),
+            },
+            today_cost: data.today_cost(),
+            daily_costs,
+            budgets: data.budgets.iter()
+                .map(|status| BudgetSnapshot { percent: status.percent(), status: status.clone() })
+                .collect(),
+            health: data.health.clone(),
+        }
+    }
+}
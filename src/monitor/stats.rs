@@ -1,6 +1,23 @@
+use chrono::{Datelike, NaiveDate};
+
 #[derive(Clone, Debug)]
 pub struct DailyCost {
     pub date: String,
     pub cost: f64,
     pub sessions: u32,
-}
\ No newline at end of file
+}
+
+impl DailyCost {
+    /// Resolves the `MM-DD` or `MM/DD` date to a calendar day. ccusage omits
+    /// the year, so it is taken from `today`, or the year before for dates
+    /// that would otherwise lie in the future.
+    pub fn day(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let (month, day) = self.date.split_once(['-', '/'])?;
+        let (month, day) = (month.trim().parse().ok()?, day.trim().parse().ok()?);
+        let day_this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+        match day_this_year {
+            Some(date) if date <= today => Some(date),
+            _ => NaiveDate::from_ymd_opt(today.year() - 1, month, day),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(feature = "mqtt")]
+mod publisher;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "mqtt")]
+use crate::utils::preferences::Preferences;
+#[cfg(feature = "mqtt")]
+use crate::monitor_log as log;
+
+/// The MQTT publisher, stored in the preferences. Only used when built with
+/// the `mqtt` feature, and read once when the worker starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MqttSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Broker host name or address
+    #[serde(default = "default_host")]
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Connect with TLS, verifying the broker against the system certificates
+    #[serde(default)]
+    pub tls: bool,
+    /// Topics are `<topic_prefix>/<host>/...`
+    #[serde(default = "default_topic_prefix")]
+    pub topic_prefix: String,
+    /// Publish Home Assistant discovery payloads
+    #[serde(default = "default_true")]
+    pub home_assistant: bool,
+    #[serde(default = "default_discovery_prefix")]
+    pub discovery_prefix: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_host(),
+            port: default_port(),
+            username: None,
+            password: None,
+            tls: false,
+            topic_prefix: default_topic_prefix(),
+            home_assistant: true,
+            discovery_prefix: default_discovery_prefix(),
+        }
+    }
+}
+
+fn default_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_topic_prefix() -> String {
+    "ccmonitor".to_string()
+}
+
+fn default_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Start publishing to the broker in the preferences on the background runtime.
+#[cfg(feature = "mqtt")]
+pub fn spawn(runtime: &tokio::runtime::Runtime) {
+    let prefs = Preferences::load().unwrap_or_default();
+    if prefs.mqtt.enabled {
+        runtime.spawn(async move {
+            if let Err(e) = publisher::run(prefs.mqtt).await {
+                log!("MQTT publisher stopped: {:#}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use anyhow::Result;
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Packet, QoS, Transport};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::MqttSettings;
+use crate::monitor::{MonitorData, MonitorSnapshot};
+use crate::ui_updates::{latest_monitor_data, subscribe_monitor_updates, MonitorUpdate};
+use crate::utils::notifications::NotificationRecord;
+use crate::monitor_log as log;
+
+/// Messages queued for the broker while the connection is busy or down.
+const QUEUE_CAPACITY: usize = 100;
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+/// Wait between connection attempts after the broker went away.
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// A Home Assistant entity built from one of the state topics.
+struct Entity {
+    component: &'static str,
+    id: &'static str,
+    name: &'static str,
+    topic: &'static str,
+    value_template: &'static str,
+    unit: Option<&'static str>,
+    device_class: Option<&'static str>,
+    icon: &'static str,
+}
+
+const ENTITIES: &[Entity] = &[
+    Entity { component: "sensor", id: "tokens", name: "Block tokens", topic: "block", value_template: "{{ value_json.tokens }}", unit: Some("tokens"), device_class: None, icon: "mdi:counter" },
+    Entity { component: "sensor", id: "cost", name: "Block cost", topic: "block", value_template: "{{ value_json.cost }}", unit: Some("USD"), device_class: Some("monetary"), icon: "mdi:currency-usd" },
+    Entity { component: "sensor", id: "reset_time", name: "Block reset", topic: "block", value_template: "{{ value_json.reset_time }}", unit: None, device_class: Some("timestamp"), icon: "mdi:timer-sand" },
+    Entity { component: "sensor", id: "plan_percent", name: "Plan usage", topic: "block", value_template: "{{ value_json.plan_percent }}", unit: Some("%"), device_class: None, icon: "mdi:gauge" },
+    Entity { component: "sensor", id: "burn_rate", name: "Burn rate", topic: "block", value_template: "{{ value_json.burn_rate_tokens_per_min }}", unit: Some("tokens/min"), device_class: None, icon: "mdi:fire" },
+    Entity { component: "sensor", id: "model", name: "Model", topic: "block", value_template: "{{ value_json.model }}", unit: None, device_class: None, icon: "mdi:robot" },
+    Entity { component: "binary_sensor", id: "block_active", name: "Block active", topic: "block", value_template: "{{ 'ON' if value_json.active else 'OFF' }}", unit: None, device_class: Some("running"), icon: "mdi:play-circle" },
+    Entity { component: "binary_sensor", id: "network", name: "Network", topic: "network", value_template: "{{ 'ON' if value_json.connected else 'OFF' }}", unit: None, device_class: Some("connectivity"), icon: "mdi:lan-connect" },
+    Entity { component: "sensor", id: "latency", name: "Latency", topic: "network", value_template: "{{ value_json.latency }}", unit: None, device_class: None, icon: "mdi:timer-outline" },
+    Entity { component: "sensor", id: "last_alert", name: "Last alert", topic: "alerts", value_template: "{{ value_json.title }}", unit: None, device_class: None, icon: "mdi:bell" },
+];
+
+/// Publishes retained JSON state topics under `<topic_prefix>/<host>/`.
+struct Publisher {
+    client: AsyncClient,
+    settings: MqttSettings,
+    host: String,
+    /// Last payload per state topic, to skip unchanged ones
+    published: HashMap<String, Vec<u8>>,
+}
+
+impl Publisher {
+    /// A publisher for the broker in the settings, with the event loop that
+    /// connects to it once polled.
+    fn new(settings: MqttSettings, host: String) -> (Self, EventLoop) {
+        let mut options = MqttOptions::new(format!("cc-monitor-{}", host), &settings.host, settings.port);
+        options.set_keep_alive(KEEP_ALIVE);
+        options.set_last_will(LastWill::new(
+            format!("{}/{}/status", settings.topic_prefix, host),
+            "offline",
+            QoS::AtLeastOnce,
+            true,
+        ));
+        if let Some(username) = &settings.username {
+            options.set_credentials(username, settings.password.clone().unwrap_or_default());
+        }
+        if settings.tls {
+            options.set_transport(Transport::tls_with_default_config());
+        }
+
+        let (client, eventloop) = AsyncClient::new(options, QUEUE_CAPACITY);
+        (Self { client, settings, host, published: HashMap::new() }, eventloop)
+    }
+
+    fn topic(&self, name: &str) -> String {
+        format!("{}/{}/{}", self.settings.topic_prefix, self.host, name)
+    }
+
+    /// Queue a retained message. Never waits, since the connection is driven
+    /// by the same task; messages are dropped when the queue is full.
+    fn publish(&self, topic: String, payload: impl Serialize) -> Result<()> {
+        let payload = serde_json::to_vec(&payload)?;
+        self.client.try_publish(topic, QoS::AtLeastOnce, true, payload)?;
+        Ok(())
+    }
+
+    /// Publish a state topic unless it still holds the same payload.
+    fn publish_state(&mut self, name: &str, payload: impl Serialize) -> Result<()> {
+        let topic = self.topic(name);
+        let payload = serde_json::to_vec(&payload)?;
+        if self.published.get(&topic) == Some(&payload) {
+            return Ok(());
+        }
+        self.client.try_publish(topic.clone(), QoS::AtLeastOnce, true, payload.clone())?;
+        self.published.insert(topic, payload);
+        Ok(())
+    }
+
+    fn publish_data(&mut self, data: &MonitorData) -> Result<()> {
+        let snapshot = MonitorSnapshot::new(data, data.plan_token_limit);
+        // The remaining time changes every second, subscribers count down to `reset_time`
+        let mut block = serde_json::to_value(&snapshot.block)?;
+        if let Some(block) = block.as_object_mut() {
+            block.remove("remaining_secs");
+        }
+        self.publish_state("block", &block)?;
+        self.publish_state("network", &snapshot.network)?;
+        self.publish_state("budgets", &snapshot.budgets)
+    }
+
+    fn publish_alert(&self, record: &NotificationRecord) -> Result<()> {
+        self.publish(self.topic("alerts"), record)
+    }
+
+    /// Mark the monitor online and (re)publish the discovery payloads, on
+    /// every connect since the broker may have lost them.
+    fn announce(&mut self) -> Result<()> {
+        self.published.clear();
+        self.client.try_publish(self.topic("status"), QoS::AtLeastOnce, true, "online")?;
+        if self.settings.home_assistant {
+            for entity in ENTITIES {
+                self.publish(self.discovery_topic(entity), self.discovery_payload(entity))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn node_id(&self) -> String {
+        format!("ccmonitor_{}", self.host)
+    }
+
+    fn discovery_topic(&self, entity: &Entity) -> String {
+        format!("{}/{}/{}/{}/config", self.settings.discovery_prefix, entity.component, self.node_id(), entity.id)
+    }
+
+    fn discovery_payload(&self, entity: &Entity) -> Value {
+        let mut payload = json!({
+            "name": entity.name,
+            "unique_id": format!("{}_{}", self.node_id(), entity.id),
+            "state_topic": self.topic(entity.topic),
+            "value_template": entity.value_template,
+            "availability_topic": self.topic("status"),
+            "icon": entity.icon,
+            "device": {
+                "identifiers": [self.node_id()],
+                "name": format!("Claude Code Monitor ({})", self.host),
+                "manufacturer": "cc-monitor-rs",
+                "sw_version": env!("CARGO_PKG_VERSION"),
+            },
+        });
+        if let Some(unit) = entity.unit {
+            payload["unit_of_measurement"] = unit.into();
+        }
+        if let Some(device_class) = entity.device_class {
+            payload["device_class"] = device_class.into();
+        }
+        if entity.topic == "alerts" {
+            payload["json_attributes_topic"] = self.topic("alerts").into();
+        }
+        payload
+    }
+}
+
+/// The host name, reduced to characters that are safe in topics and entity ids.
+fn host_name() -> String {
+    let host = hostname::get()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "localhost".to_string());
+    host.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+/// Connect to the broker and publish every data update and notification
+/// until the worker stops. Reconnects on its own when the broker goes away.
+pub async fn run(settings: MqttSettings) -> Result<()> {
+    let (mut publisher, mut eventloop) = Publisher::new(settings, host_name());
+    let mut updates = subscribe_monitor_updates();
+    let mut connected = false;
+    let mut outage_logged = false;
+    // Polling again reconnects, so after a failure wait here instead, where
+    // the worker stopping still ends the loop
+    let reconnect = tokio::time::sleep(Duration::ZERO);
+    tokio::pin!(reconnect);
+    let mut waiting = false;
+
+    loop {
+        let result = tokio::select! {
+            () = &mut reconnect, if waiting => {
+                waiting = false;
+                Ok(())
+            }
+            event = eventloop.poll(), if !waiting => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    log!("Connected to MQTT broker {}:{}", publisher.settings.host, publisher.settings.port);
+                    connected = true;
+                    outage_logged = false;
+                    publisher.announce().and_then(|_| match latest_monitor_data() {
+                        Some(data) => publisher.publish_data(&data),
+                        None => Ok(()),
+                    })
+                }
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    // Once per outage rather than on every retry
+                    if !outage_logged {
+                        log!("MQTT connection to {}:{} failed: {}", publisher.settings.host, publisher.settings.port, e);
+                        outage_logged = true;
+                    }
+                    connected = false;
+                    waiting = true;
+                    reconnect.as_mut().reset(tokio::time::Instant::now() + RECONNECT_DELAY);
+                    Ok(())
+                }
+            },
+            // While disconnected the latest data is published on reconnect instead
+            update = updates.recv() => match update {
+                Ok(MonitorUpdate::DataUpdate(data)) if connected => publisher.publish_data(&data),
+                Ok(MonitorUpdate::NotificationRecorded(record)) if connected => publisher.publish_alert(&record),
+                Ok(_) | Err(RecvError::Lagged(_)) => Ok(()),
+                Err(RecvError::Closed) => break,
+            },
+        };
+        if let Err(e) = result {
+            log!("Failed to queue MQTT message: {:#}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc;
+
+    /// Read one MQTT packet: the first header byte and the body.
+    fn read_packet(stream: &mut TcpStream) -> std::io::Result<(u8, Vec<u8>)> {
+        let mut byte = [0u8];
+        stream.read_exact(&mut byte)?;
+        let header = byte[0];
+        let (mut length, mut shift) = (0usize, 0);
+        loop {
+            stream.read_exact(&mut byte)?;
+            length |= ((byte[0] & 0x7f) as usize) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body)?;
+        Ok((header, body))
+    }
+
+    /// A broker that accepts one client and hands over every message it
+    /// publishes as (topic, payload).
+    fn serve() -> (u16, mpsc::UnboundedReceiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            while let Ok((header, body)) = read_packet(&mut stream) {
+                match header >> 4 {
+                    // CONNECT
+                    1 => stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap(),
+                    // PUBLISH
+                    3 => {
+                        let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                        let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                        let mut offset = 2 + topic_len;
+                        if (header >> 1) & 0x03 > 0 {
+                            stream.write_all(&[0x40, 0x02, body[offset], body[offset + 1]]).unwrap();
+                            offset += 2;
+                        }
+                        let _ = sender.send((topic, body[offset..].to_vec()));
+                    }
+                    // PINGREQ
+                    12 => stream.write_all(&[0xd0, 0x00]).unwrap(),
+                    _ => {}
+                }
+            }
+        });
+        (port, receiver)
+    }
+
+    #[tokio::test]
+    async fn publishes_state_to_a_local_broker() -> Result<()> {
+        let (port, mut messages) = serve();
+        let settings = MqttSettings { host: "127.0.0.1".to_string(), port, ..MqttSettings::default() };
+        let (mut publisher, mut eventloop) = Publisher::new(settings, "test-host".to_string());
+        while !matches!(eventloop.poll().await?, Event::Incoming(Packet::ConnAck(_))) {}
+
+        let mut data = MonitorData { plan_token_limit: Some(1000), ..MonitorData::default() };
+        data.ccusage_data.status = "ACTIVE".to_string();
+        data.ccusage_data.tokens_num = 250;
+        data.ccusage_data.reset_time = Some(chrono::Local::now() + chrono::Duration::hours(1));
+        publisher.announce()?;
+        publisher.publish_data(&data)?;
+        // Unchanged state topics are skipped
+        publisher.publish_data(&data)?;
+        tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+
+        let expected = 1 + ENTITIES.len() + 3;
+        let mut received = HashMap::new();
+        while received.len() < expected {
+            let (topic, payload) = tokio::time::timeout(Duration::from_secs(5), messages.recv()).await?.unwrap();
+            assert!(received.insert(topic, payload).is_none(), "topic published twice");
+        }
+        let extra = tokio::time::timeout(Duration::from_millis(200), messages.recv()).await;
+        assert!(extra.is_err(), "unexpected message {:?}", extra);
+        assert_eq!(received["ccmonitor/test-host/status"], b"online");
+        assert!(received.contains_key("homeassistant/sensor/ccmonitor_test-host/tokens/config"));
+        let block: Value = serde_json::from_slice(&received["ccmonitor/test-host/block"])?;
+        assert_eq!(block["tokens"], 250);
+        assert_eq!(block["plan_percent"], 25.0);
+        assert!(block["reset_time"].is_string());
+        assert!(block.get("remaining_secs").is_none());
+        Ok(())
+    }
+}
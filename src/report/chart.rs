@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use super::render::escape;
+
+const WIDTH: f64 = 720.0;
+const HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 60.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 12.0;
+const MARGIN_BOTTOM: f64 = 28.0;
+/// Horizontal grid lines above the baseline.
+const GRID_LINES: usize = 4;
+/// Most day labels under the bars; the others are skipped.
+const MAX_LABELS: usize = 15;
+/// Most entries in a share chart.
+const MAX_ENTRIES: usize = 10;
+const ROW_HEIGHT: f64 = 24.0;
+const NAME_WIDTH: f64 = 200.0;
+
+const ACCENT: &str = "#4a9eff";
+const GRID: &str = "#dddddd";
+const TEXT: &str = "#555555";
+
+fn open(out: &mut String, height: f64) {
+    let _ = write!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}" font-family="sans-serif" font-size="11">"#,
+        w = WIDTH,
+        h = height,
+    );
+}
+
+/// Vertical bars of the cost per day, labelled with the `MM-DD` of each bar.
+pub fn cost_bars(bars: &[(String, f64)]) -> String {
+    let mut svg = String::new();
+    open(&mut svg, HEIGHT);
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let baseline = MARGIN_TOP + plot_height;
+    let max = bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+    let scale = if max > 0.0 { max } else { 1.0 };
+
+    for line in 0..=GRID_LINES {
+        let value = scale * line as f64 / GRID_LINES as f64;
+        let y = baseline - plot_height * line as f64 / GRID_LINES as f64;
+        let _ = write!(
+            svg,
+            r#"<line x1="{x1}" y1="{y:.1}" x2="{x2}" y2="{y:.1}" stroke="{GRID}"/><text x="{tx}" y="{ty:.1}" text-anchor="end" fill="{TEXT}">${value:.2}</text>"#,
+            x1 = MARGIN_LEFT,
+            x2 = WIDTH - MARGIN_RIGHT,
+            tx = MARGIN_LEFT - 6.0,
+            ty = y + 4.0,
+        );
+    }
+
+    if !bars.is_empty() {
+        let slot = plot_width / bars.len() as f64;
+        let bar_width = (slot * 0.7).max(1.0);
+        let label_step = bars.len().div_ceil(MAX_LABELS);
+        for (i, (label, value)) in bars.iter().enumerate() {
+            let height = plot_height * value / scale;
+            let x = MARGIN_LEFT + slot * i as f64 + (slot - bar_width) / 2.0;
+            let _ = write!(
+                svg,
+                r#"<rect x="{x:.1}" y="{y:.1}" width="{bar_width:.1}" height="{height:.1}" fill="{ACCENT}"><title>{title} ${value:.2}</title></rect>"#,
+                y = baseline - height,
+                title = escape(label),
+            );
+            if i % label_step == 0 {
+                let _ = write!(
+                    svg,
+                    r#"<text x="{cx:.1}" y="{ty}" text-anchor="middle" fill="{TEXT}">{label}</text>"#,
+                    cx = x + bar_width / 2.0,
+                    ty = baseline + 16.0,
+                    label = escape(label),
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Horizontal bars of the cost per model or project, most expensive first.
+pub fn share_bars(entries: &[(String, f64, u64)]) -> String {
+    let entries = &entries[..entries.len().min(MAX_ENTRIES)];
+    let height = MARGIN_TOP * 2.0 + ROW_HEIGHT * entries.len() as f64;
+    let mut svg = String::new();
+    open(&mut svg, height);
+
+    // Leave room for the cost printed after the longest bar
+    let bar_area = WIDTH - NAME_WIDTH - MARGIN_RIGHT - 70.0;
+    let max = entries.iter().map(|(_, cost, _)| *cost).fold(0.0, f64::max);
+    let scale = if max > 0.0 { max } else { 1.0 };
+
+    for (i, (name, cost, _)) in entries.iter().enumerate() {
+        let y = MARGIN_TOP + ROW_HEIGHT * i as f64;
+        let width = bar_area * cost / scale;
+        let _ = write!(
+            svg,
+            r#"<text x="{nx}" y="{ty:.1}" text-anchor="end" fill="{TEXT}">{name}</text><rect x="{bx}" y="{by:.1}" width="{width:.1}" height="{bh:.1}" fill="{ACCENT}"/><text x="{cx:.1}" y="{ty:.1}" fill="{TEXT}">${cost:.2}</text>"#,
+            nx = NAME_WIDTH - 8.0,
+            ty = y + ROW_HEIGHT / 2.0 + 4.0,
+            name = escape(&shorten(name, 32)),
+            bx = NAME_WIDTH,
+            by = y + 4.0,
+            bh = ROW_HEIGHT - 8.0,
+            cx = NAME_WIDTH + width + 6.0,
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Keeps the end of long names, which for project paths is the telling part.
+fn shorten(name: &str, max_chars: usize) -> String {
+    let count = name.chars().count();
+    if count <= max_chars {
+        return name.to_string();
+    }
+    let tail: String = name.chars().skip(count - (max_chars - 1)).collect();
+    format!("…{}", tail)
+}
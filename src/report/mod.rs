@@ -0,0 +1,319 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::budget::{budget_statuses, BudgetSettings, BudgetStatus};
+use crate::export::{self, BlockRow, ExportSettings, Grouping, PeriodRow};
+use crate::monitor::{UsageHistory, UsageSummary};
+
+mod chart;
+mod render;
+
+/// Number of most expensive blocks listed in a report.
+const PEAK_BLOCKS: usize = 5;
+
+fn default_report_time() -> String {
+    "08:00".to_string()
+}
+
+fn default_weekly_day() -> Weekday {
+    Weekday::Mon
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Markdown,
+    #[default]
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => anyhow::bail!("Unknown format `{}`, expected `markdown` or `html`", s),
+        }
+    }
+}
+
+/// Where reports go and when they are written, stored in the preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportSettings {
+    /// Folder the reports are written to; the export folder when unset
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    #[serde(default)]
+    pub format: ReportFormat,
+    /// Write the previous week's report on `weekly_day` at `time`
+    #[serde(default)]
+    pub weekly: bool,
+    /// Write the previous month's report on the first day of the month at `time`
+    #[serde(default)]
+    pub monthly: bool,
+    /// Time as `HH:MM`
+    #[serde(default = "default_report_time")]
+    pub time: String,
+    #[serde(default = "default_weekly_day")]
+    pub weekly_day: Weekday,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            format: ReportFormat::default(),
+            weekly: false,
+            monthly: false,
+            time: default_report_time(),
+            weekly_day: default_weekly_day(),
+        }
+    }
+}
+
+impl ReportSettings {
+    pub fn directory(&self, export: &ExportSettings) -> PathBuf {
+        self.directory.clone().unwrap_or_else(|| export.directory())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Weekly,
+    Monthly,
+}
+
+impl ReportPeriod {
+    /// The completed period covered by a report written on `today`.
+    pub fn range(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            ReportPeriod::Weekly => (today - Days::new(7), today - Days::new(1)),
+            ReportPeriod::Monthly => {
+                let first = today.with_day(1).unwrap_or(today);
+                (first - Months::new(1), first - Days::new(1))
+            }
+        }
+    }
+}
+
+/// Decides when the scheduled reports are due. Reports whose time has already
+/// passed when the app starts are skipped.
+#[derive(Debug, Clone)]
+pub struct ReportSchedule {
+    settings: ReportSettings,
+    last_weekly: Option<NaiveDate>,
+    last_monthly: Option<NaiveDate>,
+}
+
+impl ReportSchedule {
+    pub fn new(settings: ReportSettings, now: DateTime<Local>) -> Self {
+        let mut schedule = Self {
+            settings,
+            last_weekly: None,
+            last_monthly: None,
+        };
+        schedule.due(now);
+        schedule
+    }
+
+    pub fn configure(&mut self, settings: ReportSettings) {
+        self.settings = settings;
+    }
+
+    pub fn format(&self) -> ReportFormat {
+        self.settings.format
+    }
+
+    /// The reports that are due now; each is returned once per day.
+    pub fn due(&mut self, now: DateTime<Local>) -> Vec<ReportPeriod> {
+        let Ok(time) = NaiveTime::parse_from_str(self.settings.time.trim(), "%H:%M") else {
+            return Vec::new();
+        };
+        let today = now.date_naive();
+        if now.time() < time {
+            return Vec::new();
+        }
+
+        let mut due = Vec::new();
+        if self.settings.weekly && today.weekday() == self.settings.weekly_day && self.last_weekly != Some(today) {
+            self.last_weekly = Some(today);
+            due.push(ReportPeriod::Weekly);
+        }
+        if self.settings.monthly && today.day() == 1 && self.last_monthly != Some(today) {
+            self.last_monthly = Some(today);
+            due.push(ReportPeriod::Monthly);
+        }
+        due
+    }
+}
+
+/// Everything shown in a report, gathered from the usage history.
+#[derive(Debug)]
+pub struct Report {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub generated: DateTime<Local>,
+    pub summary: UsageSummary,
+    /// One row per day of the range, including days without usage
+    pub days: Vec<PeriodRow>,
+    /// The most expensive blocks, most expensive first
+    pub peak_blocks: Vec<BlockRow>,
+    /// Budgets of the current period, as of `generated`
+    pub budgets: Vec<BudgetStatus>,
+}
+
+impl Report {
+    pub fn new(history: &UsageHistory, budgets: Vec<BudgetStatus>, from: NaiveDate, to: NaiveDate) -> Self {
+        let mut peak_blocks = export::block_rows(history, from, to);
+        peak_blocks.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+        peak_blocks.truncate(PEAK_BLOCKS);
+        Self {
+            from,
+            to,
+            generated: Local::now(),
+            summary: history.summarize(from, to),
+            days: export::period_rows(history, Grouping::Day, from, to),
+            peak_blocks,
+            budgets,
+        }
+    }
+
+    /// Days with any usage.
+    pub fn active_days(&self) -> usize {
+        self.days.iter().map(|day| day.active_days).sum()
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => render::markdown(self),
+            ReportFormat::Html => render::html(self),
+        }
+    }
+
+    /// Render the report into `path`, creating its folder.
+    pub fn write(&self, format: ReportFormat, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.render(format))?;
+        Ok(())
+    }
+}
+
+/// The file name of a report, e.g. `cc-monitor-report-2024-05-01-2024-05-31.html`.
+pub fn file_name(format: ReportFormat, from: NaiveDate, to: NaiveDate) -> String {
+    format!("cc-monitor-report-{}-{}.{}", from, to, format.extension())
+}
+
+/// A report of `history` for the days from `from` to `to`, with the budgets
+/// computed from the same history.
+pub fn generate(history: &UsageHistory, budgets: &BudgetSettings, from: NaiveDate, to: NaiveDate) -> Report {
+    let budgets = budget_statuses(budgets, &history.daily_costs());
+    Report::new(history, budgets, from, to)
+}
+
+/// Write a report of the stored history for the days from `from` to `to`
+/// to `path`.
+pub fn write(budgets: &BudgetSettings, format: ReportFormat, from: NaiveDate, to: NaiveDate, path: &Path) -> Result<()> {
+    let history = UsageHistory::load()?;
+    generate(&history, budgets, from, to).write(format, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use super::*;
+    use crate::monitor::history::{parse_daily_json, BlockUsage, TokenCounts};
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn at(day: &str, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.from_local_datetime(&date(day).and_hms_opt(hour, minute, 0).unwrap()).unwrap()
+    }
+
+    fn settings() -> ReportSettings {
+        ReportSettings {
+            weekly: true,
+            monthly: true,
+            ..ReportSettings::default()
+        }
+    }
+
+    #[test]
+    fn monthly_range_is_the_previous_month() {
+        assert_eq!(ReportPeriod::Monthly.range(date("2024-03-01")), (date("2024-02-01"), date("2024-02-29")));
+        assert_eq!(ReportPeriod::Monthly.range(date("2024-01-01")), (date("2023-12-01"), date("2023-12-31")));
+    }
+
+    #[test]
+    fn weekly_range_is_the_previous_seven_days() {
+        // 2024-05-20 is a Monday
+        assert_eq!(ReportPeriod::Weekly.range(date("2024-05-20")), (date("2024-05-13"), date("2024-05-19")));
+    }
+
+    #[test]
+    fn reports_are_due_once_at_their_time() {
+        // 2024-04-01 is a Monday and the first of the month
+        let mut schedule = ReportSchedule::new(settings(), at("2024-04-01", 7, 0));
+        assert!(schedule.due(at("2024-04-01", 7, 59)).is_empty());
+        assert_eq!(schedule.due(at("2024-04-01", 8, 0)), vec![ReportPeriod::Weekly, ReportPeriod::Monthly]);
+        assert!(schedule.due(at("2024-04-01", 9, 0)).is_empty());
+        assert!(schedule.due(at("2024-04-02", 8, 0)).is_empty());
+        assert_eq!(schedule.due(at("2024-04-08", 8, 30)), vec![ReportPeriod::Weekly]);
+        assert_eq!(schedule.due(at("2024-05-01", 8, 0)), vec![ReportPeriod::Monthly]);
+    }
+
+    #[test]
+    fn reports_already_due_at_start_are_skipped() {
+        let mut schedule = ReportSchedule::new(settings(), at("2024-04-01", 10, 0));
+        assert!(schedule.due(at("2024-04-01", 11, 0)).is_empty());
+
+        schedule.configure(ReportSettings { time: "25:00".to_string(), ..settings() });
+        assert!(schedule.due(at("2024-04-08", 8, 0)).is_empty());
+    }
+
+    #[test]
+    fn report_lists_every_day_and_the_peak_blocks() {
+        let mut history = UsageHistory::default();
+        history.merge_days(parse_daily_json(r#"{"daily": [
+            {"date": "2024-05-14", "inputTokens": 100, "outputTokens": 50, "totalCost": 1.5},
+            {"date": "2024-05-20", "inputTokens": 100, "outputTokens": 50, "totalCost": 9.0}
+        ]}"#).unwrap());
+        let start = at("2024-05-14", 0, 0);
+        history.merge_blocks((0..7).map(|i| BlockUsage {
+            start: start + Duration::hours(5 * i),
+            end: start + Duration::hours(5 * i + 5),
+            actual_end: None,
+            is_active: false,
+            tokens: TokenCounts { input: 10, ..TokenCounts::default() },
+            cost: [0.5, 3.0, 0.1, 2.0, 1.0, 4.0, 0.2][i as usize],
+            models: vec!["sonnet".to_string()],
+            burn_rate_tokens_per_min: None,
+            imported: false,
+        }).collect());
+
+        let report = Report::new(&history, Vec::new(), date("2024-05-13"), date("2024-05-19"));
+        assert_eq!(report.days.len(), 7);
+        assert_eq!(report.active_days(), 1);
+        assert_eq!(report.summary.cost, 1.5);
+        let costs: Vec<f64> = report.peak_blocks.iter().map(|block| block.cost_usd).collect();
+        assert_eq!(costs, vec![4.0, 3.0, 2.0, 1.0, 0.5]);
+    }
+}
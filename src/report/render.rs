@@ -0,0 +1,253 @@
+use std::fmt::Write;
+use base64::Engine;
+
+use super::{chart, Report};
+use crate::utils::notifications::format_number;
+use crate::i18n::{self, keys};
+
+const REPORT_CSS: &str = include_str!("report.css");
+
+/// Escapes text for HTML and SVG.
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Keeps a table cell from ending early in Markdown.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn tokens(count: u64) -> String {
+    format_number(count as i64)
+}
+
+fn percent(part: f64, total: f64) -> String {
+    if total > 0.0 {
+        format!("{:.1}%", part / total * 100.0)
+    } else {
+        "--".to_string()
+    }
+}
+
+/// A section of the report with its table, kept apart from the output format.
+struct Table {
+    title: String,
+    chart: Option<String>,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+fn summary_rows(report: &Report) -> Vec<Vec<String>> {
+    let summary = &report.summary;
+    let active_days = report.active_days();
+    let mut rows = vec![
+        vec![i18n::get(keys::EMAIL_TOTAL_COST), format!("${:.2}", summary.cost)],
+        vec![i18n::get(keys::EMAIL_TOTAL_TOKENS), tokens(summary.tokens.total())],
+        vec![i18n::get(keys::EMAIL_BLOCKS), summary.blocks.to_string()],
+        vec![i18n::get(keys::REPORT_ACTIVE_DAYS), active_days.to_string()],
+    ];
+    if active_days > 0 {
+        rows.push(vec![
+            i18n::get(keys::REPORT_AVERAGE_COST),
+            format!("${:.2}", summary.cost / active_days as f64),
+        ]);
+    }
+    if let Some(rate) = summary.peak_burn_rate {
+        rows.push(vec![i18n::get(keys::EMAIL_PEAK_BURN_RATE), format!("{:.0}/min", rate)]);
+    }
+    rows
+}
+
+/// The sections after the summary, skipping breakdowns without entries.
+fn tables(report: &Report) -> Vec<Table> {
+    let summary = &report.summary;
+    let mut tables = Vec::new();
+
+    let bars: Vec<(String, f64)> = report.days.iter()
+        .map(|day| (day.start.format("%m-%d").to_string(), day.cost_usd))
+        .collect();
+    tables.push(Table {
+        title: i18n::get(keys::REPORT_DAILY_COST),
+        chart: Some(chart::cost_bars(&bars)),
+        columns: vec![
+            i18n::get(keys::REPORT_DATE),
+            i18n::get(keys::REPORT_COST),
+            i18n::get(keys::REPORT_TOKENS),
+            i18n::get(keys::REPORT_BLOCKS),
+        ],
+        rows: report.days.iter()
+            .map(|day| vec![
+                day.period.clone(),
+                format!("${:.2}", day.cost_usd),
+                tokens(day.total_tokens),
+                day.blocks.to_string(),
+            ])
+            .collect(),
+    });
+
+    let mut breakdown = |title: &str, name: &str, entries: &[(String, f64, u64)]| {
+        if entries.is_empty() {
+            return;
+        }
+        tables.push(Table {
+            title: i18n::get(title),
+            chart: Some(chart::share_bars(entries)),
+            columns: vec![
+                i18n::get(name),
+                i18n::get(keys::REPORT_COST),
+                i18n::get(keys::REPORT_SHARE),
+                i18n::get(keys::REPORT_TOKENS),
+            ],
+            rows: entries.iter()
+                .map(|(name, cost, count)| vec![
+                    name.clone(),
+                    format!("${:.2}", cost),
+                    percent(*cost, summary.cost),
+                    tokens(*count),
+                ])
+                .collect(),
+        });
+    };
+    breakdown(keys::REPORT_MODELS, keys::REPORT_MODEL, &summary.models);
+    breakdown(keys::REPORT_PROJECTS, keys::REPORT_PROJECT, &summary.projects);
+
+    if !report.peak_blocks.is_empty() {
+        tables.push(Table {
+            title: i18n::get(keys::REPORT_PEAK_BLOCKS),
+            chart: None,
+            columns: vec![
+                i18n::get(keys::REPORT_START),
+                i18n::get(keys::REPORT_MODELS_USED),
+                i18n::get(keys::REPORT_COST),
+                i18n::get(keys::REPORT_TOKENS),
+                i18n::get(keys::REPORT_TOKENS_PER_MINUTE),
+            ],
+            rows: report.peak_blocks.iter()
+                .map(|block| vec![
+                    block.start.format("%Y-%m-%d %H:%M").to_string(),
+                    block.models.replace(';', ", "),
+                    format!("${:.2}", block.cost_usd),
+                    tokens(block.total_tokens),
+                    block.tokens_per_minute.map_or("--".to_string(), |rate| format!("{:.0}", rate)),
+                ])
+                .collect(),
+        });
+    }
+
+    if !report.budgets.is_empty() {
+        tables.push(Table {
+            title: i18n::get(keys::EMAIL_BUDGETS),
+            chart: None,
+            columns: vec![
+                i18n::get(keys::REPORT_BUDGET),
+                i18n::get(keys::REPORT_SINCE),
+                i18n::get(keys::REPORT_SPENT),
+                i18n::get(keys::REPORT_LIMIT),
+                i18n::get(keys::REPORT_USED),
+            ],
+            rows: report.budgets.iter()
+                .map(|status| vec![
+                    status.period.label(),
+                    status.period_start.to_string(),
+                    format!("${:.2}", status.spent),
+                    format!("${:.2}", status.limit),
+                    format!("{:.0}%", status.percent()),
+                ])
+                .collect(),
+        });
+    }
+
+    tables
+}
+
+fn subtitle(report: &Report) -> String {
+    format!(
+        "{} · {}",
+        i18n::format(keys::REPORT_PERIOD, &[&report.from.to_string(), &report.to.to_string()]),
+        i18n::format(keys::REPORT_GENERATED, &[&report.generated.format("%Y-%m-%d %H:%M").to_string()]),
+    )
+}
+
+fn markdown_table(out: &mut String, columns: &[String], rows: &[Vec<String>]) {
+    let _ = writeln!(out, "| {} |", columns.iter().map(|column| cell(column)).collect::<Vec<_>>().join(" | "));
+    let _ = writeln!(out, "|{}", "---|".repeat(columns.len()));
+    for row in rows {
+        let _ = writeln!(out, "| {} |", row.iter().map(|value| cell(value)).collect::<Vec<_>>().join(" | "));
+    }
+    out.push('\n');
+}
+
+/// Markdown with the charts embedded as SVG data URIs.
+pub fn markdown(report: &Report) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", i18n::get(keys::REPORT_TITLE));
+    let _ = writeln!(out, "{}\n", subtitle(report));
+
+    let _ = writeln!(out, "## {}\n", i18n::get(keys::REPORT_SUMMARY));
+    for row in summary_rows(report) {
+        let _ = writeln!(out, "- {}: **{}**", row[0], row[1]);
+    }
+    out.push('\n');
+
+    for table in tables(report) {
+        let _ = writeln!(out, "## {}\n", table.title);
+        if let Some(svg) = &table.chart {
+            let data = base64::engine::general_purpose::STANDARD.encode(svg);
+            let _ = writeln!(out, "![{}](data:image/svg+xml;base64,{})\n", table.title, data);
+        }
+        markdown_table(&mut out, &table.columns, &table.rows);
+    }
+    out
+}
+
+fn html_table(out: &mut String, columns: Option<&[String]>, rows: &[Vec<String>]) {
+    out.push_str("<table>\n");
+    if let Some(columns) = columns {
+        out.push_str("<thead><tr>");
+        for column in columns {
+            let _ = write!(out, "<th>{}</th>", escape(column));
+        }
+        out.push_str("</tr></thead>\n");
+    }
+    out.push_str("<tbody>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for value in row {
+            let _ = write!(out, "<td>{}</td>", escape(value));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n");
+}
+
+/// A single HTML page with the styles and charts inline.
+pub fn html(report: &Report) -> String {
+    let title = i18n::get(keys::REPORT_TITLE);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{} {} – {}</title>\n<style>\n{}</style>\n</head>\n<body>",
+        i18n::get_language().code(),
+        escape(&title),
+        report.from,
+        report.to,
+        REPORT_CSS,
+    );
+    let _ = writeln!(out, "<h1>{}</h1>\n<p class=\"subtitle\">{}</p>", escape(&title), escape(&subtitle(report)));
+
+    let _ = writeln!(out, "<h2>{}</h2>", escape(&i18n::get(keys::REPORT_SUMMARY)));
+    html_table(&mut out, None, &summary_rows(report));
+
+    for table in tables(report) {
+        let _ = writeln!(out, "<h2>{}</h2>", escape(&table.title));
+        if let Some(svg) = &table.chart {
+            let _ = writeln!(out, "<figure>{}</figure>", svg);
+        }
+        html_table(&mut out, Some(&table.columns), &table.rows);
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::{dashboard, ApiSettings};
+use crate::alerts::{AlertRule, DigestSchedule};
+use crate::background::{submit_monitor_request, MonitorRequest};
+use crate::budget::BudgetSettings;
+use crate::monitor::history::{BlockUsage, DayUsage};
+use crate::monitor::{MonitorSnapshot, UsageHistory};
+use crate::ui_updates::{latest_monitor_data, subscribe_monitor_updates, MonitorUpdate, RequestAck};
+use crate::utils::notifications::{self, NotificationPolicySettings, NotificationRecord, SinkKind};
+use crate::utils::preferences::{MonitorProfile, Preferences};
+use crate::monitor_log as log;
+
+/// Days returned by `/api/history` when no range is given.
+const DEFAULT_HISTORY_DAYS: i64 = 30;
+const TOKEN_LENGTH: usize = 32;
+/// Replaces secrets in the preferences returned by the API.
+const REDACTED: &str = "********";
+
+#[derive(Clone)]
+struct ApiState {
+    token: Arc<str>,
+}
+
+/// An error response with a JSON body.
+struct ApiError(StatusCode, String);
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// One message on the WebSocket stream, one per `MonitorUpdate`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    Data { snapshot: Box<MonitorSnapshot> },
+    Status { message: String },
+    Error { message: String },
+    Ack(RequestAck),
+    Notification(NotificationRecord),
+}
+
+impl StreamEvent {
+    fn new(update: MonitorUpdate) -> Self {
+        match update {
+            MonitorUpdate::DataUpdate(data) => StreamEvent::Data { snapshot: Box::new(MonitorSnapshot::new(&data, data.plan_token_limit)) },
+            MonitorUpdate::StatusMessage(message) => StreamEvent::Status { message },
+            MonitorUpdate::ErrorMessage(message) => StreamEvent::Error { message },
+            MonitorUpdate::RequestAck(ack) => StreamEvent::Ack(ack),
+            MonitorUpdate::NotificationRecorded(record) => StreamEvent::Notification(record),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+#[derive(Serialize)]
+struct HistoryResponse {
+    from: NaiveDate,
+    to: NaiveDate,
+    days: Vec<DayUsage>,
+    blocks: Vec<BlockUsage>,
+}
+
+#[derive(Deserialize)]
+struct AlertsQuery {
+    #[serde(default)]
+    unread: bool,
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Tells a missing field apart from `null`, which clears the value.
+fn present<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// The preferences that can be changed over the API; fields that are left
+/// out keep their value. Sinks, exporters, servers and folders are not
+/// included, as they decide which files the monitor writes and which hosts
+/// it contacts.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PreferencesUpdate {
+    language: Option<String>,
+    profiles: Option<HashMap<String, MonitorProfile>>,
+    active_profile: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    plan_token_limit: Option<Option<u64>>,
+    alert_rules: Option<Vec<AlertRule>>,
+    reset_marks_minutes: Option<Vec<u32>>,
+    notify_window_available: Option<bool>,
+    digest_schedule: Option<DigestSchedule>,
+    budgets: Option<BudgetSettings>,
+    notification_policy: Option<NotificationPolicySettings>,
+}
+
+impl PreferencesUpdate {
+    fn apply(self, prefs: &mut Preferences) -> Result<()> {
+        if let Some(language) = self.language {
+            prefs.language = language;
+        }
+        if let Some(profiles) = self.profiles {
+            prefs.profiles = profiles;
+        }
+        let active_profile = self.active_profile.unwrap_or_else(|| prefs.active_profile.clone());
+        prefs.switch_profile(&active_profile)?;
+        if let Some(plan_token_limit) = self.plan_token_limit {
+            prefs.plan_token_limit = plan_token_limit;
+        }
+        if let Some(alert_rules) = self.alert_rules {
+            prefs.alert_rules = alert_rules;
+        }
+        if let Some(reset_marks_minutes) = self.reset_marks_minutes {
+            prefs.reset_marks_minutes = reset_marks_minutes;
+        }
+        if let Some(notify_window_available) = self.notify_window_available {
+            prefs.notify_window_available = notify_window_available;
+        }
+        if let Some(digest_schedule) = self.digest_schedule {
+            prefs.digest_schedule = digest_schedule;
+        }
+        if let Some(budgets) = self.budgets {
+            prefs.budgets = budgets;
+        }
+        if let Some(notification_policy) = self.notification_policy {
+            prefs.notification_policy = notification_policy;
+        }
+        Ok(())
+    }
+}
+
+/// Hide the API token, passwords, webhook URLs and exporter headers.
+fn redact(prefs: &mut Preferences) {
+    prefs.api.token = None;
+    if prefs.mqtt.password.is_some() {
+        prefs.mqtt.password = Some(REDACTED.to_string());
+    }
+    for value in prefs.telemetry.headers.values_mut() {
+        *value = REDACTED.to_string();
+    }
+    for sink in prefs.notification_sinks.iter_mut() {
+        match &mut sink.kind {
+            SinkKind::Webhook(config) => config.url = REDACTED.to_string(),
+            SinkKind::Email(config) if config.password.is_some() => config.password = Some(REDACTED.to_string()),
+            _ => {}
+        }
+    }
+}
+
+async fn snapshot() -> Result<Json<MonitorSnapshot>, ApiError> {
+    let data = latest_monitor_data().ok_or_else(|| ApiError(StatusCode::SERVICE_UNAVAILABLE, "No data yet".to_string()))?;
+    Ok(Json(MonitorSnapshot::new(&data, data.plan_token_limit)))
+}
+
+async fn history(Query(query): Query<HistoryQuery>) -> Result<Json<HistoryResponse>, ApiError> {
+    let to = query.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = query.from.unwrap_or(to - chrono::Duration::days(DEFAULT_HISTORY_DAYS - 1));
+    if from > to {
+        return Err(ApiError(StatusCode::BAD_REQUEST, "`from` is after `to`".to_string()));
+    }
+    let (days, blocks) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let history = UsageHistory::load()?;
+        Ok((history.days(from, to).collect(), history.blocks(from, to).cloned().collect()))
+    }).await.map_err(anyhow::Error::from)??;
+    Ok(Json(HistoryResponse { from, to, days, blocks }))
+}
+
+async fn alerts(Query(query): Query<AlertsQuery>) -> Json<Vec<NotificationRecord>> {
+    let mut records = notifications::recent_notifications();
+    if query.unread {
+        records.retain(|record| !record.read);
+    }
+    Json(records)
+}
+
+async fn mark_alerts_read() -> StatusCode {
+    let _ = tokio::task::spawn_blocking(notifications::mark_all_read).await;
+    StatusCode::NO_CONTENT
+}
+
+/// The preferences, without secrets.
+async fn get_preferences() -> Result<Json<Preferences>, ApiError> {
+    let mut prefs = Preferences::load()?;
+    redact(&mut prefs);
+    Ok(Json(prefs))
+}
+
+/// Change the fields of `PreferencesUpdate` and apply the preferences.
+async fn put_preferences(Json(update): Json<PreferencesUpdate>) -> Result<StatusCode, ApiError> {
+    let mut prefs = Preferences::load()?;
+    update.apply(&mut prefs).map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+    prefs.save()?;
+    submit_monitor_request(MonitorRequest::ReloadPreferences)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn websocket(upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(stream_updates)
+}
+
+/// Push the latest snapshot, then every monitor update until the client disconnects.
+async fn stream_updates(mut socket: WebSocket) {
+    let mut updates = subscribe_monitor_updates();
+
+    if let Some(data) = latest_monitor_data()
+        && send_event(&mut socket, StreamEvent::new(MonitorUpdate::DataUpdate(Box::new(data)))).await.is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    if send_event(&mut socket, StreamEvent::new(update)).await.is_err() {
+                        break;
+                    }
+                }
+                // The client is too slow, skip to the newest updates
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn send_event(socket: &mut WebSocket, event: StreamEvent) -> Result<()> {
+    let text = serde_json::to_string(&event)?;
+    socket.send(Message::text(text)).await?;
+    Ok(())
+}
+
+/// The token of `Authorization: Bearer <token>`, or of `?token=<token>` for
+/// browsers that can't set headers on a WebSocket.
+fn request_token(request: &Request) -> Option<String> {
+    let header = request.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = header {
+        return Some(token.to_string());
+    }
+    // Percent-decoded, as browsers encode the token in the URL
+    Query::<TokenQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(query)| query.token)
+}
+
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    match request_token(&request) {
+        Some(token) if constant_time_eq(token.as_bytes(), state.token.as_bytes()) => next.run(request).await,
+        _ => ApiError(StatusCode::UNAUTHORIZED, "Invalid or missing token".to_string()).into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// The configured token, or a new one that is saved to the preferences.
+fn api_token(settings: &ApiSettings) -> Result<String> {
+    if let Some(token) = settings.token.as_ref().filter(|token| !token.is_empty()) {
+        return Ok(token.clone());
+    }
+    let token: String = (0..TOKEN_LENGTH).map(|_| fastrand::alphanumeric()).collect();
+    let mut prefs = Preferences::load()?;
+    prefs.api.token = Some(token.clone());
+    prefs.save()?;
+    log!("Generated an API token, see `api.token` in the preferences");
+    Ok(token)
+}
+
+/// Serve the REST API and WebSocket stream until the runtime shuts down.
+pub async fn serve(settings: ApiSettings) -> Result<()> {
+    let state = ApiState {
+        token: api_token(&settings)?.into(),
+    };
+
+    let listener = tokio::net::TcpListener::bind(&settings.listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", settings.listen))?;
+    log!("Serving the API on http://{}/api", listener.local_addr()?);
+
+    let mut app = Router::new()
+        .route("/api/snapshot", get(snapshot))
+        .route("/api/history", get(history))
+        .route("/api/alerts", get(alerts))
+        .route("/api/alerts/read", post(mark_alerts_read))
+        .route("/api/preferences", get(get_preferences).put(put_preferences))
+        .route("/api/ws", get(websocket))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+    if settings.dashboard {
+        log!("Serving the dashboard on http://{}/", listener.local_addr()?);
+        app = app.merge(dashboard::router());
+    }
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use super::*;
+
+    fn preferences() -> Preferences {
+        serde_json::from_value(json!({
+            "language": "en",
+            "plan_token_limit": 1000,
+            "api": { "token": "secret-token" },
+            "mqtt": { "password": "mqtt-password" },
+            "telemetry": { "headers": { "x-api-key": "otlp-key" } },
+            "notification_sinks": [
+                { "type": "webhook", "url": "https://hooks.example.com/T000/secret" },
+                { "type": "email", "server": "smtp.example.com", "password": "smtp-password",
+                  "from": "monitor@example.com", "recipients": ["lead@example.com"] },
+                { "type": "log_file", "path": "/tmp/notifications.log" }
+            ]
+        })).unwrap()
+    }
+
+    #[test]
+    fn secrets_are_redacted() {
+        let mut prefs = preferences();
+        redact(&mut prefs);
+
+        let text = serde_json::to_string(&prefs).unwrap();
+        for secret in ["secret-token", "mqtt-password", "otlp-key", "hooks.example.com", "smtp-password"] {
+            assert!(!text.contains(secret), "{} was not redacted", secret);
+        }
+        assert!(text.contains("/tmp/notifications.log"));
+    }
+
+    #[test]
+    fn update_changes_only_given_fields() {
+        let mut prefs = preferences();
+        let update: PreferencesUpdate = serde_json::from_value(json!({
+            "language": "ja",
+            "active_profile": "low-power",
+            "budgets": { "daily": 20.0 }
+        })).unwrap();
+        update.apply(&mut prefs).unwrap();
+
+        assert_eq!(prefs.language, "ja");
+        assert_eq!(prefs.active_profile, "low-power");
+        assert_eq!(prefs.budgets.daily, Some(20.0));
+        assert_eq!(prefs.plan_token_limit, Some(1000));
+        assert_eq!(prefs.notification_sinks.len(), 3);
+
+        let clear: PreferencesUpdate = serde_json::from_value(json!({ "plan_token_limit": null })).unwrap();
+        clear.apply(&mut prefs).unwrap();
+        assert_eq!(prefs.plan_token_limit, None);
+    }
+
+    #[test]
+    fn update_rejects_other_fields_and_unknown_profiles() {
+        for field in ["notification_sinks", "export", "report", "mqtt", "telemetry", "api", "metrics"] {
+            let update = serde_json::from_value::<PreferencesUpdate>(json!({ field: {} }));
+            assert!(update.is_err(), "{} can be changed", field);
+        }
+
+        let update: PreferencesUpdate = serde_json::from_value(json!({ "active_profile": "missing" })).unwrap();
+        assert!(update.apply(&mut preferences()).is_err());
+    }
+
+    #[test]
+    fn query_token_is_percent_decoded() {
+        let request = Request::builder().uri("/api/ws?from=2024-05-01&token=a%2Bb%20c").body(Body::empty()).unwrap();
+        assert_eq!(request_token(&request).as_deref(), Some("a+b c"));
+
+        let request = Request::builder()
+            .uri("/api/ws?token=other")
+            .header(AUTHORIZATION, "Bearer header-token")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(request_token(&request).as_deref(), Some("header-token"));
+
+        let request = Request::builder().uri("/api/snapshot").body(Body::empty()).unwrap();
+        assert_eq!(request_token(&request), None);
+    }
+}
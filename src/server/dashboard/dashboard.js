@@ -0,0 +1,239 @@
+"use strict";
+
+// Translations for the monitor's current language, filled in by the server
+const LABELS = JSON.parse(document.getElementById("labels").textContent);
+const TOKEN_KEY = "cc-monitor-token";
+const MAX_RECONNECT_DELAY = 30000;
+
+let token = null;
+let socket = null;
+let snapshot = null;
+let reconnectDelay = 1000;
+
+const $ = (id) => document.getElementById(id);
+const label = (key) => LABELS[key] ?? key;
+const format = (key, args) => label(key).replace(/\{(\d+)\}/g, (_, i) => args[i]);
+
+function formatNumber(value) {
+  if (value >= 1e6) return (value / 1e6).toFixed(1) + "M";
+  if (value >= 1e3) return (value / 1e3).toFixed(1) + "K";
+  return String(Math.round(value));
+}
+
+const dollars = (value) => "$" + value.toFixed(2);
+const time = (value) => value ? new Date(value).toLocaleTimeString() : "--";
+
+function setText(id, text, className) {
+  const element = $(id);
+  element.textContent = text;
+  if (className !== undefined) element.className = className;
+}
+
+function setConnection(live) {
+  setText("connection", label(live ? "web.live" : "web.reconnecting"), live ? "badge live" : "badge");
+}
+
+// Token handling: `?token=` is saved and removed from the address bar
+
+function loadToken() {
+  const params = new URLSearchParams(location.search);
+  if (params.has("token")) {
+    localStorage.setItem(TOKEN_KEY, params.get("token"));
+    history.replaceState(null, "", location.pathname);
+  }
+  return localStorage.getItem(TOKEN_KEY);
+}
+
+function showTokenForm(invalid) {
+  if (socket) {
+    socket.onclose = null;
+    socket.close();
+    socket = null;
+  }
+  $("dashboard").hidden = true;
+  $("token-form").hidden = false;
+  $("token-error").hidden = !invalid;
+  $("token").focus();
+}
+
+$("token-form").addEventListener("submit", (event) => {
+  event.preventDefault();
+  token = $("token").value.trim();
+  localStorage.setItem(TOKEN_KEY, token);
+  start().catch(reconnect);
+});
+
+// Rendering
+
+function renderNetwork(network) {
+  setText("network-status",
+    label(network.connected ? "network.connected" : "network.disconnected"),
+    network.connected ? "connected" : "disconnected");
+  setText("network-latency", network.latency ?? "--");
+  setText("network-speed", network.speed ?? "--");
+}
+
+function renderBlock(block) {
+  const status = { active: "usage.active", completed: "usage.completed", running: "usage.running" }[block.status];
+  setText("block-status", label(status ?? "usage.inactive"));
+  setText("block-model", block.model || "--");
+  setText("block-tokens", formatNumber(block.tokens));
+  setText("block-cost", dollars(block.cost));
+  setText("block-start", time(block.start));
+
+  const rates = [];
+  if (block.burn_rate_tokens_per_min != null) rates.push(formatNumber(block.burn_rate_tokens_per_min) + "/min");
+  if (block.burn_rate_cost_per_hour != null) rates.push(dollars(block.burn_rate_cost_per_hour) + "/h");
+  setText("block-burn-rate", rates.join("  ") || "--");
+  renderCountdown();
+}
+
+// Counts down every second rather than waiting for the next update
+function renderCountdown() {
+  const block = snapshot?.block;
+  if (!block?.active || !block.reset_time) {
+    setText("block-remaining", "--");
+    return;
+  }
+  const secs = Math.max(0, Math.floor((new Date(block.reset_time) - Date.now()) / 1000));
+  const pad = (value) => String(value).padStart(2, "0");
+  setText("block-remaining", `${Math.floor(secs / 3600)}:${pad(Math.floor(secs % 3600 / 60))}:${pad(secs % 60)}`);
+}
+
+function levelColor(percent, budget) {
+  if (!budget) return "var(--accent)";
+  if (percent >= budget.critical_percent) return "var(--red)";
+  if (percent >= budget.warning_percent) return "var(--yellow)";
+  return "var(--green)";
+}
+
+function renderBudgets(budgets) {
+  const container = $("budgets");
+  container.replaceChildren(...budgets.map((budget) => {
+    const row = document.createElement("div");
+    row.className = "budget";
+    row.textContent = format("budget.progress", [
+      label("budget." + budget.period), budget.spent.toFixed(2), budget.limit.toFixed(2), budget.percent.toFixed(0),
+    ]);
+    const bar = document.createElement("div");
+    bar.className = "bar";
+    const fill = document.createElement("div");
+    fill.className = "fill";
+    fill.style.width = Math.min(budget.percent, 100) + "%";
+    fill.style.background = levelColor(budget.percent, budget);
+    bar.appendChild(fill);
+    row.appendChild(bar);
+    return row;
+  }));
+}
+
+function renderChart(days, budgets) {
+  const svg = $("chart");
+  const width = svg.clientWidth || 600;
+  const height = svg.clientHeight || 220;
+  svg.setAttribute("viewBox", `0 0 ${width} ${height}`);
+  svg.replaceChildren();
+  if (days.length === 0) return;
+
+  const daily = budgets.find((budget) => budget.period === "daily");
+  const max = Math.max(...days.map((day) => day.cost), daily?.limit ?? 0, 0.01);
+  const slot = width / days.length;
+  const barWidth = Math.min(40, slot * 0.7);
+  const chartHeight = height - 36;
+  const ns = "http://www.w3.org/2000/svg";
+  const element = (name, attributes, text, parent = svg) => {
+    const node = document.createElementNS(ns, name);
+    for (const [key, value] of Object.entries(attributes)) node.setAttribute(key, value);
+    if (text !== undefined) node.textContent = text;
+    parent.appendChild(node);
+    return node;
+  };
+
+  days.forEach((day, index) => {
+    const x = index * slot + (slot - barWidth) / 2;
+    const barHeight = Math.max(1, day.cost / max * chartHeight);
+    const y = 16 + chartHeight - barHeight;
+    const percent = daily ? day.cost / daily.limit * 100 : 0;
+    const bar = element("rect", { x, y, width: barWidth, height: barHeight, rx: 2, fill: levelColor(percent, daily) });
+    element("title", {}, `${day.date ?? ""}: ${dollars(day.cost)}`, bar);
+    element("text", { x: x + barWidth / 2, y: y - 4, "text-anchor": "middle" }, "$" + day.cost.toFixed(0));
+    element("text", { x: x + barWidth / 2, y: height - 4, "text-anchor": "middle" }, (day.date ?? "").slice(5));
+  });
+
+  if (daily) {
+    const y = 16 + chartHeight - daily.limit / max * chartHeight;
+    element("line", { x1: 0, x2: width, y1: y, y2: y, stroke: "var(--yellow)", "stroke-dasharray": "4 4" });
+  }
+}
+
+function render(data) {
+  snapshot = data;
+  $("token-form").hidden = true;
+  $("dashboard").hidden = false;
+
+  renderNetwork(data.network);
+  renderBlock(data.block);
+
+  const total = data.daily_costs.reduce((sum, day) => sum + day.cost, 0);
+  const average = data.daily_costs.length ? total / data.daily_costs.length : 0;
+  setText("history-total", `${label("history.total")}: ${dollars(total)}`);
+  setText("history-average", `${label("history.average")}: ${dollars(average)}/${label("common.day")}`);
+  setText("today-cost", `${label("usage.today_cost")}: ${data.today_cost != null ? dollars(data.today_cost) : "--"}`);
+  renderBudgets(data.budgets);
+  renderChart(data.daily_costs, data.budgets);
+
+  setText("last-update", `${label("common.last_update")}: ${new Date(data.timestamp).toLocaleString()}`);
+}
+
+// Data: the current snapshot over REST, then live updates over the WebSocket
+
+async function start() {
+  token = token ?? loadToken();
+  if (!token) {
+    showTokenForm(false);
+    return;
+  }
+
+  const response = await fetch("api/snapshot", { headers: { Authorization: "Bearer " + token } });
+  if (response.status === 401) {
+    showTokenForm(true);
+    return;
+  }
+  if (response.ok) render(await response.json());
+  connect();
+}
+
+// Starts over, so a revoked token brings back the form
+function reconnect() {
+  setTimeout(() => start().catch(reconnect), reconnectDelay);
+  reconnectDelay = Math.min(reconnectDelay * 2, MAX_RECONNECT_DELAY);
+}
+
+function connect() {
+  const scheme = location.protocol === "https:" ? "wss:" : "ws:";
+  const base = location.pathname.replace(/[^/]*$/, "");
+  socket = new WebSocket(`${scheme}//${location.host}${base}api/ws?token=${encodeURIComponent(token)}`);
+
+  socket.onopen = () => {
+    reconnectDelay = 1000;
+    setConnection(true);
+  };
+  socket.onmessage = (event) => {
+    const message = JSON.parse(event.data);
+    if (message.type === "data") render(message.snapshot);
+  };
+  socket.onclose = () => {
+    socket = null;
+    setConnection(false);
+    reconnect();
+  };
+}
+
+for (const element of document.querySelectorAll("[data-label]")) {
+  element.textContent = label(element.dataset.label);
+}
+document.title = label("app.name");
+setConnection(false);
+setInterval(renderCountdown, 1000);
+window.addEventListener("resize", () => snapshot && renderChart(snapshot.daily_costs, snapshot.budgets));
+start().catch(reconnect);
@@ -0,0 +1,69 @@
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+use serde_json::{Map, Value};
+
+use crate::i18n::{self, keys};
+
+const INDEX_HTML: &str = include_str!("index.html");
+const DASHBOARD_JS: &str = include_str!("dashboard.js");
+const DASHBOARD_CSS: &str = include_str!("dashboard.css");
+const FAVICON: &[u8] = include_bytes!("../../../assets/ccm-tray.png");
+
+/// Translations used by the dashboard, sent in the monitor's current language.
+const LABEL_KEYS: &[&str] = &[
+    keys::APP_NAME,
+    keys::NETWORK_TITLE,
+    keys::NETWORK_STATUS,
+    keys::NETWORK_CONNECTED,
+    keys::NETWORK_DISCONNECTED,
+    keys::NETWORK_LATENCY,
+    keys::NETWORK_SPEED,
+    keys::USAGE_TITLE,
+    keys::USAGE_STATUS,
+    keys::USAGE_ACTIVE,
+    keys::USAGE_COMPLETED,
+    keys::USAGE_RUNNING,
+    keys::USAGE_INACTIVE,
+    keys::USAGE_MODEL,
+    keys::USAGE_TOKENS,
+    keys::USAGE_COST,
+    keys::USAGE_SESSION_START,
+    keys::USAGE_REMAINING,
+    keys::USAGE_BURN_RATE,
+    keys::USAGE_TODAY_COST,
+    keys::HISTORY_TITLE,
+    keys::HISTORY_TOTAL,
+    keys::HISTORY_AVERAGE,
+    keys::BUDGET_DAILY,
+    keys::BUDGET_WEEKLY,
+    keys::BUDGET_MONTHLY,
+    keys::BUDGET_PROGRESS,
+    keys::COMMON_DAY,
+    keys::COMMON_LAST_UPDATE,
+    keys::WEB_LIVE,
+    keys::WEB_RECONNECTING,
+    keys::WEB_TOKEN_PROMPT,
+    keys::WEB_TOKEN_INVALID,
+    keys::WEB_CONNECT,
+];
+
+async fn index() -> Html<String> {
+    let labels: Map<String, Value> = LABEL_KEYS.iter()
+        .map(|key| (key.to_string(), Value::String(i18n::get(key))))
+        .collect();
+    // A `</` in a translation would end the script element early
+    let labels = Value::Object(labels).to_string().replace("</", "<\\/");
+    Html(INDEX_HTML.replace("__LABELS__", &labels))
+}
+
+/// The static dashboard. It needs no token itself; the page asks for one
+/// before calling the API.
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/dashboard.js", get(|| async { ([(CONTENT_TYPE, "text/javascript; charset=utf-8")], DASHBOARD_JS).into_response() }))
+        .route("/dashboard.css", get(|| async { ([(CONTENT_TYPE, "text/css; charset=utf-8")], DASHBOARD_CSS).into_response() }))
+        .route("/favicon.png", get(|| async { ([(CONTENT_TYPE, "image/png")], FAVICON).into_response() }))
+}
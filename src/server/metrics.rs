@@ -0,0 +1,189 @@
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use anyhow::{Context, Result};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use prometheus::{
+    Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::monitor::{DataSource, HealthState, MonitorData, MonitorSnapshot};
+use crate::monitor_log as log;
+
+/// Probe latency buckets in seconds, from a fast ping to a slow `npx ccusage` run.
+const PROBE_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// The exported metrics, updated by the worker on every publish.
+struct Metrics {
+    registry: Registry,
+    block_tokens: IntGaugeVec,
+    block_cost: Gauge,
+    block_active: IntGauge,
+    block_remaining: IntGauge,
+    burn_rate_tokens: Gauge,
+    burn_rate_cost: Gauge,
+    daily_cost: Gauge,
+    network_up: IntGauge,
+    probe_duration: HistogramVec,
+    source_up: IntGaugeVec,
+    source_state: IntGaugeVec,
+    source_consecutive_failures: IntGaugeVec,
+    source_last_success: GaugeVec,
+    source_failures: IntCounterVec,
+    /// Consecutive failures per source at the previous update, to count new ones
+    last_failures: Mutex<[u32; 3]>,
+}
+
+impl Metrics {
+    fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("ccmonitor".to_string()), None)?;
+        let metrics = Self {
+            block_tokens: IntGaugeVec::new(
+                Opts::new("block_tokens", "Tokens used in the current block"),
+                &["type", "model"],
+            )?,
+            block_cost: Gauge::new("block_cost_usd", "Cost of the current block in USD")?,
+            block_active: IntGauge::new("block_active", "1 while a block is active")?,
+            block_remaining: IntGauge::new("block_remaining_seconds", "Seconds until the active block resets")?,
+            burn_rate_tokens: Gauge::new("burn_rate_tokens_per_minute", "Token burn rate of the active block")?,
+            burn_rate_cost: Gauge::new("burn_rate_cost_usd_per_hour", "Cost burn rate of the active block")?,
+            daily_cost: Gauge::new("daily_cost_usd", "Cost of today in USD")?,
+            network_up: IntGauge::new("network_up", "1 when the network probes succeed")?,
+            probe_duration: HistogramVec::new(
+                HistogramOpts::new("probe_duration_seconds", "Duration of the data source probes")
+                    .buckets(PROBE_BUCKETS.to_vec()),
+                &["probe"],
+            )?,
+            source_up: IntGaugeVec::new(
+                Opts::new("source_up", "1 when the last poll of the data source succeeded"),
+                &["source"],
+            )?,
+            source_state: IntGaugeVec::new(
+                Opts::new("source_state", "Health state of the data source, 1 for the current state"),
+                &["source", "state"],
+            )?,
+            source_consecutive_failures: IntGaugeVec::new(
+                Opts::new("source_consecutive_failures", "Failed polls of the data source since the last success"),
+                &["source"],
+            )?,
+            source_last_success: GaugeVec::new(
+                Opts::new("source_last_success_timestamp_seconds", "Unix time of the last successful poll"),
+                &["source"],
+            )?,
+            source_failures: IntCounterVec::new(
+                Opts::new("source_failures_total", "Failed polls of the data source"),
+                &["source"],
+            )?,
+            last_failures: Mutex::new([0; 3]),
+            registry,
+        };
+
+        metrics.registry.register(Box::new(metrics.block_tokens.clone()))?;
+        metrics.registry.register(Box::new(metrics.block_cost.clone()))?;
+        metrics.registry.register(Box::new(metrics.block_active.clone()))?;
+        metrics.registry.register(Box::new(metrics.block_remaining.clone()))?;
+        metrics.registry.register(Box::new(metrics.burn_rate_tokens.clone()))?;
+        metrics.registry.register(Box::new(metrics.burn_rate_cost.clone()))?;
+        metrics.registry.register(Box::new(metrics.daily_cost.clone()))?;
+        metrics.registry.register(Box::new(metrics.network_up.clone()))?;
+        metrics.registry.register(Box::new(metrics.probe_duration.clone()))?;
+        metrics.registry.register(Box::new(metrics.source_up.clone()))?;
+        metrics.registry.register(Box::new(metrics.source_state.clone()))?;
+        metrics.registry.register(Box::new(metrics.source_consecutive_failures.clone()))?;
+        metrics.registry.register(Box::new(metrics.source_last_success.clone()))?;
+        metrics.registry.register(Box::new(metrics.source_failures.clone()))?;
+        Ok(metrics)
+    }
+
+    fn update(&self, data: &MonitorData) {
+        let snapshot = MonitorSnapshot::new(data, None);
+        let block = &snapshot.block;
+
+        // Drop the series of the previous block's model
+        self.block_tokens.reset();
+        let model = if block.model.is_empty() { "unknown" } else { block.model.as_str() };
+        let counts = &data.ccusage_data.token_counts;
+        for (kind, tokens) in [
+            ("input", counts.input),
+            ("output", counts.output),
+            ("cache_creation", counts.cache_creation),
+            ("cache_read", counts.cache_read),
+        ] {
+            self.block_tokens.with_label_values(&[kind, model]).set(tokens as i64);
+        }
+        self.block_cost.set(block.cost);
+        self.block_active.set(block.active as i64);
+        self.block_remaining.set(block.remaining_secs.unwrap_or(0));
+        self.burn_rate_tokens.set(block.burn_rate_tokens_per_min.unwrap_or(0.0));
+        self.burn_rate_cost.set(block.burn_rate_cost_per_hour.unwrap_or(0.0));
+        self.daily_cost.set(snapshot.today_cost.unwrap_or(0.0));
+        self.network_up.set(snapshot.network.connected as i64);
+
+        let mut last_failures = self.last_failures.lock().unwrap();
+        for (index, source) in DataSource::ALL.into_iter().enumerate() {
+            let health = data.health.get(source);
+            let name = source.name();
+            self.source_up.with_label_values(&[name]).set((health.consecutive_failures == 0) as i64);
+            for state in HealthState::ALL {
+                self.source_state.with_label_values(&[name, state.name()]).set((health.state == state) as i64);
+            }
+            self.source_consecutive_failures.with_label_values(&[name]).set(health.consecutive_failures as i64);
+            if let Some(last_success) = health.last_success {
+                self.source_last_success.with_label_values(&[name]).set(last_success.timestamp() as f64);
+            }
+            let new_failures = health.consecutive_failures.saturating_sub(last_failures[index]);
+            self.source_failures.with_label_values(&[name]).inc_by(new_failures as u64);
+            last_failures[index] = health.consecutive_failures;
+        }
+    }
+}
+
+static METRICS: LazyLock<Option<Metrics>> = LazyLock::new(|| {
+    Metrics::new()
+        .map_err(|e| log!("Failed to register metrics: {}", e))
+        .ok()
+});
+
+/// Update the gauges from the latest monitor data.
+pub fn update(data: &MonitorData) {
+    if let Some(metrics) = METRICS.as_ref() {
+        metrics.update(data);
+    }
+}
+
+/// Record how long a probe (`ping`, `connection`, `ccusage` or `history`) took.
+pub fn observe_probe(probe: &str, duration: Duration) {
+    if let Some(metrics) = METRICS.as_ref() {
+        metrics.probe_duration.with_label_values(&[probe]).observe(duration.as_secs_f64());
+    }
+}
+
+/// Render all metrics in the Prometheus text format.
+pub fn render() -> Result<String> {
+    let metrics = METRICS.as_ref().context("Metrics are not available")?;
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&metrics.registry.gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    match render() {
+        Ok(body) => ([(CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Serve `/metrics` on `listen` until the runtime shuts down.
+pub async fn serve(listen: &str) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+    log!("Serving Prometheus metrics on http://{}/metrics", listener.local_addr()?);
+
+    let app = Router::new().route("/metrics", get(metrics_handler));
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+#[cfg(feature = "server")]
+pub mod api;
+#[cfg(feature = "server")]
+pub mod dashboard;
+#[cfg(feature = "server")]
+pub mod metrics;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::utils::preferences::Preferences;
+#[cfg(feature = "server")]
+use crate::monitor_log as log;
+
+/// The Prometheus exporter, stored in the preferences. Only used when built
+/// with the `server` feature, and read once when the worker starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address the `/metrics` endpoint listens on
+    #[serde(default = "default_metrics_listen")]
+    pub listen: String,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_metrics_listen(),
+        }
+    }
+}
+
+fn default_metrics_listen() -> String {
+    "127.0.0.1:9464".to_string()
+}
+
+/// The REST API and WebSocket stream, stored in the preferences. Only used
+/// when built with the `server` feature, and read once when the worker starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address the API listens on
+    #[serde(default = "default_api_listen")]
+    pub listen: String,
+    /// Bearer token required by every request; generated on first start when empty
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Serve the web dashboard at `/`
+    #[serde(default = "default_true")]
+    pub dashboard: bool,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_api_listen(),
+            token: None,
+            dashboard: true,
+        }
+    }
+}
+
+fn default_api_listen() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Start the HTTP endpoints enabled in the preferences on the background runtime.
+#[cfg(feature = "server")]
+pub fn spawn_servers(runtime: &tokio::runtime::Runtime) {
+    let prefs = Preferences::load().unwrap_or_default();
+    if prefs.metrics.enabled {
+        let listen = prefs.metrics.listen.clone();
+        runtime.spawn(async move {
+            if let Err(e) = metrics::serve(&listen).await {
+                log!("Metrics endpoint stopped: {:#}", e);
+            }
+        });
+    }
+    if prefs.api.enabled {
+        runtime.spawn(async move {
+            if let Err(e) = api::serve(prefs.api).await {
+                log!("API server stopped: {:#}", e);
+            }
+        });
+    }
+}
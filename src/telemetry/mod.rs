@@ -0,0 +1,146 @@
+#[cfg(feature = "otel")]
+mod otlp;
+
+use std::collections::HashMap;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::{DataSource, MonitorData};
+#[cfg(feature = "otel")]
+use crate::utils::preferences::Preferences;
+#[cfg(feature = "otel")]
+use crate::monitor_log as log;
+
+/// The OpenTelemetry exporter, stored in the preferences. Only used when
+/// built with the `otel` feature, and read once when the worker starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the OTLP/HTTP collector; `/v1/metrics` and `/v1/traces` are appended
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    /// Extra headers sent with every export, e.g. an API key
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Seconds between metric exports
+    #[serde(default = "default_export_interval")]
+    pub export_interval_secs: u64,
+    /// The `service.name` resource attribute
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_endpoint(),
+            headers: HashMap::new(),
+            export_interval_secs: default_export_interval(),
+            service_name: default_service_name(),
+        }
+    }
+}
+
+fn default_endpoint() -> String {
+    "http://localhost:4318".to_string()
+}
+
+fn default_export_interval() -> u64 {
+    60
+}
+
+fn default_service_name() -> String {
+    "cc-monitor-rs".to_string()
+}
+
+/// Whether a probe result counts as a failure, and why.
+pub trait ProbeOutcome {
+    fn error(&self) -> Option<String>;
+}
+
+impl<T> ProbeOutcome for Result<T> {
+    fn error(&self) -> Option<String> {
+        self.as_ref().err().map(|e| format!("{:#}", e))
+    }
+}
+
+/// `NetworkMonitor::ping_google`
+impl ProbeOutcome for (bool, Option<String>) {
+    fn error(&self) -> Option<String> {
+        (!self.0).then(|| "Unreachable".to_string())
+    }
+}
+
+/// `NetworkMonitor::check_connection`
+impl ProbeOutcome for (bool, Option<String>, Option<f64>) {
+    fn error(&self) -> Option<String> {
+        (!self.0).then(|| "Unreachable".to_string())
+    }
+}
+
+/// One update of a data source, traced as a span with a child span per
+/// probe. Does nothing unless the exporter was started by `init`.
+#[derive(Clone, Default)]
+pub struct Cycle {
+    #[cfg(feature = "otel")]
+    span: Option<otlp::CycleSpan>,
+}
+
+impl Cycle {
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    pub fn start(source: DataSource) -> Self {
+        Self {
+            #[cfg(feature = "otel")]
+            span: otlp::start_cycle(source),
+        }
+    }
+
+    /// Run a probe, in a child span when tracing.
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    pub fn probe<T: ProbeOutcome>(&self, probe: &'static str, run: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "otel")]
+        if let Some(span) = &self.span {
+            return otlp::probe(span, probe, run);
+        }
+        run()
+    }
+
+    /// End the span, marking it failed when the update failed.
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    pub fn end(self, error: Option<&str>) {
+        #[cfg(feature = "otel")]
+        if let Some(span) = self.span {
+            otlp::end_cycle(span, error);
+        }
+    }
+}
+
+/// Start exporting to the collector in the preferences. Must not be called
+/// from async code, since the exporters use blocking HTTP clients.
+pub fn init() {
+    #[cfg(feature = "otel")]
+    {
+        let prefs = Preferences::load().unwrap_or_default();
+        if prefs.telemetry.enabled
+            && let Err(e) = otlp::init(&prefs.telemetry)
+        {
+            log!("Failed to start the OpenTelemetry exporter: {:#}", e);
+        }
+    }
+}
+
+/// Record the usage gauges from the latest monitor data.
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub fn update(data: &MonitorData) {
+    #[cfg(feature = "otel")]
+    otlp::update(data);
+}
+
+/// Export what is still buffered and stop the exporters. Blocks until the
+/// collector answered or timed out.
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otlp::shutdown();
+}
@@ -0,0 +1,244 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use opentelemetry::metrics::{Counter, Gauge, Histogram, Meter, MeterProvider as _};
+use opentelemetry::trace::{Span as _, SpanKind, Status, TraceContextExt, Tracer as _, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+
+use super::{ProbeOutcome, TelemetrySettings};
+use crate::monitor::{DataSource, MonitorData, MonitorSnapshot};
+use crate::monitor_log as log;
+
+/// Instrumentation scope of all spans and metrics.
+const SCOPE: &str = "cc-monitor-rs";
+/// How long an export may take before it is dropped.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Probe latency buckets in seconds, the same as the Prometheus histogram.
+const PROBE_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// The metric instruments, recorded by the worker and exported periodically.
+struct Instruments {
+    block_tokens: Gauge<u64>,
+    block_cost: Gauge<f64>,
+    block_active: Gauge<u64>,
+    block_remaining: Gauge<u64>,
+    burn_rate_tokens: Gauge<f64>,
+    burn_rate_cost: Gauge<f64>,
+    plan_usage: Gauge<f64>,
+    daily_cost: Gauge<f64>,
+    network_up: Gauge<u64>,
+    probe_duration: Histogram<f64>,
+    source_polls: Counter<u64>,
+}
+
+impl Instruments {
+    fn new(meter: &Meter) -> Self {
+        Self {
+            block_tokens: meter.u64_gauge("ccmonitor.block.tokens")
+                .with_description("Tokens used in the current block")
+                .with_unit("{token}")
+                .build(),
+            block_cost: meter.f64_gauge("ccmonitor.block.cost")
+                .with_description("Cost of the current block")
+                .with_unit("USD")
+                .build(),
+            block_active: meter.u64_gauge("ccmonitor.block.active")
+                .with_description("1 while a block is active")
+                .build(),
+            block_remaining: meter.u64_gauge("ccmonitor.block.remaining")
+                .with_description("Time until the active block resets")
+                .with_unit("s")
+                .build(),
+            burn_rate_tokens: meter.f64_gauge("ccmonitor.burn_rate.tokens")
+                .with_description("Token burn rate of the active block")
+                .with_unit("{token}/min")
+                .build(),
+            burn_rate_cost: meter.f64_gauge("ccmonitor.burn_rate.cost")
+                .with_description("Cost burn rate of the active block")
+                .with_unit("USD/h")
+                .build(),
+            plan_usage: meter.f64_gauge("ccmonitor.plan.usage")
+                .with_description("Share of the plan token limit used by the active block")
+                .with_unit("%")
+                .build(),
+            daily_cost: meter.f64_gauge("ccmonitor.daily.cost")
+                .with_description("Cost of today")
+                .with_unit("USD")
+                .build(),
+            network_up: meter.u64_gauge("ccmonitor.network.up")
+                .with_description("1 when the network probes succeed")
+                .build(),
+            probe_duration: meter.f64_histogram("ccmonitor.probe.duration")
+                .with_description("Duration of the data source probes")
+                .with_unit("s")
+                .with_boundaries(PROBE_BUCKETS.to_vec())
+                .build(),
+            source_polls: meter.u64_counter("ccmonitor.source.polls")
+                .with_description("Updates of the data sources, by outcome")
+                .build(),
+        }
+    }
+}
+
+struct Telemetry {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+    tracer: SdkTracer,
+    instruments: Instruments,
+}
+
+static TELEMETRY: OnceLock<Telemetry> = OnceLock::new();
+
+/// The URL of a signal below the collector base URL, e.g. `/v1/traces`.
+fn signal_url(endpoint: &str, path: &str) -> String {
+    format!("{}{}", endpoint.trim_end_matches('/'), path)
+}
+
+fn outcome(error: Option<&str>) -> KeyValue {
+    KeyValue::new("outcome", if error.is_some() { "failure" } else { "success" })
+}
+
+/// Build the OTLP/HTTP exporters and start exporting.
+pub fn init(settings: &TelemetrySettings) -> Result<()> {
+    let resource = Resource::builder()
+        .with_service_name(settings.service_name.clone())
+        .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let span_exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(signal_url(&settings.endpoint, "/v1/traces"))
+        .with_headers(settings.headers.clone())
+        .with_timeout(EXPORT_TIMEOUT)
+        .build()?;
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_resource(resource.clone())
+        .with_batch_exporter(span_exporter)
+        .build();
+
+    let metric_exporter = MetricExporter::builder()
+        .with_http()
+        .with_endpoint(signal_url(&settings.endpoint, "/v1/metrics"))
+        .with_headers(settings.headers.clone())
+        .with_timeout(EXPORT_TIMEOUT)
+        .build()?;
+    let reader = PeriodicReader::builder(metric_exporter)
+        .with_interval(Duration::from_secs(settings.export_interval_secs.max(1)))
+        .build();
+    let meter_provider = SdkMeterProvider::builder()
+        .with_resource(resource)
+        .with_reader(reader)
+        .build();
+
+    let telemetry = Telemetry {
+        tracer: tracer_provider.tracer(SCOPE),
+        instruments: Instruments::new(&meter_provider.meter(SCOPE)),
+        tracer_provider,
+        meter_provider,
+    };
+    if TELEMETRY.set(telemetry).is_ok() {
+        log!("Exporting OpenTelemetry metrics and traces to {}", settings.endpoint);
+    }
+    Ok(())
+}
+
+/// The span of a data source update, shared with the probes it runs.
+#[derive(Clone)]
+pub struct CycleSpan {
+    source: DataSource,
+    context: Context,
+}
+
+/// A root span for an update of `source`, or `None` when not exporting.
+pub fn start_cycle(source: DataSource) -> Option<CycleSpan> {
+    let telemetry = TELEMETRY.get()?;
+    let span = telemetry.tracer
+        .span_builder(format!("update {}", source.name()))
+        .with_kind(SpanKind::Internal)
+        .with_attributes([KeyValue::new("source", source.name())])
+        .start(&telemetry.tracer);
+    Some(CycleSpan { source, context: Context::new().with_span(span) })
+}
+
+/// Run a probe in a child span of the cycle and record its duration.
+pub fn probe<T: ProbeOutcome>(cycle: &CycleSpan, probe: &'static str, run: impl FnOnce() -> T) -> T {
+    let Some(telemetry) = TELEMETRY.get() else {
+        return run();
+    };
+    let mut span = telemetry.tracer.start_with_context(probe, &cycle.context);
+    let start = Instant::now();
+    let result = run();
+    let elapsed = start.elapsed();
+
+    let error = result.error();
+    if let Some(error) = &error {
+        span.set_status(Status::error(error.clone()));
+    }
+    span.end();
+    telemetry.instruments.probe_duration.record(
+        elapsed.as_secs_f64(),
+        &[KeyValue::new("probe", probe), outcome(error.as_deref())],
+    );
+    result
+}
+
+/// End the cycle span and count the update.
+pub fn end_cycle(cycle: CycleSpan, error: Option<&str>) {
+    let span = cycle.context.span();
+    if let Some(error) = error {
+        span.set_status(Status::error(error.to_string()));
+    }
+    span.end();
+
+    if let Some(telemetry) = TELEMETRY.get() {
+        telemetry.instruments.source_polls.add(1, &[KeyValue::new("source", cycle.source.name()), outcome(error)]);
+    }
+}
+
+/// Record the usage gauges from the latest monitor data.
+pub fn update(data: &MonitorData) {
+    let Some(telemetry) = TELEMETRY.get() else {
+        return;
+    };
+    let instruments = &telemetry.instruments;
+    let snapshot = MonitorSnapshot::new(data, data.plan_token_limit);
+    let block = &snapshot.block;
+
+    let model = if block.model.is_empty() { "unknown" } else { block.model.as_str() };
+    let counts = &data.ccusage_data.token_counts;
+    for (kind, tokens) in [
+        ("input", counts.input),
+        ("output", counts.output),
+        ("cache_creation", counts.cache_creation),
+        ("cache_read", counts.cache_read),
+    ] {
+        instruments.block_tokens.record(tokens, &[KeyValue::new("type", kind), KeyValue::new("model", model.to_string())]);
+    }
+    instruments.block_cost.record(block.cost, &[]);
+    instruments.block_active.record(block.active as u64, &[]);
+    instruments.block_remaining.record(block.remaining_secs.unwrap_or(0).max(0) as u64, &[]);
+    instruments.burn_rate_tokens.record(block.burn_rate_tokens_per_min.unwrap_or(0.0), &[]);
+    instruments.burn_rate_cost.record(block.burn_rate_cost_per_hour.unwrap_or(0.0), &[]);
+    if let Some(plan_percent) = block.plan_percent {
+        instruments.plan_usage.record(plan_percent, &[]);
+    }
+    instruments.daily_cost.record(snapshot.today_cost.unwrap_or(0.0), &[]);
+    instruments.network_up.record(snapshot.network.connected as u64, &[]);
+}
+
+/// Flush and stop both providers.
+pub fn shutdown() {
+    let Some(telemetry) = TELEMETRY.get() else {
+        return;
+    };
+    if let Err(e) = telemetry.tracer_provider.shutdown() {
+        log!("Failed to flush OpenTelemetry traces: {}", e);
+    }
+    if let Err(e) = telemetry.meter_provider.shutdown() {
+        log!("Failed to flush OpenTelemetry metrics: {}", e);
+    }
+}
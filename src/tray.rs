@@ -10,8 +10,21 @@ use tray_icon::{
 use std::sync::{Arc, Mutex};
 use anyhow::Result;
 use crate::monitor::MonitorData;
+use crate::utils::notifications;
 use crate::i18n;
 
+/// Actions requested from the tray menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    Refresh,
+    Pause,
+    Resume,
+    Snooze,
+    Unsnooze,
+    MarkNotificationsRead,
+    Quit,
+}
+
 pub struct TrayManager {
     tray: TrayIcon,
     menu: Menu,
@@ -22,6 +35,12 @@ pub struct TrayManager {
     model_item: MenuItem,
     remaining_item: MenuItem,
     status_active_item: MenuItem,
+    refresh_item: MenuItem,
+    pause_item: MenuItem,
+    resume_item: MenuItem,
+    snooze_item: MenuItem,
+    unsnooze_item: MenuItem,
+    notifications_item: MenuItem,
     quit_item: MenuItem,
 }
 
@@ -63,6 +82,23 @@ impl TrayManager {
         );
 
         let separator2 = PredefinedMenuItem::separator();
+        let refresh_item = MenuItem::new(&i18n::get(i18n::keys::TRAY_REFRESH), true, None);
+        let pause_item = MenuItem::new(&i18n::get(i18n::keys::TRAY_PAUSE), true, None);
+        let resume_item = MenuItem::new(&i18n::get(i18n::keys::TRAY_RESUME), false, None);
+        let snooze_item = MenuItem::new(
+            &i18n::format(i18n::keys::TRAY_SNOOZE, &[&notifications::snooze_minutes().to_string()]),
+            true,
+            None
+        );
+        let unsnooze_item = MenuItem::new(&i18n::get(i18n::keys::TRAY_UNSNOOZE), false, None);
+
+        let notifications_item = MenuItem::new(
+            &i18n::format(i18n::keys::TRAY_NOTIFICATIONS, &["0"]),
+            false,
+            None
+        );
+
+        let separator3 = PredefinedMenuItem::separator();
         let quit_item = MenuItem::new(&i18n::get(i18n::keys::TRAY_QUIT), true, None);
 
         // Create menu
@@ -76,6 +112,13 @@ impl TrayManager {
         menu.append(&remaining_item)?;
         menu.append(&status_active_item)?;
         menu.append(&separator2)?;
+        menu.append(&refresh_item)?;
+        menu.append(&pause_item)?;
+        menu.append(&resume_item)?;
+        menu.append(&snooze_item)?;
+        menu.append(&unsnooze_item)?;
+        menu.append(&notifications_item)?;
+        menu.append(&separator3)?;
         menu.append(&quit_item)?;
 
         // Create tray icon using embedded icon
@@ -105,6 +148,12 @@ impl TrayManager {
             model_item,
             remaining_item,
             status_active_item,
+            refresh_item,
+            pause_item,
+            resume_item,
+            snooze_item,
+            unsnooze_item,
+            notifications_item,
             quit_item,
         })
     }
@@ -232,9 +281,45 @@ impl TrayManager {
         Ok(icon)
     }
 
-    pub fn handle_menu_event(&self, event: &tray_icon::menu::MenuEvent) -> bool {
-        // Check if quit was clicked
-        event.id() == self.quit_item.id()
+    /// Reflect the paused state of the worker in the pause/resume items.
+    pub fn set_paused(&self, paused: bool) {
+        self.pause_item.set_enabled(!paused);
+        self.resume_item.set_enabled(paused);
+    }
+
+    /// Reflect whether notifications are snoozed in the snooze items.
+    pub fn set_snoozed(&self, snoozed: bool) {
+        self.snooze_item.set_text(
+            i18n::format(i18n::keys::TRAY_SNOOZE, &[&notifications::snooze_minutes().to_string()]));
+        self.snooze_item.set_enabled(!snoozed);
+        self.unsnooze_item.set_enabled(snoozed);
+    }
+
+    /// Show the unread notification badge; clicking the item marks them read.
+    pub fn set_unread_notifications(&self, count: usize) {
+        self.notifications_item.set_text(i18n::format(i18n::keys::TRAY_NOTIFICATIONS, &[&count.to_string()]));
+        self.notifications_item.set_enabled(count > 0);
+    }
+
+    pub fn handle_menu_event(&self, event: &tray_icon::menu::MenuEvent) -> Option<TrayCommand> {
+        let id = event.id();
+        if id == self.quit_item.id() {
+            Some(TrayCommand::Quit)
+        } else if id == self.refresh_item.id() {
+            Some(TrayCommand::Refresh)
+        } else if id == self.pause_item.id() {
+            Some(TrayCommand::Pause)
+        } else if id == self.resume_item.id() {
+            Some(TrayCommand::Resume)
+        } else if id == self.snooze_item.id() {
+            Some(TrayCommand::Snooze)
+        } else if id == self.unsnooze_item.id() {
+            Some(TrayCommand::Unsnooze)
+        } else if id == self.notifications_item.id() {
+            Some(TrayCommand::MarkNotificationsRead)
+        } else {
+            None
+        }
     }
 }
 
@@ -263,10 +348,28 @@ impl TrayHandle {
         Ok(())
     }
 
-    pub fn handle_menu_event(&self, event: &tray_icon::menu::MenuEvent) -> bool {
+    pub fn set_paused(&self, paused: bool) {
+        if let Some(tray) = self.inner.lock().unwrap().as_ref() {
+            tray.set_paused(paused);
+        }
+    }
+
+    pub fn set_snoozed(&self, snoozed: bool) {
+        if let Some(tray) = self.inner.lock().unwrap().as_ref() {
+            tray.set_snoozed(snoozed);
+        }
+    }
+
+    pub fn set_unread_notifications(&self, count: usize) {
+        if let Some(tray) = self.inner.lock().unwrap().as_ref() {
+            tray.set_unread_notifications(count);
+        }
+    }
+
+    pub fn handle_menu_event(&self, event: &tray_icon::menu::MenuEvent) -> Option<TrayCommand> {
         if let Some(tray) = self.inner.lock().unwrap().as_ref() {
             return tray.handle_menu_event(event);
         }
-        false
+        None
     }
 }
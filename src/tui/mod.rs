@@ -0,0 +1,194 @@
+mod view;
+
+use std::path::PathBuf;
+use std::time::Duration;
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::widgets::ListState;
+use ratatui::DefaultTerminal;
+
+use crate::background::{start_monitor_tokio, stop_monitor_worker, submit_monitor_request, tokio_runtime, MonitorRequest};
+use crate::monitor::MonitorData;
+use crate::ui_updates::{dequeue_monitor_updates, MonitorUpdate};
+use crate::utils::logging;
+use crate::utils::notifications::{self, NotificationRecord};
+use crate::i18n;
+use crate::monitor_log as log;
+
+/// How long to wait for a key press before redrawing the countdown.
+const TICK: Duration = Duration::from_millis(250);
+/// Number of notifications kept in the alerts panel.
+const MAX_ALERTS: usize = 50;
+const PAUSE_SECS: u64 = 30 * 60;
+
+/// Options of the terminal UI.
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Append log lines to this file; they are dropped otherwise since the UI owns the terminal
+    pub log_file: Option<PathBuf>,
+}
+
+/// The panel that receives the arrow keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Panel {
+    Chart,
+    Alerts,
+}
+
+impl Panel {
+    fn next(self) -> Self {
+        match self {
+            Panel::Chart => Panel::Alerts,
+            Panel::Alerts => Panel::Chart,
+        }
+    }
+}
+
+/// State of the terminal UI, fed from the monitor update queue.
+struct TuiApp {
+    data: Option<MonitorData>,
+    alerts: Vec<NotificationRecord>,
+    alert_state: ListState,
+    /// Index of the highlighted day in the daily cost chart
+    selected_day: Option<usize>,
+    focus: Panel,
+    paused: bool,
+    /// Result of the last request or the last error, shown in the footer
+    status: Option<String>,
+    quit: bool,
+}
+
+impl TuiApp {
+    fn new() -> Self {
+        let mut alerts = notifications::recent_notifications();
+        alerts.truncate(MAX_ALERTS);
+        Self {
+            data: None,
+            alerts,
+            alert_state: ListState::default(),
+            selected_day: None,
+            focus: Panel::Alerts,
+            paused: false,
+            status: None,
+            quit: false,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            self.handle_updates();
+            terminal.draw(|frame| view::draw(frame, self))?;
+            if event::poll(TICK)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_updates(&mut self) {
+        for update in dequeue_monitor_updates() {
+            match update {
+                MonitorUpdate::DataUpdate(data) => self.data = Some(*data),
+                MonitorUpdate::StatusMessage(message) => self.status = Some(message),
+                MonitorUpdate::ErrorMessage(message) => self.status = Some(message),
+                MonitorUpdate::RequestAck(ack) => {
+                    match (ack.request, ack.success) {
+                        ("Pause", true) => self.paused = true,
+                        ("Resume", true) => self.paused = false,
+                        // `cc-monitor-rs quit` from another terminal
+                        ("Shutdown", _) => self.quit = true,
+                        _ => {}
+                    }
+                    if !ack.success {
+                        self.status = Some(format!("{}: {}", ack.request, ack.message.unwrap_or_default()));
+                    }
+                }
+                MonitorUpdate::NotificationRecorded(record) => {
+                    self.alerts.insert(0, record);
+                    self.alerts.truncate(MAX_ALERTS);
+                    // Keep the same notification selected
+                    if let Some(selected) = self.alert_state.selected() {
+                        self.alert_state.select(Some((selected + 1).min(self.alerts.len() - 1)));
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('r') | KeyCode::F(5) => self.submit(MonitorRequest::ForceUpdate),
+            KeyCode::Char('p') => {
+                let request = if self.paused {
+                    MonitorRequest::Resume
+                } else {
+                    MonitorRequest::Pause { duration_secs: Some(PAUSE_SECS) }
+                };
+                self.submit(request);
+            }
+            KeyCode::Char('l') => cycle_language(),
+            KeyCode::Char('m') => {
+                notifications::mark_all_read();
+                self.alerts.iter_mut().for_each(|record| record.read = true);
+            }
+            KeyCode::Tab | KeyCode::BackTab => self.focus = self.focus.next(),
+            KeyCode::Up | KeyCode::Char('k') if self.focus == Panel::Alerts => self.alert_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') if self.focus == Panel::Alerts => self.alert_state.select_next(),
+            KeyCode::Left if self.focus == Panel::Chart => self.move_day(-1),
+            KeyCode::Right if self.focus == Panel::Chart => self.move_day(1),
+            _ => {}
+        }
+    }
+
+    fn move_day(&mut self, step: isize) {
+        let days = self.data.as_ref().map_or(0, |data| data.daily_costs.len());
+        if days == 0 {
+            return;
+        }
+        let current = self.selected_day.unwrap_or(days - 1) as isize;
+        self.selected_day = Some((current + step).clamp(0, days as isize - 1) as usize);
+    }
+
+    fn submit(&mut self, request: MonitorRequest) {
+        if let Err(e) = submit_monitor_request(request) {
+            self.status = Some(e.to_string());
+        }
+    }
+}
+
+/// Switch to the next language, in the same order as the desktop app.
+fn cycle_language() {
+    let next = match i18n::get_language() {
+        i18n::Language::Chinese => i18n::Language::English,
+        i18n::Language::English => i18n::Language::Japanese,
+        i18n::Language::Japanese => i18n::Language::Chinese,
+    };
+    i18n::set_language(next);
+}
+
+/// Run the monitor with a terminal UI until the user quits.
+pub fn run(options: TuiOptions) -> Result<()> {
+    match &options.log_file {
+        Some(path) => logging::log_to_file(path)?,
+        None => logging::disable(),
+    }
+    // Notifications are listed in the alerts panel instead of written over the screen
+    notifications::set_terminal_enabled(false);
+
+    start_monitor_tokio()?;
+    submit_monitor_request(MonitorRequest::ForceUpdate)?;
+
+    let mut terminal = ratatui::init();
+    let result = TuiApp::new().run(&mut terminal);
+    ratatui::restore();
+
+    if let Err(e) = tokio_runtime().block_on(stop_monitor_worker(Duration::from_secs(2))) {
+        log!("Failed to stop monitor worker: {}", e);
+    }
+    result
+}
@@ -0,0 +1,206 @@
+use chrono::Local;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, List, ListItem, Paragraph};
+use ratatui::Frame;
+
+use super::{Panel, TuiApp};
+use crate::budget::{BudgetPeriod, BudgetStatus};
+use crate::monitor::MonitorData;
+use crate::utils::notifications::{format_number, Severity};
+use crate::i18n;
+
+const ACCENT: Color = Color::Cyan;
+
+pub(super) fn draw(frame: &mut Frame, app: &mut TuiApp) {
+    let [header, top, chart, alerts, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(9),
+        Constraint::Min(10),
+        Constraint::Length(10),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [network, block] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(top);
+
+    draw_header(frame, header, app);
+    match &app.data {
+        Some(data) => {
+            draw_network(frame, network, data);
+            draw_block(frame, block, data);
+            draw_chart(frame, chart, data, app.selected_day, app.focus == Panel::Chart);
+        }
+        None => {
+            let waiting = Paragraph::new(i18n::get(i18n::keys::TUI_WAITING)).block(Block::bordered());
+            frame.render_widget(waiting, top);
+        }
+    }
+    draw_alerts(frame, alerts, app);
+    draw_footer(frame, footer, app);
+}
+
+fn panel(title: String, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(format!(" {} ", title));
+    if focused {
+        block.border_style(Style::new().fg(ACCENT))
+    } else {
+        block
+    }
+}
+
+fn field(label: String, value: impl Into<Span<'static>>) -> Line<'static> {
+    Line::from(vec![Span::raw(format!("{}: ", label)).dim(), value.into()])
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, app: &TuiApp) {
+    let mut spans = vec![
+        Span::raw(i18n::get(i18n::keys::APP_NAME)).bold(),
+        Span::raw("  "),
+        Span::raw(i18n::get_language().name()).fg(ACCENT),
+    ];
+    if app.paused {
+        spans.push(Span::raw("  "));
+        spans.push(Span::raw(i18n::get(i18n::keys::TUI_PAUSED)).yellow());
+    }
+    if let Some(data) = &app.data {
+        spans.push(Span::raw(format!("  {}: {}",
+            i18n::get(i18n::keys::COMMON_LAST_UPDATE),
+            data.last_update.format("%H:%M:%S"))).dim());
+    }
+    frame.render_widget(Line::from(spans), area);
+}
+
+fn draw_network(frame: &mut Frame, area: Rect, data: &MonitorData) {
+    let status = if data.network_connected {
+        Span::raw(i18n::get(i18n::keys::NETWORK_CONNECTED)).green()
+    } else {
+        Span::raw(i18n::get(i18n::keys::NETWORK_DISCONNECTED)).red()
+    };
+    let lines = vec![
+        field(i18n::get(i18n::keys::NETWORK_STATUS), status),
+        field(i18n::get(i18n::keys::NETWORK_LATENCY), data.latency.clone().unwrap_or_else(|| "--".to_string())),
+        field(i18n::get(i18n::keys::NETWORK_SPEED), data.connection_speed.clone().unwrap_or_else(|| "--".to_string())),
+    ];
+    let block = panel(i18n::get(i18n::keys::NETWORK_TITLE), false);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_block(frame: &mut Frame, area: Rect, data: &MonitorData) {
+    let ccusage = &data.ccusage_data;
+    let status = match ccusage.status.as_str() {
+        "ACTIVE" => Span::raw(i18n::get(i18n::keys::USAGE_ACTIVE)).green(),
+        "COMPLETED" => Span::raw(i18n::get(i18n::keys::USAGE_COMPLETED)),
+        "RUNNING" => Span::raw(i18n::get(i18n::keys::USAGE_RUNNING)),
+        _ => Span::raw(i18n::get(i18n::keys::USAGE_INACTIVE)).dim(),
+    };
+
+    // Count down every frame rather than waiting for the next update
+    let remaining = match ccusage.reset_time {
+        Some(reset_time) if ccusage.status == "ACTIVE" => {
+            let secs = (reset_time - Local::now()).num_seconds().max(0);
+            format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+        }
+        _ => ccusage.remaining_time.clone(),
+    };
+
+    let burn_rate = match (ccusage.burn_rate_tokens_per_min, ccusage.burn_rate_cost_per_hour) {
+        (Some(tokens), Some(cost)) => format!("{}/min  ${:.2}/h", format_number(tokens as i64), cost),
+        (Some(tokens), None) => format!("{}/min", format_number(tokens as i64)),
+        _ => "--".to_string(),
+    };
+
+    let lines = vec![
+        field(i18n::get(i18n::keys::USAGE_STATUS), status),
+        field(i18n::get(i18n::keys::USAGE_MODEL), ccusage.model.clone()),
+        field(i18n::get(i18n::keys::USAGE_TOKENS), Span::raw(ccusage.tokens.clone()).bold()),
+        field(i18n::get(i18n::keys::USAGE_COST), Span::raw(ccusage.cost.clone()).bold()),
+        field(i18n::get(i18n::keys::USAGE_REMAINING), Span::raw(remaining).fg(ACCENT)),
+        field(i18n::get(i18n::keys::USAGE_BURN_RATE), burn_rate),
+    ];
+    let block = panel(i18n::get(i18n::keys::USAGE_TITLE), false);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_chart(frame: &mut Frame, area: Rect, data: &MonitorData, selected: Option<usize>, focused: bool) {
+    let today = Local::now().date_naive();
+    let mut days: Vec<_> = data.daily_costs.values().collect();
+    days.sort_by_key(|day| day.day(today));
+
+    let daily_budget = data.budgets.iter().find(|status| status.period == BudgetPeriod::Daily);
+    let selected = selected.map(|index| index.min(days.len().saturating_sub(1)));
+    let bars: Vec<Bar> = days.iter().enumerate().map(|(index, day)| {
+        let style = if focused && selected == Some(index) {
+            Style::new().fg(ACCENT).add_modifier(Modifier::BOLD)
+        } else {
+            Style::new().fg(cost_color(day.cost, daily_budget))
+        };
+        Bar::default()
+            .value((day.cost * 100.0).round().max(0.0) as u64)
+            .text_value(format!("${:.0}", day.cost))
+            .label(Line::from(day.date.clone()))
+            .style(style)
+    }).collect();
+
+    let total: f64 = days.iter().map(|day| day.cost).sum();
+    let mut title = format!("{}  {}: ${:.2}", i18n::get(i18n::keys::TUI_DAILY_COST), i18n::get(i18n::keys::HISTORY_TOTAL), total);
+    if let Some(day) = selected.filter(|_| focused).and_then(|index| days.get(index)) {
+        title.push_str(&format!("  [{}: ${:.2}]", day.date, day.cost));
+    }
+
+    let chart = BarChart::default()
+        .block(panel(title, focused))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(7)
+        .bar_gap(2);
+    frame.render_widget(chart, area);
+}
+
+/// Colors a day by its spend against the daily budget, when one is set.
+fn cost_color(cost: f64, daily_budget: Option<&BudgetStatus>) -> Color {
+    let level = daily_budget.and_then(|budget| BudgetStatus { spent: cost, ..budget.clone() }.level());
+    match level {
+        Some(Severity::Critical) => Color::Red,
+        Some(_) => Color::Yellow,
+        None => Color::Green,
+    }
+}
+
+fn draw_alerts(frame: &mut Frame, area: Rect, app: &mut TuiApp) {
+    let focused = app.focus == Panel::Alerts;
+    let block = panel(i18n::get(i18n::keys::TUI_ALERTS), focused);
+    if app.alerts.is_empty() {
+        frame.render_widget(Paragraph::new(i18n::get(i18n::keys::NOTIF_CENTER_EMPTY)).dim().block(block), area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app.alerts.iter().map(|record| {
+        let event = &record.event;
+        let severity = match event.severity {
+            Severity::Info => Span::raw("INFO").fg(ACCENT),
+            Severity::Warning => Span::raw("WARN").yellow(),
+            Severity::Critical => Span::raw("CRIT").red().bold(),
+        };
+        let mut line = Line::from(vec![
+            Span::raw(event.timestamp.format("%m-%d %H:%M ").to_string()).dim(),
+            severity,
+            Span::raw(format!(" {}: {}", event.title, event.body.replace('\n', " | "))),
+        ]);
+        if !record.read {
+            line = line.bold();
+        }
+        ListItem::new(line)
+    }).collect();
+
+    let highlight = if focused { Style::new().reversed() } else { Style::new() };
+    let list = List::new(items).block(block).highlight_style(highlight);
+    frame.render_stateful_widget(list, area, &mut app.alert_state);
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, app: &TuiApp) {
+    let mut spans = vec![Span::raw(i18n::get(i18n::keys::TUI_HELP)).dim()];
+    if let Some(status) = &app.status {
+        spans.push(Span::raw(format!("  {}", status)).yellow());
+    }
+    frame.render_widget(Line::from(spans), area);
+}
@@ -10,6 +10,10 @@ live_design! {
     use crate::ui::widgets::status_indicator::StatusIndicator;
     use crate::ui::widgets::usage_display::UsageDisplay;
     use crate::ui::widgets::chart::Chart;
+    use crate::ui::widgets::source_health::SourceHealthView;
+    use crate::ui::widgets::notification_center::NotificationCenter;
+    use crate::ui::widgets::export_panel::ExportPanel;
+    use crate::ui::widgets::import_panel::ImportPanel;
 
     pub MainScreen = {{MainScreen}} {
         width: Fill,
@@ -135,6 +139,90 @@ live_design! {
             usage_display = <UsageDisplay> {}
         }
 
+        // Data Source Health Section
+        health_section = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 10
+            margin: {top: 20}
+
+            health_section_label = <Label> {
+                text: "[🩺 数据源状态]"
+                draw_text: {
+                    text_style: {
+                        font_size: 16.0
+                    }
+                    color: #ffffff
+                }
+            }
+
+            source_health = <SourceHealthView> {}
+        }
+
+        // Notification Center Section
+        notification_section = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 10
+            margin: {top: 20}
+
+            notification_section_label = <Label> {
+                text: "[🔔 通知中心]"
+                draw_text: {
+                    text_style: {
+                        font_size: 16.0
+                    }
+                    color: #ffffff
+                }
+            }
+
+            notification_center = <NotificationCenter> {}
+        }
+
+        // Export Section
+        export_section = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 10
+            margin: {top: 20}
+
+            export_section_label = <Label> {
+                text: "[📤 数据导出]"
+                draw_text: {
+                    text_style: {
+                        font_size: 16.0
+                    }
+                    color: #ffffff
+                }
+            }
+
+            export_panel = <ExportPanel> {}
+        }
+
+        // Import Section
+        import_section = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 10
+            margin: {top: 20}
+
+            import_section_label = <Label> {
+                text: "[📥 数据导入]"
+                draw_text: {
+                    text_style: {
+                        font_size: 16.0
+                    }
+                    color: #ffffff
+                }
+            }
+
+            import_panel = <ImportPanel> {}
+        }
+
         // Historical Stats Section
         stats_section = <View> {
             width: Fill,
@@ -258,6 +346,11 @@ impl MainScreen {
             usage.update_data(cx, self.monitor_data.ccusage_data.clone());
         }
 
+        // Update data source health
+        if let Some(mut health) = self.view.widget(id!(source_health)).borrow_mut::<crate::ui::widgets::source_health::SourceHealthView>() {
+            health.update_health(cx, self.monitor_data.health.clone());
+        }
+
         // Update chart
         if let Some(mut chart) = self.view.widget(id!(chart)).borrow_mut::<crate::ui::widgets::chart::Chart>() {
             let total_cost: f64 = self.monitor_data.daily_costs.values()
@@ -275,7 +368,8 @@ impl MainScreen {
                 self.monitor_data.daily_costs.clone(),
                 total_cost,
                 session_count,
-                active_sessions
+                active_sessions,
+                self.monitor_data.budgets.clone()
             );
         }
 
@@ -286,6 +380,20 @@ impl MainScreen {
         }
     }
     
+    /// Reload the notification center after a notification was recorded.
+    pub fn update_notifications(&mut self, cx: &mut Cx) {
+        if let Some(mut center) = self.view.widget(id!(notification_center)).borrow_mut::<crate::ui::widgets::notification_center::NotificationCenter>() {
+            center.refresh(cx);
+        }
+    }
+    
+    /// Show the worker's answer to an import started from the import panel.
+    pub fn import_finished(&mut self, cx: &mut Cx, success: bool, message: Option<&str>) {
+        if let Some(mut panel) = self.view.widget(id!(import_panel)).borrow_mut::<crate::ui::widgets::import_panel::ImportPanel>() {
+            panel.finished(cx, success, message);
+        }
+    }
+    
     pub fn refresh_translations(&mut self, cx: &mut Cx) {
         // Update title
         if let Some(mut label) = self.view.label(id!(title_label)).borrow_mut() {
@@ -301,6 +409,22 @@ impl MainScreen {
             label.set_text(cx, &format!("[{}]", i18n::get(i18n::keys::USAGE_TITLE)));
         }
         
+        if let Some(mut label) = self.view.label(id!(health_section_label)).borrow_mut() {
+            label.set_text(cx, &format!("[{}]", i18n::get(i18n::keys::HEALTH_TITLE)));
+        }
+        
+        if let Some(mut label) = self.view.label(id!(notification_section_label)).borrow_mut() {
+            label.set_text(cx, &format!("[{}]", i18n::get(i18n::keys::NOTIF_CENTER_TITLE)));
+        }
+        
+        if let Some(mut label) = self.view.label(id!(export_section_label)).borrow_mut() {
+            label.set_text(cx, &format!("[{}]", i18n::get(i18n::keys::EXPORT_TITLE)));
+        }
+        
+        if let Some(mut label) = self.view.label(id!(import_section_label)).borrow_mut() {
+            label.set_text(cx, &format!("[{}]", i18n::get(i18n::keys::IMPORT_TITLE)));
+        }
+        
         if let Some(mut label) = self.view.label(id!(stats_section_label)).borrow_mut() {
             label.set_text(cx, &format!("[{}]", i18n::get(i18n::keys::HISTORY_TITLE)));
         }
@@ -319,6 +443,22 @@ impl MainScreen {
             usage.refresh_translations(cx);
         }
         
+        if let Some(mut health) = self.view.widget(id!(source_health)).borrow_mut::<crate::ui::widgets::source_health::SourceHealthView>() {
+            health.refresh_translations(cx);
+        }
+        
+        if let Some(mut center) = self.view.widget(id!(notification_center)).borrow_mut::<crate::ui::widgets::notification_center::NotificationCenter>() {
+            center.refresh_translations(cx);
+        }
+        
+        if let Some(mut panel) = self.view.widget(id!(export_panel)).borrow_mut::<crate::ui::widgets::export_panel::ExportPanel>() {
+            panel.refresh_translations(cx);
+        }
+        
+        if let Some(mut panel) = self.view.widget(id!(import_panel)).borrow_mut::<crate::ui::widgets::import_panel::ImportPanel>() {
+            panel.refresh_translations(cx);
+        }
+        
         if let Some(mut chart) = self.view.widget(id!(chart)).borrow_mut::<crate::ui::widgets::chart::Chart>() {
             chart.refresh_translations(cx);
         }
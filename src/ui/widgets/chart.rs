@@ -1,6 +1,8 @@
 use makepad_widgets::*;
 use std::collections::HashMap;
+use crate::budget::{BudgetPeriod, BudgetStatus};
 use crate::monitor::DailyCost;
+use crate::utils::notifications::Severity;
 use crate::i18n;
 
 live_design! {
@@ -10,13 +12,31 @@ live_design! {
     
     use crate::ui::styles::*;
     
+    // Horizontal progress bar, filled up to `progress` (0.0 - 1.0)
+    BudgetBar = <View> {
+        width: Fill,
+        height: 8,
+        show_bg: true,
+        draw_bg: {
+            instance progress: 0.0
+            instance bar_color: #4caf50
+            
+            fn pixel(self) -> vec4 {
+                if self.pos.x <= self.progress {
+                    return self.bar_color;
+                }
+                return #2a2a2a;
+            }
+        }
+    }
+    
     pub Chart = {{Chart}} {
         width: Fill,
-        height: 300,
+        height: Fit,
         
         chart_view = <View> {
             width: Fill,
-            height: Fill,
+            height: Fit,
             flow: Down,
             spacing: 10
             
@@ -68,6 +88,66 @@ live_design! {
                         color: #ffffff
                     }
                 }
+
+                budget_daily_row = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+                    spacing: 3
+                    visible: false
+
+                    budget_daily_label = <Label> {
+                        text: ""
+                        draw_text: {
+                            text_style: {
+                                font_size: 12.0
+                            }
+                            color: #cccccc
+                        }
+                    }
+
+                    budget_daily_bar = <BudgetBar> {}
+                }
+
+                budget_weekly_row = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+                    spacing: 3
+                    visible: false
+
+                    budget_weekly_label = <Label> {
+                        text: ""
+                        draw_text: {
+                            text_style: {
+                                font_size: 12.0
+                            }
+                            color: #cccccc
+                        }
+                    }
+
+                    budget_weekly_bar = <BudgetBar> {}
+                }
+
+                budget_monthly_row = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+                    spacing: 3
+                    visible: false
+
+                    budget_monthly_label = <Label> {
+                        text: ""
+                        draw_text: {
+                            text_style: {
+                                font_size: 12.0
+                            }
+                            color: #cccccc
+                        }
+                    }
+
+                    budget_monthly_bar = <BudgetBar> {}
+                }
             }
         }
     }
@@ -80,6 +160,7 @@ pub struct Chart {
     #[rust] total_cost: f64,
     #[rust] session_count: u32,
     #[rust] active_sessions: u32,
+    #[rust] budgets: Vec<BudgetStatus>,
 }
 
 impl Widget for Chart {
@@ -89,17 +170,32 @@ impl Widget for Chart {
     }
     
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        // Update budget progress bars
+        for status in &self.budgets {
+            let progress = (status.percent() / 100.0).clamp(0.0, 1.0);
+            let color = match status.level() {
+                Some(Severity::Critical) => vec4(0.96, 0.26, 0.21, 1.0), // Red
+                Some(_) => vec4(1.0, 0.76, 0.03, 1.0), // Yellow
+                None => vec4(0.30, 0.69, 0.31, 1.0), // Green
+            };
+            self.view(Self::budget_bar_id(status.period)).apply_over(cx, live!{
+                draw_bg: { progress: (progress), bar_color: (color) }
+            });
+        }
+        
         self.view.draw_walk(cx, scope, walk)
     }
 }
 
 impl Chart {
     pub fn update_data(&mut self, cx: &mut Cx, daily_costs: HashMap<String, DailyCost>, 
-                      total_cost: f64, session_count: u32, active_sessions: u32) {
+                      total_cost: f64, session_count: u32, active_sessions: u32,
+                      budgets: Vec<BudgetStatus>) {
         self.daily_costs = daily_costs;
         self.total_cost = total_cost;
         self.session_count = session_count;
         self.active_sessions = active_sessions;
+        self.budgets = budgets;
         self.apply_chart_updates(cx);
         cx.redraw_all();
     }
@@ -133,6 +229,33 @@ impl Chart {
                 ));
             }
         }
+        
+        // Update budget progress, hiding budgets that aren't configured
+        for period in [BudgetPeriod::Daily, BudgetPeriod::Weekly, BudgetPeriod::Monthly] {
+            let status = self.budgets.iter().find(|status| status.period == period);
+            let (row_id, label_id) = match period {
+                BudgetPeriod::Daily => (id!(budget_daily_row), id!(budget_daily_label)),
+                BudgetPeriod::Weekly => (id!(budget_weekly_row), id!(budget_weekly_label)),
+                BudgetPeriod::Monthly => (id!(budget_monthly_row), id!(budget_monthly_label)),
+            };
+            self.view.view(row_id).set_visible(cx, status.is_some());
+            if let (Some(status), Some(mut label)) = (status, self.view.label(label_id).borrow_mut()) {
+                label.set_text(cx, &i18n::format(i18n::keys::BUDGET_PROGRESS, &[
+                    &period.label(),
+                    &format!("{:.2}", status.spent),
+                    &format!("{:.2}", status.limit),
+                    &format!("{:.0}", status.percent()),
+                ]));
+            }
+        }
+    }
+    
+    fn budget_bar_id(period: BudgetPeriod) -> &'static [LiveId] {
+        match period {
+            BudgetPeriod::Daily => id!(budget_daily_bar),
+            BudgetPeriod::Weekly => id!(budget_weekly_bar),
+            BudgetPeriod::Monthly => id!(budget_monthly_bar),
+        }
     }
     
     pub fn refresh_translations(&mut self, cx: &mut Cx) {
@@ -0,0 +1,222 @@
+use makepad_widgets::*;
+use chrono::{Datelike, Local, NaiveDate};
+use crate::export::{self, ExportFormat};
+use crate::report;
+use crate::utils::preferences::Preferences;
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    pub ExportButton = <Button> {
+        width: Fit,
+        height: 26,
+        padding: {left: 10, right: 10}
+        draw_text: {
+            text_style: {
+                font_size: 11.0
+            }
+            color: #ffffff
+        }
+        draw_bg: {
+            instance hover: 0.0
+            instance pressed: 0.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    1,
+                    1,
+                    self.rect_size.x - 2.0,
+                    self.rect_size.y - 2.0,
+                    4.0
+                );
+
+                if self.pressed > 0.0 {
+                    sdf.fill(#3a3a3a);
+                } else if self.hover > 0.0 {
+                    sdf.fill(#4a4a4a);
+                } else {
+                    sdf.fill(#2a2a2a);
+                }
+
+                return sdf.result;
+            }
+        }
+    }
+
+    pub ExportPanel = {{ExportPanel}} {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 8
+
+        toolbar = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 10
+            align: {y: 0.5}
+
+            range_button = <ExportButton> { text: "最近 30 天" }
+            format_button = <ExportButton> { text: "CSV" }
+
+            <View> { width: Fill, height: Fit }
+
+            report_button = <ExportButton> { text: "生成报告" }
+            export_button = <ExportButton> { text: "导出" }
+        }
+
+        export_status = <Label> {
+            width: Fill,
+            text: ""
+            draw_text: {
+                text_style: {
+                    font_size: 12.0
+                }
+                color: #cccccc
+                wrap: Word
+            }
+        }
+    }
+}
+
+/// The date ranges offered in the window; the command line takes any range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportRange {
+    Last7Days,
+    #[default]
+    Last30Days,
+    ThisMonth,
+    LastMonth,
+}
+
+impl ExportRange {
+    fn next(self) -> Self {
+        match self {
+            ExportRange::Last7Days => ExportRange::Last30Days,
+            ExportRange::Last30Days => ExportRange::ThisMonth,
+            ExportRange::ThisMonth => ExportRange::LastMonth,
+            ExportRange::LastMonth => ExportRange::Last7Days,
+        }
+    }
+
+    /// First and last day of the range, relative to `today`.
+    fn dates(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let first_of_month = today.with_day(1).unwrap_or(today);
+        match self {
+            ExportRange::Last7Days => (today - chrono::Duration::days(6), today),
+            ExportRange::Last30Days => (today - chrono::Duration::days(29), today),
+            ExportRange::ThisMonth => (first_of_month, today),
+            ExportRange::LastMonth => {
+                let last = first_of_month - chrono::Duration::days(1);
+                (last.with_day(1).unwrap_or(last), last)
+            }
+        }
+    }
+
+    fn label(self) -> String {
+        let key = match self {
+            ExportRange::Last7Days => i18n::keys::EXPORT_RANGE_LAST_7_DAYS,
+            ExportRange::Last30Days => i18n::keys::EXPORT_RANGE_LAST_30_DAYS,
+            ExportRange::ThisMonth => i18n::keys::EXPORT_RANGE_THIS_MONTH,
+            ExportRange::LastMonth => i18n::keys::EXPORT_RANGE_LAST_MONTH,
+        };
+        i18n::get(key)
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct ExportPanel {
+    #[deref] view: View,
+    #[rust] range: ExportRange,
+    #[rust] format: ExportFormat,
+}
+
+impl Widget for ExportPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+
+        if let Event::Actions(actions) = event {
+            if self.view.button(id!(range_button)).clicked(actions) {
+                self.range = self.range.next();
+                self.refresh_buttons(cx);
+            }
+            if self.view.button(id!(format_button)).clicked(actions) {
+                self.format = match self.format {
+                    ExportFormat::Csv => ExportFormat::Json,
+                    ExportFormat::Json => ExportFormat::Csv,
+                };
+                self.refresh_buttons(cx);
+            }
+            if self.view.button(id!(export_button)).clicked(actions) {
+                self.export(cx);
+            }
+            if self.view.button(id!(report_button)).clicked(actions) {
+                self.report(cx);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl ExportPanel {
+    /// Write every dataset of the selected range to the export folder.
+    fn export(&mut self, cx: &mut Cx) {
+        let (from, to) = self.range.dates(Local::now().date_naive());
+        let directory = Preferences::load().unwrap_or_default().export.directory();
+        let status = match export::export_all(&directory, self.format, from, to) {
+            Ok(paths) => i18n::format(i18n::keys::EXPORT_DONE, &[
+                &paths.len().to_string(),
+                &directory.display().to_string(),
+            ]),
+            Err(e) => i18n::format(i18n::keys::EXPORT_FAILED, &[&e.to_string()]),
+        };
+        self.set_status(cx, &status);
+    }
+
+    /// Write a report of the selected range to the report folder.
+    fn report(&mut self, cx: &mut Cx) {
+        let (from, to) = self.range.dates(Local::now().date_naive());
+        let prefs = Preferences::load().unwrap_or_default();
+        let format = prefs.report.format;
+        let path = prefs.report.directory(&prefs.export).join(report::file_name(format, from, to));
+        let status = match report::write(&prefs.budgets, format, from, to, &path) {
+            Ok(()) => i18n::format(i18n::keys::REPORT_DONE, &[&path.display().to_string()]),
+            Err(e) => i18n::format(i18n::keys::REPORT_FAILED, &[&e.to_string()]),
+        };
+        self.set_status(cx, &status);
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, status: &str) {
+        if let Some(mut label) = self.view.label(id!(export_status)).borrow_mut() {
+            label.set_text(cx, status);
+        }
+        cx.redraw_all();
+    }
+
+    fn refresh_buttons(&mut self, cx: &mut Cx) {
+        if let Some(mut button) = self.view.button(id!(range_button)).borrow_mut() {
+            button.set_text(cx, &self.range.label());
+        }
+        if let Some(mut button) = self.view.button(id!(format_button)).borrow_mut() {
+            button.set_text(cx, &self.format.extension().to_uppercase());
+        }
+        cx.redraw_all();
+    }
+
+    pub fn refresh_translations(&mut self, cx: &mut Cx) {
+        if let Some(mut button) = self.view.button(id!(export_button)).borrow_mut() {
+            button.set_text(cx, &i18n::get(i18n::keys::EXPORT_BUTTON));
+        }
+        if let Some(mut button) = self.view.button(id!(report_button)).borrow_mut() {
+            button.set_text(cx, &i18n::get(i18n::keys::REPORT_BUTTON));
+        }
+        self.refresh_buttons(cx);
+    }
+}
@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+use makepad_widgets::*;
+use crate::background::{submit_monitor_request, MonitorRequest};
+use crate::import::{self, Import, ImportSummary};
+use crate::monitor::UsageHistory;
+use crate::utils::preferences::Preferences;
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::ui::widgets::export_panel::ExportButton;
+
+    pub ImportPanel = {{ImportPanel}} {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 8
+
+        toolbar = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 10
+            align: {y: 0.5}
+
+            preview_button = <ExportButton> { text: "预览" }
+
+            <View> { width: Fill, height: Fit }
+
+            import_button = <ExportButton> { text: "导入" }
+        }
+
+        import_status = <Label> {
+            width: Fill,
+            text: ""
+            draw_text: {
+                text_style: {
+                    font_size: 12.0
+                }
+                color: #cccccc
+                wrap: Word
+            }
+        }
+    }
+}
+
+/// Previews the files in the import folder and hands them to the worker,
+/// which merges them into the history it keeps in memory.
+#[derive(Live, LiveHook, Widget)]
+pub struct ImportPanel {
+    #[deref] view: View,
+    /// Files of the last preview that can be imported
+    #[rust] paths: Vec<PathBuf>,
+    #[rust] pending: Option<ImportSummary>,
+}
+
+impl Widget for ImportPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+
+        if let Event::Actions(actions) = event {
+            if self.view.button(id!(preview_button)).clicked(actions) {
+                self.preview(cx);
+            }
+            if self.view.button(id!(import_button)).clicked(actions) {
+                self.import(cx);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl ImportPanel {
+    /// List what each file in the import folder holds and what importing
+    /// them would add to the history.
+    fn preview(&mut self, cx: &mut Cx) {
+        self.paths.clear();
+        self.pending = None;
+
+        let directory = Preferences::load().unwrap_or_default().export.import_directory();
+        let paths = fs::create_dir_all(&directory)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| import::files_in(&directory));
+        let paths = match paths {
+            Ok(paths) if paths.is_empty() => {
+                self.set_status(cx, &i18n::format(i18n::keys::IMPORT_EMPTY, &[&directory.display().to_string()]));
+                return;
+            }
+            Ok(paths) => paths,
+            Err(e) => {
+                self.set_status(cx, &i18n::format(i18n::keys::IMPORT_FAILED, &[&e.to_string()]));
+                return;
+            }
+        };
+
+        let mut import = Import::default();
+        let mut lines = Vec::new();
+        for path in &paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            match import.add_file(path) {
+                Ok(file) => lines.push(i18n::format(i18n::keys::IMPORT_FILE, &[
+                    &name,
+                    &file.source.name(),
+                    &file.days.to_string(),
+                    &file.blocks.to_string(),
+                ])),
+                Err(e) => lines.push(i18n::format(i18n::keys::IMPORT_SKIPPED, &[&name, &e.root_cause().to_string()])),
+            }
+        }
+
+        let history = UsageHistory::load().unwrap_or_default();
+        let summary = import.preview(&history);
+        if summary.is_empty() {
+            lines.push(i18n::get(i18n::keys::IMPORT_NOTHING));
+        } else {
+            lines.push(i18n::format(i18n::keys::IMPORT_PREVIEW, &[
+                &summary.days.to_string(),
+                &summary.new_days.to_string(),
+                &summary.duplicate_days().to_string(),
+                &summary.blocks.to_string(),
+                &summary.new_blocks.to_string(),
+                &summary.duplicate_blocks().to_string(),
+                &format!("{:.2}", summary.cost),
+            ]));
+            self.paths = import.paths();
+            self.pending = Some(summary);
+        }
+        self.set_status(cx, &lines.join("\n"));
+    }
+
+    /// Import the files of the last preview, or preview first.
+    fn import(&mut self, cx: &mut Cx) {
+        if self.paths.is_empty() {
+            self.preview(cx);
+            return;
+        }
+        let paths = std::mem::take(&mut self.paths);
+        if let Err(e) = submit_monitor_request(MonitorRequest::ImportHistory { paths }) {
+            self.pending = None;
+            self.set_status(cx, &i18n::format(i18n::keys::IMPORT_FAILED, &[&e.to_string()]));
+        }
+    }
+
+    /// Called with the worker's answer to the import request.
+    pub fn finished(&mut self, cx: &mut Cx, success: bool, message: Option<&str>) {
+        let status = match (success, self.pending.take()) {
+            (true, Some(summary)) => i18n::format(i18n::keys::IMPORT_DONE, &[
+                &summary.new_days.to_string(),
+                &summary.new_blocks.to_string(),
+            ]),
+            (true, None) => return,
+            (false, _) => i18n::format(i18n::keys::IMPORT_FAILED, &[message.unwrap_or_default()]),
+        };
+        self.set_status(cx, &status);
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, status: &str) {
+        if let Some(mut label) = self.view.label(id!(import_status)).borrow_mut() {
+            label.set_text(cx, status);
+        }
+        cx.redraw_all();
+    }
+
+    pub fn refresh_translations(&mut self, cx: &mut Cx) {
+        if let Some(mut button) = self.view.button(id!(preview_button)).borrow_mut() {
+            button.set_text(cx, &i18n::get(i18n::keys::IMPORT_PREVIEW_BUTTON));
+        }
+        if let Some(mut button) = self.view.button(id!(import_button)).borrow_mut() {
+            button.set_text(cx, &i18n::get(i18n::keys::IMPORT_BUTTON));
+        }
+    }
+}
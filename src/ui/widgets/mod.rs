@@ -1,3 +1,7 @@
 pub mod status_indicator;
 pub mod usage_display;
-pub mod chart;
\ No newline at end of file
+pub mod chart;
+pub mod source_health;
+pub mod notification_center;
+pub mod export_panel;
+pub mod import_panel;
\ No newline at end of file
@@ -0,0 +1,218 @@
+use makepad_widgets::*;
+use crate::utils::notifications::{self, NotificationRecord, Severity};
+use crate::i18n;
+
+/// Number of notifications listed in the panel.
+const MAX_LISTED: usize = 15;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    NotificationButton = <Button> {
+        width: Fit,
+        height: 26,
+        padding: {left: 10, right: 10}
+        draw_text: {
+            text_style: {
+                font_size: 11.0
+            }
+            color: #ffffff
+        }
+        draw_bg: {
+            instance hover: 0.0
+            instance pressed: 0.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    1,
+                    1,
+                    self.rect_size.x - 2.0,
+                    self.rect_size.y - 2.0,
+                    4.0
+                );
+
+                if self.pressed > 0.0 {
+                    sdf.fill(#3a3a3a);
+                } else if self.hover > 0.0 {
+                    sdf.fill(#4a4a4a);
+                } else {
+                    sdf.fill(#2a2a2a);
+                }
+
+                return sdf.result;
+            }
+        }
+    }
+
+    pub NotificationCenter = {{NotificationCenter}} {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 8
+
+        toolbar = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 10
+            align: {y: 0.5}
+
+            unread_label = <Label> {
+                text: "0"
+                draw_text: {
+                    text_style: {
+                        font_size: 12.0
+                    }
+                    color: #ffaa00
+                }
+            }
+
+            <View> { width: Fill, height: Fit }
+
+            filter_button = <NotificationButton> { text: "全部" }
+            mark_read_button = <NotificationButton> { text: "全部已读" }
+            clear_button = <NotificationButton> { text: "清空" }
+        }
+
+        notification_list = <Label> {
+            width: Fill,
+            text: "--"
+            draw_text: {
+                text_style: {
+                    font_size: 12.0
+                }
+                color: #cccccc
+                wrap: Word
+            }
+        }
+    }
+}
+
+/// Minimum severity of the notifications listed in the panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotificationFilter {
+    #[default]
+    All,
+    Warnings,
+    Critical,
+}
+
+impl NotificationFilter {
+    fn next(self) -> Self {
+        match self {
+            NotificationFilter::All => NotificationFilter::Warnings,
+            NotificationFilter::Warnings => NotificationFilter::Critical,
+            NotificationFilter::Critical => NotificationFilter::All,
+        }
+    }
+
+    fn matches(self, record: &NotificationRecord) -> bool {
+        match self {
+            NotificationFilter::All => true,
+            NotificationFilter::Warnings => record.event.severity >= Severity::Warning,
+            NotificationFilter::Critical => record.event.severity >= Severity::Critical,
+        }
+    }
+
+    fn label(self) -> String {
+        let key = match self {
+            NotificationFilter::All => i18n::keys::NOTIF_CENTER_FILTER_ALL,
+            NotificationFilter::Warnings => i18n::keys::NOTIF_CENTER_FILTER_WARNINGS,
+            NotificationFilter::Critical => i18n::keys::NOTIF_CENTER_FILTER_CRITICAL,
+        };
+        i18n::get(key)
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct NotificationCenter {
+    #[deref] view: View,
+    #[rust] filter: NotificationFilter,
+}
+
+impl Widget for NotificationCenter {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+
+        if let Event::Actions(actions) = event {
+            if self.view.button(id!(filter_button)).clicked(actions) {
+                self.filter = self.filter.next();
+                self.refresh(cx);
+            }
+            if self.view.button(id!(mark_read_button)).clicked(actions) {
+                notifications::mark_all_read();
+                self.refresh(cx);
+            }
+            if self.view.button(id!(clear_button)).clicked(actions) {
+                notifications::clear_notifications();
+                self.refresh(cx);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl NotificationCenter {
+    /// Re-read the notification log and redraw the panel. Called when a
+    /// notification was recorded or changed, not on every data update.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        let records = notifications::recent_notifications();
+        let unread = records.iter().filter(|record| !record.read).count();
+
+        let lines: Vec<String> = records.iter()
+            .filter(|record| self.filter.matches(record))
+            .take(MAX_LISTED)
+            .map(Self::format_record)
+            .collect();
+        let list = if lines.is_empty() {
+            i18n::get(i18n::keys::NOTIF_CENTER_EMPTY)
+        } else {
+            lines.join("\n")
+        };
+
+        if let Some(mut label) = self.view.label(id!(notification_list)).borrow_mut() {
+            label.set_text(cx, &list);
+        }
+        if let Some(mut label) = self.view.label(id!(unread_label)).borrow_mut() {
+            label.set_text(cx, &i18n::format(i18n::keys::NOTIF_CENTER_UNREAD, &[&unread.to_string()]));
+        }
+        if let Some(mut button) = self.view.button(id!(filter_button)).borrow_mut() {
+            button.set_text(cx, &self.filter.label());
+        }
+        self.view.redraw(cx);
+    }
+
+    fn format_record(record: &NotificationRecord) -> String {
+        let icon = match record.event.severity {
+            Severity::Info => "🔵",
+            Severity::Warning => "🟡",
+            Severity::Critical => "🔴",
+        };
+        let mut line = format!("{} {} {}  {} - {}",
+            if record.read { " " } else { "•" },
+            icon,
+            record.event.timestamp.format("%m-%d %H:%M"),
+            record.event.title,
+            record.event.body.lines().next().unwrap_or(""));
+        if !record.delivered {
+            line.push_str(&format!(" {}", i18n::get(i18n::keys::NOTIF_CENTER_HELD_BACK)));
+        }
+        line
+    }
+
+    pub fn refresh_translations(&mut self, cx: &mut Cx) {
+        if let Some(mut button) = self.view.button(id!(mark_read_button)).borrow_mut() {
+            button.set_text(cx, &i18n::get(i18n::keys::NOTIF_CENTER_MARK_READ));
+        }
+        if let Some(mut button) = self.view.button(id!(clear_button)).borrow_mut() {
+            button.set_text(cx, &i18n::get(i18n::keys::NOTIF_CENTER_CLEAR));
+        }
+        self.refresh(cx);
+    }
+}
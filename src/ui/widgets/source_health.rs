@@ -0,0 +1,123 @@
+use makepad_widgets::*;
+use crate::monitor::{DataSource, HealthReport, HealthState, SourceHealth};
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    pub SourceHealthView = {{SourceHealthView}} {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 4
+
+        network_health_label = <Label> {
+            text: "🌐 --"
+            draw_text: {
+                text_style: {
+                    font_size: 12.0
+                }
+                color: #cccccc
+            }
+        }
+
+        ccusage_health_label = <Label> {
+            text: "🤖 --"
+            draw_text: {
+                text_style: {
+                    font_size: 12.0
+                }
+                color: #cccccc
+            }
+        }
+
+        history_health_label = <Label> {
+            text: "📊 --"
+            draw_text: {
+                text_style: {
+                    font_size: 12.0
+                }
+                color: #cccccc
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SourceHealthView {
+    #[deref] view: View,
+    #[rust] health: HealthReport,
+}
+
+impl Widget for SourceHealthView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.apply_health_updates(cx);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl SourceHealthView {
+    pub fn update_health(&mut self, cx: &mut Cx, health: HealthReport) {
+        self.health = health;
+        self.apply_health_updates(cx);
+        cx.redraw_all();
+    }
+
+    fn apply_health_updates(&mut self, cx: &mut Cx) {
+        for source in DataSource::ALL {
+            let label_id = match source {
+                DataSource::Network => id!(network_health_label),
+                DataSource::Ccusage => id!(ccusage_health_label),
+                DataSource::History => id!(history_health_label),
+            };
+            let text = Self::format_health(source, self.health.get(source));
+            if let Some(mut label) = self.view.label(label_id).borrow_mut() {
+                label.set_text(cx, &text);
+            }
+        }
+    }
+
+    fn format_health(source: DataSource, health: &SourceHealth) -> String {
+        let name = match source {
+            DataSource::Network => i18n::get(i18n::keys::HEALTH_SOURCE_NETWORK),
+            DataSource::Ccusage => i18n::get(i18n::keys::HEALTH_SOURCE_CCUSAGE),
+            DataSource::History => i18n::get(i18n::keys::HEALTH_SOURCE_HISTORY),
+        };
+        let state = match health.state {
+            HealthState::Healthy => i18n::get(i18n::keys::HEALTH_HEALTHY),
+            HealthState::Degraded => i18n::get(i18n::keys::HEALTH_DEGRADED),
+            HealthState::Failing => i18n::get(i18n::keys::HEALTH_FAILING),
+            HealthState::Disabled => i18n::get(i18n::keys::HEALTH_DISABLED),
+        };
+
+        let mut text = format!("{}: {}", name, state);
+        if let Some(last_success) = health.last_success {
+            text.push_str(&format!("  |  {}: {}",
+                i18n::get(i18n::keys::HEALTH_LAST_SUCCESS),
+                last_success.format("%H:%M:%S")));
+        }
+        if health.state != HealthState::Healthy {
+            if let Some(last_error) = health.last_error {
+                text.push_str(&format!("  |  {}: {} {}",
+                    i18n::get(i18n::keys::HEALTH_LAST_ERROR),
+                    last_error.format("%H:%M:%S"),
+                    health.last_error_message.as_deref().unwrap_or("")));
+            }
+            if let (HealthState::Degraded | HealthState::Failing, Some(retry_at)) = (health.state, health.retry_at) {
+                let secs = (retry_at - chrono::Local::now()).num_seconds().max(0);
+                text.push_str(&format!("  |  {}: {}s", i18n::get(i18n::keys::HEALTH_RETRY_IN), secs));
+            }
+        }
+        text
+    }
+
+    pub fn refresh_translations(&mut self, cx: &mut Cx) {
+        self.apply_health_updates(cx);
+    }
+}
@@ -1,25 +1,80 @@
+use std::sync::{LazyLock, Mutex};
 use crossbeam_queue::SegQueue;
+use serde::Serialize;
+use tokio::sync::broadcast;
+#[cfg(feature = "gui")]
 use makepad_widgets::SignalToUI;
 use crate::monitor::MonitorData;
+use crate::utils::notifications::NotificationRecord;
 
 /// The possible updates that should be displayed by the monitor UI.
 /// These updates are enqueued by background tasks and dequeued by the UI.
 #[derive(Debug, Clone)]
 pub enum MonitorUpdate {
     /// Update with new monitor data
-    DataUpdate(MonitorData),
+    DataUpdate(Box<MonitorData>),
     /// Status message to display
     StatusMessage(String),
     /// Error message to display
     ErrorMessage(String),
+    /// Acknowledgement for a request handled by the background worker
+    RequestAck(RequestAck),
+    /// A notification was emitted (or held back) and recorded in the notification center
+    NotificationRecorded(NotificationRecord),
+}
+
+/// Sent back by the background worker once a `MonitorRequest` has been processed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestAck {
+    /// Id returned by `submit_monitor_request`, unset for acknowledgements
+    /// the worker sends on its own, such as the resume after a timed pause
+    pub id: Option<u64>,
+    /// Name of the request that was handled, e.g. `"Pause"`
+    pub request: &'static str,
+    /// Whether the request was applied successfully
+    pub success: bool,
+    /// Optional human readable detail (new interval, error reason, ...)
+    pub message: Option<String>,
+}
+
+impl RequestAck {
+    pub fn ok(request: &'static str, message: Option<String>) -> Self {
+        Self { id: None, request, success: true, message }
+    }
+
+    pub fn failed(request: &'static str, message: String) -> Self {
+        Self { id: None, request, success: false, message: Some(message) }
+    }
+
+    pub fn with_id(self, id: u64) -> Self {
+        Self { id: Some(id), ..self }
+    }
 }
 
 /// Global queue for pending monitor updates
 static PENDING_MONITOR_UPDATES: SegQueue<MonitorUpdate> = SegQueue::new();
 
+/// Number of updates kept for slow subscribers before they start skipping.
+const BROADCAST_CAPACITY: usize = 64;
+
+/// Copies of every update for other consumers, such as the HTTP API.
+static MONITOR_UPDATE_BROADCAST: LazyLock<broadcast::Sender<MonitorUpdate>> =
+    LazyLock::new(|| broadcast::channel(BROADCAST_CAPACITY).0);
+
+/// The last data published by the worker, for consumers that join late.
+static LATEST_MONITOR_DATA: Mutex<Option<MonitorData>> = Mutex::new(None);
+
 /// Enqueue a new monitor update and signal the UI that an update is available.
+/// Frontends without Makepad poll the queue instead.
 pub fn enqueue_monitor_update(update: MonitorUpdate) {
+    if let MonitorUpdate::DataUpdate(data) = &update {
+        *LATEST_MONITOR_DATA.lock().unwrap() = Some(data.as_ref().clone());
+    }
+    if MONITOR_UPDATE_BROADCAST.receiver_count() > 0 {
+        let _ = MONITOR_UPDATE_BROADCAST.send(update.clone());
+    }
     PENDING_MONITOR_UPDATES.push(update);
+    #[cfg(feature = "gui")]
     SignalToUI::set_ui_signal();
 }
 
@@ -31,4 +86,15 @@ pub fn dequeue_monitor_updates() -> Vec<MonitorUpdate> {
         updates.push(update);
     }
     updates
-}
\ No newline at end of file
+}
+
+/// Receive a copy of every monitor update enqueued from now on, independently
+/// of the UI queue.
+pub fn subscribe_monitor_updates() -> broadcast::Receiver<MonitorUpdate> {
+    MONITOR_UPDATE_BROADCAST.subscribe()
+}
+
+/// The data of the last `DataUpdate`, if the worker published one yet.
+pub fn latest_monitor_data() -> Option<MonitorData> {
+    LATEST_MONITOR_DATA.lock().unwrap().clone()
+}
@@ -0,0 +1,56 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use anyhow::Result;
+use chrono::Local;
+
+/// Where log lines go.
+enum LogTarget {
+    Stdout,
+    File(File),
+    /// Dropped, e.g. while a terminal UI owns the screen
+    Off,
+}
+
+static LOG_TARGET: Mutex<LogTarget> = Mutex::new(LogTarget::Stdout);
+
+/// Send all further log lines to `path` instead of stdout.
+pub fn log_to_file(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *LOG_TARGET.lock().unwrap() = LogTarget::File(file);
+    Ok(())
+}
+
+/// Drop all further log lines.
+pub fn disable() {
+    *LOG_TARGET.lock().unwrap() = LogTarget::Off;
+}
+
+/// Write one timestamped log line. Use the [`monitor_log!`](crate::monitor_log) macro instead.
+pub fn write_line(args: std::fmt::Arguments) {
+    let mut target = LOG_TARGET.lock().unwrap();
+    let line = format!("[{}] {}", Local::now().format("%Y-%m-%d %H:%M:%S"), args);
+    match &mut *target {
+        LogTarget::Stdout => {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", line);
+            let _ = stdout.flush();
+        }
+        LogTarget::File(file) => {
+            let _ = writeln!(file, "{}", line);
+        }
+        LogTarget::Off => {}
+    }
+}
+
+/// Logs a line to stdout or the log file, usable without the Makepad UI.
+#[macro_export]
+macro_rules! monitor_log {
+    ($($arg:tt)*) => {
+        $crate::utils::logging::write_line(format_args!($($arg)*))
+    };
+}
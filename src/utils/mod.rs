@@ -1,3 +1,4 @@
+pub mod logging;
 pub mod notifications;
 pub mod process;
 pub mod preferences;
\ No newline at end of file
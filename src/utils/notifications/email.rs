@@ -0,0 +1,366 @@
+use std::time::Duration;
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveTime, Weekday};
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+
+use super::sink::{NotificationSink, SinkConfig, SinkKind};
+use super::{format_number, NotificationEvent};
+use crate::budget::BudgetStatus;
+use crate::monitor::UsageSummary;
+use crate::i18n;
+
+/// Number of projects and models listed in a digest.
+const DIGEST_TOP_ENTRIES: usize = 5;
+
+/// How the connection to the SMTP server is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsMode {
+    /// Plain connection, e.g. for a local SMTP sink
+    None,
+    /// Upgrade a plain connection with STARTTLS (port 587)
+    #[default]
+    Starttls,
+    /// TLS from the start (port 465)
+    Tls,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_digest_time() -> String {
+    "08:00".to_string()
+}
+
+fn default_weekly_day() -> Weekday {
+    Weekday::Mon
+}
+
+/// An SMTP server and the digests to send through it, configured as an
+/// `email` notification sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub server: String,
+    /// Defaults to the standard port of the TLS mode
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: TlsMode,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    pub from: String,
+    pub recipients: Vec<String>,
+    /// Send yesterday's usage every day at `digest_time`
+    #[serde(default = "default_true")]
+    pub daily_digest: bool,
+    /// Send the previous week's usage on `weekly_day` at `digest_time`
+    #[serde(default)]
+    pub weekly_digest: bool,
+    /// Time as `HH:MM`
+    #[serde(default = "default_digest_time")]
+    pub digest_time: String,
+    #[serde(default = "default_weekly_day")]
+    pub weekly_day: Weekday,
+}
+
+impl EmailConfig {
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.tls {
+            TlsMode::None => 25,
+            TlsMode::Starttls => 587,
+            TlsMode::Tls => 465,
+        })
+    }
+
+    pub fn send(&self, subject: &str, body: &str) -> Result<()> {
+        if self.recipients.is_empty() {
+            anyhow::bail!("No email recipients configured");
+        }
+        let mut builder = Message::builder()
+            .from(self.from.parse::<Mailbox>()?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in &self.recipients {
+            builder = builder.to(recipient.parse::<Mailbox>()?);
+        }
+        let message = builder.body(body.to_string())?;
+
+        let mut transport = match self.tls {
+            TlsMode::None => SmtpTransport::builder_dangerous(&self.server),
+            TlsMode::Starttls => SmtpTransport::starttls_relay(&self.server)?,
+            TlsMode::Tls => SmtpTransport::relay(&self.server)?,
+        }
+        .port(self.port())
+        .timeout(Some(Duration::from_secs(30)));
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        transport.build().send(&message)?;
+        Ok(())
+    }
+}
+
+/// The email configurations among the enabled notification sinks.
+pub fn email_configs(sinks: &[SinkConfig]) -> Vec<EmailConfig> {
+    sinks.iter()
+        .filter(|sink| sink.enabled)
+        .filter_map(|sink| match &sink.kind {
+            SinkKind::Email(config) => Some(config.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Emails notifications that pass the sink's severity filter, typically
+/// only critical alerts.
+pub struct EmailSink {
+    config: EmailConfig,
+}
+
+impl EmailSink {
+    pub fn new(config: EmailConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()> {
+        let body = format!("{}\n\n{}", event.body, event.timestamp.format("%Y-%m-%d %H:%M:%S"));
+        self.config.send(&event.title, &body)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestPeriod {
+    Daily,
+    Weekly,
+}
+
+impl DigestPeriod {
+    /// The completed period covered by a digest sent on `today`.
+    pub fn range(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let yesterday = today - Days::new(1);
+        match self {
+            DigestPeriod::Daily => (yesterday, yesterday),
+            DigestPeriod::Weekly => (today - Days::new(7), yesterday),
+        }
+    }
+}
+
+/// Decides when the daily and weekly digests of one email sink are due.
+/// Digests whose time has already passed when the app starts are skipped.
+#[derive(Debug, Clone)]
+pub struct EmailDigestSchedule {
+    pub config: EmailConfig,
+    last_daily: Option<NaiveDate>,
+    last_weekly: Option<NaiveDate>,
+}
+
+impl EmailDigestSchedule {
+    pub fn new(config: EmailConfig, now: DateTime<Local>) -> Self {
+        let mut schedule = Self {
+            config,
+            last_daily: None,
+            last_weekly: None,
+        };
+        schedule.due(now);
+        schedule
+    }
+
+    /// The digests that are due now; each is returned once per day.
+    pub fn due(&mut self, now: DateTime<Local>) -> Vec<DigestPeriod> {
+        let Ok(time) = NaiveTime::parse_from_str(self.config.digest_time.trim(), "%H:%M") else {
+            return Vec::new();
+        };
+        let today = now.date_naive();
+        if now.time() < time {
+            return Vec::new();
+        }
+
+        let mut due = Vec::new();
+        if self.config.daily_digest && self.last_daily != Some(today) {
+            self.last_daily = Some(today);
+            due.push(DigestPeriod::Daily);
+        }
+        if self.config.weekly_digest && today.weekday() == self.config.weekly_day && self.last_weekly != Some(today) {
+            self.last_weekly = Some(today);
+            due.push(DigestPeriod::Weekly);
+        }
+        due
+    }
+}
+
+/// Builds the subject and plain text body of a usage digest.
+pub fn digest_message(
+    period: DigestPeriod,
+    from: NaiveDate,
+    to: NaiveDate,
+    summary: &UsageSummary,
+    budgets: &[BudgetStatus],
+) -> (String, String) {
+    let subject = match period {
+        DigestPeriod::Daily => i18n::format(i18n::keys::EMAIL_DAILY_SUBJECT, &[&from.to_string()]),
+        DigestPeriod::Weekly => i18n::format(i18n::keys::EMAIL_WEEKLY_SUBJECT, &[&from.to_string(), &to.to_string()]),
+    };
+
+    let mut lines = vec![
+        format!("{}: ${:.2}", i18n::get(i18n::keys::EMAIL_TOTAL_COST), summary.cost),
+        format!("{}: {}", i18n::get(i18n::keys::EMAIL_TOTAL_TOKENS), format_number(summary.tokens.total() as i64)),
+        format!("{}: {}", i18n::get(i18n::keys::EMAIL_BLOCKS), summary.blocks),
+    ];
+    if let Some(rate) = summary.peak_burn_rate {
+        lines.push(format!("{}: {:.0}/min", i18n::get(i18n::keys::EMAIL_PEAK_BURN_RATE), rate));
+    }
+
+    let mut section = |title: &str, entries: &[(String, f64, u64)]| {
+        if entries.is_empty() {
+            return;
+        }
+        lines.push(String::new());
+        lines.push(title.to_string());
+        for (name, cost, tokens) in entries.iter().take(DIGEST_TOP_ENTRIES) {
+            lines.push(format!("  {}  ${:.2}  ({})", name, cost, format_number(*tokens as i64)));
+        }
+    };
+    section(&i18n::get(i18n::keys::EMAIL_TOP_PROJECTS), &summary.projects);
+    section(&i18n::get(i18n::keys::EMAIL_MODEL_SPEND), &summary.models);
+
+    if !budgets.is_empty() {
+        lines.push(String::new());
+        lines.push(i18n::get(i18n::keys::EMAIL_BUDGETS));
+        for status in budgets {
+            lines.push(format!("  {}", i18n::format(i18n::keys::BUDGET_PROGRESS, &[
+                &status.period.label(),
+                &format!("{:.2}", status.spent),
+                &format!("{:.2}", status.limit),
+                &format!("{:.0}", status.percent()),
+            ])));
+        }
+    }
+
+    (subject, lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc;
+    use super::*;
+    use chrono::TimeZone;
+    use crate::utils::notifications::{NotificationKind, Severity};
+
+    /// A local SMTP stand-in accepting a single message, whose commands and
+    /// data are sent back over the returned channel.
+    fn serve() -> (u16, mpsc::Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut lines = Vec::new();
+            let reply = |reader: &mut BufReader<TcpStream>, text: &str| {
+                reader.get_mut().write_all(text.as_bytes()).unwrap();
+            };
+            reply(&mut reader, "220 localhost ESMTP\r\n");
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end_matches(['\r', '\n']).to_string();
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        reply(&mut reader, "250 Queued\r\n");
+                    } else {
+                        lines.push(line);
+                    }
+                    continue;
+                }
+                let command = line.to_ascii_uppercase();
+                lines.push(line);
+                if command.starts_with("EHLO") {
+                    reply(&mut reader, "250-localhost\r\n250 8BITMIME\r\n");
+                } else if command.starts_with("DATA") {
+                    in_data = true;
+                    reply(&mut reader, "354 Go ahead\r\n");
+                } else if command.starts_with("QUIT") {
+                    reply(&mut reader, "221 Bye\r\n");
+                    break;
+                } else {
+                    reply(&mut reader, "250 OK\r\n");
+                }
+            }
+            sender.send(lines).unwrap();
+        });
+        (port, receiver)
+    }
+
+    fn config(port: u16) -> EmailConfig {
+        EmailConfig {
+            server: "127.0.0.1".to_string(),
+            port: Some(port),
+            tls: TlsMode::None,
+            username: None,
+            password: None,
+            from: "CC Monitor <monitor@example.com>".to_string(),
+            recipients: vec!["lead@example.com".to_string(), "ops@example.com".to_string()],
+            daily_digest: true,
+            weekly_digest: false,
+            digest_time: default_digest_time(),
+            weekly_day: default_weekly_day(),
+        }
+    }
+
+    #[test]
+    fn sends_notification_through_local_smtp() {
+        let (port, receiver) = serve();
+        let mut sink = EmailSink::new(config(port));
+
+        let event = NotificationEvent::new(NotificationKind::Budget, Severity::Critical, "Budget reached", "Spent $10 of $10");
+        sink.send(&event).unwrap();
+
+        let lines = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(lines.iter().any(|line| line == "MAIL FROM:<monitor@example.com>"));
+        assert!(lines.iter().any(|line| line == "RCPT TO:<lead@example.com>"));
+        assert!(lines.iter().any(|line| line == "RCPT TO:<ops@example.com>"));
+        assert!(lines.iter().any(|line| line == "Subject: Budget reached"));
+        assert!(lines.iter().any(|line| line == "Spent $10 of $10"));
+    }
+
+    #[test]
+    fn email_sinks_default_to_critical() {
+        let email = SinkConfig { kind: SinkKind::Email(config(25)), enabled: true, min_severity: None };
+        assert_eq!(email.min_severity(), Severity::Critical);
+        assert_eq!(SinkConfig { min_severity: Some(Severity::Warning), ..email }.min_severity(), Severity::Warning);
+        assert_eq!(SinkConfig::desktop().min_severity(), Severity::Info);
+    }
+
+    #[test]
+    fn digests_are_due_once_per_day_after_their_time() {
+        let config = EmailConfig { weekly_digest: true, ..config(25) };
+        // A Monday, before the digest time
+        let morning = Local.with_ymd_and_hms(2024, 5, 13, 7, 0, 0).unwrap();
+        let mut schedule = EmailDigestSchedule::new(config, morning);
+
+        assert!(schedule.due(morning).is_empty());
+        let later = morning + chrono::Duration::hours(2);
+        assert_eq!(schedule.due(later), vec![DigestPeriod::Daily, DigestPeriod::Weekly]);
+        assert!(schedule.due(later).is_empty());
+        assert_eq!(schedule.due(later + chrono::Duration::days(1)), vec![DigestPeriod::Daily]);
+    }
+}
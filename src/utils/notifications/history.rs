@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::NotificationEvent;
+
+/// Maximum number of notifications kept in the log; older ones are dropped.
+const MAX_RECORDS: usize = 200;
+
+/// A notification as recorded in the notification center.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRecord {
+    pub id: u64,
+    #[serde(flatten)]
+    pub event: NotificationEvent,
+    /// False when the notification was held back by the quiet hours policy
+    pub delivered: bool,
+    #[serde(default)]
+    pub read: bool,
+}
+
+/// Bounded log of every notification the app emitted, persisted next to the
+/// preferences so it survives restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotificationLog {
+    next_id: u64,
+    records: VecDeque<NotificationRecord>,
+}
+
+impl NotificationLog {
+    pub fn load() -> Result<Self> {
+        let path = Self::get_log_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::get_log_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn get_log_path() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+        Ok(config_dir.join("cc-monitor-rs").join("notifications.json"))
+    }
+
+    pub fn record(&mut self, event: NotificationEvent, delivered: bool) -> NotificationRecord {
+        let record = NotificationRecord {
+            id: self.next_id,
+            event,
+            delivered,
+            read: false,
+        };
+        self.next_id += 1;
+        self.records.push_back(record.clone());
+        while self.records.len() > MAX_RECORDS {
+            self.records.pop_front();
+        }
+        record
+    }
+
+    /// Recorded notifications, newest first.
+    pub fn records(&self) -> impl Iterator<Item = &NotificationRecord> {
+        self.records.iter().rev()
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.records.iter().filter(|record| !record.read).count()
+    }
+
+    pub fn mark_all_read(&mut self) {
+        for record in self.records.iter_mut() {
+            record.read = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
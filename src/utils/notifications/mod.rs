@@ -0,0 +1,305 @@
+pub mod email;
+pub mod history;
+pub mod policy;
+pub mod sink;
+pub mod webhook;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use chrono::{DateTime, Local};
+#[cfg(feature = "desktop-notify")]
+use notify_rust::Notification;
+#[cfg(all(feature = "desktop-notify", not(target_os = "macos")))]
+use notify_rust::Urgency;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use crate::ui_updates::{enqueue_monitor_update, MonitorUpdate};
+use crate::utils::preferences::Preferences;
+use crate::monitor_log as log;
+
+pub use self::email::{EmailConfig, TlsMode};
+pub use self::history::{NotificationLog, NotificationRecord};
+pub use self::policy::{NotificationPolicy, NotificationPolicySettings, QuietWindow};
+pub use self::sink::{NotificationDispatcher, NotificationSink, SinkConfig, SinkKind};
+pub use self::webhook::{WebhookConfig, WebhookService};
+
+/// How urgent a notification is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// What produced a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Network connection lost or restored
+    Network,
+    /// Periodic usage summary
+    UsageSummary,
+    /// A user-defined alert rule fired
+    Alert,
+    /// Block expiry marks and new windows
+    BlockReset,
+    /// Budget warnings
+    Budget,
+    /// Notifications held back during quiet hours
+    Digest,
+}
+
+/// A notification emitted by the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationEvent {
+    pub kind: NotificationKind,
+    pub severity: Severity,
+    pub title: String,
+    pub body: String,
+    pub timestamp: DateTime<Local>,
+}
+
+impl NotificationEvent {
+    pub fn new(kind: NotificationKind, severity: Severity, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            kind,
+            severity,
+            title: title.into(),
+            body: body.into(),
+            timestamp: Local::now(),
+        }
+    }
+}
+
+/// The global do-not-disturb policy, initialized from the preferences.
+static POLICY: Lazy<Mutex<NotificationPolicy>> = Lazy::new(|| {
+    let settings = Preferences::load()
+        .map(|prefs| prefs.notification_policy)
+        .unwrap_or_default();
+    Mutex::new(NotificationPolicy::new(settings))
+});
+
+/// The sinks notifications are fanned out to, initialized from the preferences.
+static DISPATCHER: Lazy<Mutex<NotificationDispatcher>> = Lazy::new(|| {
+    let sinks = Preferences::load()
+        .map(|prefs| prefs.notification_sinks)
+        .unwrap_or_else(|_| sink::default_sinks());
+    Mutex::new(NotificationDispatcher::from_configs(&sinks))
+});
+
+/// Whether desktop sinks show notifications; off when running headless.
+static DESKTOP_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Whether terminal sinks print notifications; off while the terminal UI owns the screen.
+static TERMINAL_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// The notification center log, loaded from disk on first use.
+static LOG: Lazy<Mutex<NotificationLog>> = Lazy::new(|| {
+    let log = NotificationLog::load().unwrap_or_else(|e| {
+        log!("Failed to load notification log: {}", e);
+        NotificationLog::default()
+    });
+    Mutex::new(log)
+});
+
+/// Emit a notification, unless the policy holds it back for a later digest.
+/// Every notification is recorded in the notification center either way.
+/// This blocks while the notification is shown, so call it off the async runtime.
+pub fn dispatch(event: NotificationEvent) {
+    let decision = POLICY.lock().unwrap().decide(&event);
+    let delivered = decision == policy::Decision::Deliver;
+    if delivered {
+        deliver(&event);
+    }
+    record(event, delivered);
+}
+
+/// Deliver the digest of held-back notifications if the quiet period is over.
+pub fn flush_digest() {
+    let digest = POLICY.lock().unwrap().take_digest(Local::now());
+    if let Some(digest) = digest {
+        deliver(&digest);
+        record(digest, true);
+    }
+}
+
+fn record(event: NotificationEvent, delivered: bool) {
+    let record = {
+        let mut log = LOG.lock().unwrap();
+        let record = log.record(event, delivered);
+        if let Err(e) = log.save() {
+            log!("Failed to save notification log: {}", e);
+        }
+        record
+    };
+    enqueue_monitor_update(MonitorUpdate::NotificationRecorded(record));
+}
+
+/// Recorded notifications, newest first.
+pub fn recent_notifications() -> Vec<NotificationRecord> {
+    LOG.lock().unwrap().records().cloned().collect()
+}
+
+pub fn unread_count() -> usize {
+    LOG.lock().unwrap().unread_count()
+}
+
+pub fn mark_all_read() {
+    let mut log = LOG.lock().unwrap();
+    log.mark_all_read();
+    if let Err(e) = log.save() {
+        log!("Failed to save notification log: {}", e);
+    }
+}
+
+pub fn clear_notifications() {
+    let mut log = LOG.lock().unwrap();
+    log.clear();
+    if let Err(e) = log.save() {
+        log!("Failed to save notification log: {}", e);
+    }
+}
+
+/// Whether notifications are currently being held back.
+pub fn is_quiet() -> bool {
+    POLICY.lock().unwrap().is_quiet(Local::now())
+}
+
+/// Hold back non-critical notifications for the given number of minutes,
+/// or for the configured snooze duration if `None`. Returns the end time.
+pub fn snooze(minutes: Option<u64>) -> DateTime<Local> {
+    let mut policy = POLICY.lock().unwrap();
+    let minutes = minutes.unwrap_or(policy.settings().snooze_minutes);
+    let until = Local::now() + chrono::Duration::minutes(minutes as i64);
+    policy.snooze(Some(until));
+    until
+}
+
+/// The configured snooze duration in minutes.
+pub fn snooze_minutes() -> u64 {
+    POLICY.lock().unwrap().settings().snooze_minutes
+}
+
+pub fn cancel_snooze() {
+    POLICY.lock().unwrap().snooze(None);
+}
+
+pub fn snoozed_until() -> Option<DateTime<Local>> {
+    POLICY.lock().unwrap().snoozed_until()
+}
+
+/// Apply changed policy settings, keeping the snooze state and queued notifications.
+pub fn reload_policy(settings: NotificationPolicySettings) {
+    POLICY.lock().unwrap().set_settings(settings);
+}
+
+/// Rebuild the sinks from changed preferences.
+pub fn reload_sinks(configs: &[SinkConfig]) {
+    *DISPATCHER.lock().unwrap() = NotificationDispatcher::from_configs(configs);
+}
+
+/// Turn desktop notifications on or off, e.g. when there is no desktop session.
+/// The other sinks are unaffected.
+pub fn set_desktop_enabled(enabled: bool) {
+    DESKTOP_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn desktop_enabled() -> bool {
+    DESKTOP_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turn terminal notifications on or off, e.g. while the terminal UI runs.
+/// The other sinks are unaffected.
+pub fn set_terminal_enabled(enabled: bool) {
+    TERMINAL_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn terminal_enabled() -> bool {
+    TERMINAL_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Give sinks a chance to retry failed deliveries.
+pub fn flush_sinks() {
+    let dispatcher = DISPATCHER.lock().unwrap().clone();
+    dispatcher.flush();
+}
+
+/// Add a sink that isn't configured in the preferences, e.g. a `MemorySink` in tests.
+pub fn add_sink(sink: Box<dyn NotificationSink>, min_severity: Severity) {
+    DISPATCHER.lock().unwrap().add_sink(sink, min_severity);
+}
+
+fn deliver(event: &NotificationEvent) {
+    // Slow webhook and SMTP deliveries must not block other notifiers or a reload
+    let dispatcher = DISPATCHER.lock().unwrap().clone();
+    dispatcher.send(event);
+}
+
+#[cfg(feature = "desktop-notify")]
+pub fn show_notification(title: &str, message: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        // Use macOS-specific notification with sound
+        if let Err(e) = Notification::new()
+            .summary(title)
+            .body(message)
+            .sound_name("Glass")
+            .show()
+        {
+            log!("Failed to show notification: {}", e);
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        // Generic notification for other platforms
+        if let Err(e) = Notification::new()
+            .summary(title)
+            .body(message)
+            .show()
+        {
+            log!("Failed to show notification: {}", e);
+        }
+    }
+}
+
+#[cfg(feature = "desktop-notify")]
+pub fn show_critical_notification(title: &str, message: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        // Use macOS-specific notification with alert sound
+        if let Err(e) = Notification::new()
+            .summary(title)
+            .body(message)
+            .sound_name("Basso")  // Alert sound
+            .show()
+        {
+            log!("Failed to show notification: {}", e);
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        // Generic notification for other platforms
+        if let Err(e) = Notification::new()
+            .summary(title)
+            .body(message)
+            .urgency(Urgency::Critical)
+            .show()
+        {
+            log!("Failed to show notification: {}", e);
+        }
+    }
+}
+
+pub fn format_number(num: i64) -> String {
+    if num >= 1_000_000 {
+        format!("{:.1}M", num as f64 / 1_000_000.0)
+    } else if num >= 1_000 {
+        format!("{:.1}K", num as f64 / 1_000.0)
+    } else {
+        num.to_string()
+    }
+}
\ No newline at end of file
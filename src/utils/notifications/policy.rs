@@ -0,0 +1,255 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use super::{NotificationEvent, NotificationKind, Severity};
+use crate::i18n;
+
+/// Maximum number of queued notifications listed in a digest.
+const DIGEST_MAX_ITEMS: usize = 10;
+
+/// A recurring do-not-disturb window, e.g. 22:00 - 08:00 on weekdays.
+/// Windows whose end is before their start span midnight, windows with the
+/// same start and end are empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietWindow {
+    /// Days on which the window starts
+    pub days: Vec<Weekday>,
+    /// Start time as `HH:MM`
+    pub start: String,
+    /// End time as `HH:MM`
+    pub end: String,
+}
+
+impl QuietWindow {
+    pub fn contains(&self, now: DateTime<Local>) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let time = now.time();
+        let today = now.weekday();
+
+        if start <= end {
+            self.days.contains(&today) && time >= start && time < end
+        } else {
+            // Spans midnight: either the evening part of today's window, or
+            // the morning part of yesterday's window
+            (self.days.contains(&today) && time >= start)
+                || (self.days.contains(&today.pred()) && time < end)
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn default_bypass_severity() -> Severity {
+    Severity::Critical
+}
+
+fn default_snooze_minutes() -> u64 {
+    60
+}
+
+/// Do-not-disturb settings, stored in the preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationPolicySettings {
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietWindow>,
+    /// Notifications at or above this severity are delivered even while quiet
+    #[serde(default = "default_bypass_severity")]
+    pub bypass_severity: Severity,
+    /// Duration used by the tray's snooze item
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: u64,
+}
+
+impl Default for NotificationPolicySettings {
+    fn default() -> Self {
+        Self {
+            quiet_hours: Vec::new(),
+            bypass_severity: default_bypass_severity(),
+            snooze_minutes: default_snooze_minutes(),
+        }
+    }
+}
+
+/// Whether a notification should be shown now or held back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Deliver,
+    Queue,
+}
+
+/// Decides which notifications get through and collects the suppressed ones
+/// into a digest that is delivered once the quiet period is over.
+#[derive(Debug, Default)]
+pub struct NotificationPolicy {
+    settings: NotificationPolicySettings,
+    snooze_until: Option<DateTime<Local>>,
+    pending: Vec<NotificationEvent>,
+}
+
+impl NotificationPolicy {
+    pub fn new(settings: NotificationPolicySettings) -> Self {
+        Self {
+            settings,
+            snooze_until: None,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &NotificationPolicySettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: NotificationPolicySettings) {
+        self.settings = settings;
+    }
+
+    pub fn snooze(&mut self, until: Option<DateTime<Local>>) {
+        self.snooze_until = until;
+    }
+
+    pub fn snoozed_until(&self) -> Option<DateTime<Local>> {
+        self.snooze_until.filter(|until| *until > Local::now())
+    }
+
+    pub fn is_quiet(&self, now: DateTime<Local>) -> bool {
+        self.snooze_until.is_some_and(|until| now < until)
+            || self.settings.quiet_hours.iter().any(|window| window.contains(now))
+    }
+
+    /// Decide what to do with an event, queueing it if it is held back.
+    pub fn decide(&mut self, event: &NotificationEvent) -> Decision {
+        if event.severity >= self.settings.bypass_severity || !self.is_quiet(event.timestamp) {
+            return Decision::Deliver;
+        }
+        self.pending.push(event.clone());
+        Decision::Queue
+    }
+
+    /// Once the quiet period is over, drain the queued notifications into a
+    /// single digest event.
+    pub fn take_digest(&mut self, now: DateTime<Local>) -> Option<NotificationEvent> {
+        if self.pending.is_empty() || self.is_quiet(now) {
+            return None;
+        }
+        let pending = std::mem::take(&mut self.pending);
+
+        let mut lines: Vec<String> = pending.iter()
+            .rev()
+            .take(DIGEST_MAX_ITEMS)
+            .map(|event| format!("{} {} - {}",
+                event.timestamp.format("%H:%M"),
+                event.title,
+                event.body.lines().next().unwrap_or("")))
+            .collect();
+        lines.reverse();
+        if pending.len() > DIGEST_MAX_ITEMS {
+            lines.insert(0, "…".to_string());
+        }
+
+        Some(NotificationEvent {
+            kind: NotificationKind::Digest,
+            severity: pending.iter().map(|event| event.severity).max().unwrap_or_default(),
+            title: i18n::format(i18n::keys::NOTIF_QUIET_DIGEST, &[&pending.len().to_string()]),
+            body: lines.join("\n"),
+            timestamp: now,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    /// 2024-05-13 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, day, hour, minute, 0).unwrap()
+    }
+
+    fn window(days: &[Weekday], start: &str, end: &str) -> QuietWindow {
+        QuietWindow { days: days.to_vec(), start: start.to_string(), end: end.to_string() }
+    }
+
+    fn policy(quiet_hours: Vec<QuietWindow>) -> NotificationPolicy {
+        NotificationPolicy::new(NotificationPolicySettings { quiet_hours, ..NotificationPolicySettings::default() })
+    }
+
+    fn event(severity: Severity, title: &str, timestamp: DateTime<Local>) -> NotificationEvent {
+        NotificationEvent {
+            kind: NotificationKind::Alert,
+            severity,
+            title: title.to_string(),
+            body: "body".to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn window_spans_midnight() {
+        let night = window(&[Weekday::Mon], "22:00", "07:00");
+        assert!(!night.contains(at(13, 21, 59)));
+        assert!(night.contains(at(13, 22, 0)));
+        assert!(night.contains(at(14, 3, 0)));
+        assert!(night.contains(at(14, 6, 59)));
+        assert!(!night.contains(at(14, 7, 0)));
+        // Sunday's night is not quiet, nor Tuesday's
+        assert!(!night.contains(at(13, 3, 0)));
+        assert!(!night.contains(at(14, 22, 30)));
+    }
+
+    #[test]
+    fn window_within_a_day_includes_start_and_excludes_end() {
+        let lunch = window(&[Weekday::Mon], "12:00", "13:00");
+        assert!(lunch.contains(at(13, 12, 0)));
+        assert!(!lunch.contains(at(13, 13, 0)));
+        assert!(!lunch.contains(at(14, 12, 30)));
+    }
+
+    #[test]
+    fn window_with_equal_start_and_end_is_empty() {
+        let empty = window(&[Weekday::Mon], "09:00", "09:00");
+        assert!(!empty.contains(at(13, 9, 0)));
+        assert!(!empty.contains(at(13, 15, 0)));
+    }
+
+    #[test]
+    fn severity_bypasses_quiet_hours() {
+        let mut policy = policy(vec![window(&[Weekday::Mon], "22:00", "07:00")]);
+        assert_eq!(policy.decide(&event(Severity::Warning, "quiet", at(13, 23, 0))), Decision::Queue);
+        assert_eq!(policy.decide(&event(Severity::Critical, "urgent", at(13, 23, 0))), Decision::Deliver);
+        assert_eq!(policy.decide(&event(Severity::Info, "daytime", at(14, 9, 0))), Decision::Deliver);
+    }
+
+    #[test]
+    fn digest_drains_queued_notifications_in_order() {
+        let mut policy = policy(vec![window(&[Weekday::Mon], "22:00", "07:00")]);
+        policy.decide(&event(Severity::Info, "first", at(13, 23, 0)));
+        policy.decide(&event(Severity::Warning, "second", at(14, 1, 0)));
+
+        assert!(policy.take_digest(at(14, 6, 0)).is_none());
+        let digest = policy.take_digest(at(14, 7, 0)).unwrap();
+        assert_eq!(digest.kind, NotificationKind::Digest);
+        assert_eq!(digest.severity, Severity::Warning);
+        assert!(digest.title.contains('2'));
+        assert_eq!(digest.body, "23:00 first - body\n01:00 second - body");
+        assert!(policy.take_digest(at(14, 8, 0)).is_none());
+    }
+
+    #[test]
+    fn digest_lists_the_latest_notifications() {
+        let mut policy = policy(vec![window(&[Weekday::Mon], "22:00", "07:00")]);
+        for minute in 0..12 {
+            policy.decide(&event(Severity::Info, &format!("n{}", minute), at(13, 23, minute)));
+        }
+        let digest = policy.take_digest(at(14, 7, 0)).unwrap();
+        let lines: Vec<&str> = digest.body.lines().collect();
+        assert_eq!(lines.len(), DIGEST_MAX_ITEMS + 1);
+        assert_eq!(lines[0], "…");
+        assert!(lines[1].contains("n2"));
+        assert!(lines[DIGEST_MAX_ITEMS].contains("n11"));
+    }
+}
@@ -0,0 +1,358 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::email::{EmailConfig, EmailSink};
+use super::webhook::{WebhookConfig, WebhookSink};
+use super::{terminal_enabled, NotificationEvent, Severity};
+#[cfg(feature = "desktop-notify")]
+use super::{desktop_enabled, show_critical_notification, show_notification};
+use crate::monitor_log as log;
+
+/// A destination notifications are delivered to.
+pub trait NotificationSink: Send {
+    /// Short name used in error messages
+    fn name(&self) -> &str;
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()>;
+
+    /// Called periodically so sinks can retry failed deliveries.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Native desktop notifications, the default sink.
+#[cfg(feature = "desktop-notify")]
+pub struct DesktopSink;
+
+#[cfg(feature = "desktop-notify")]
+impl NotificationSink for DesktopSink {
+    fn name(&self) -> &str {
+        "desktop"
+    }
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()> {
+        if !desktop_enabled() {
+            return Ok(());
+        }
+        match event.severity {
+            Severity::Critical => show_critical_notification(&event.title, &event.body),
+            Severity::Info | Severity::Warning => show_notification(&event.title, &event.body),
+        }
+        Ok(())
+    }
+}
+
+/// Appends each notification as a JSON line to a log file.
+pub struct LogFileSink {
+    path: PathBuf,
+}
+
+impl LogFileSink {
+    pub fn new(path: Option<PathBuf>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None => dirs::config_dir()
+                .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?
+                .join("cc-monitor-rs")
+                .join("notifications.log"),
+        };
+        Ok(Self { path })
+    }
+}
+
+impl NotificationSink for LogFileSink {
+    fn name(&self) -> &str {
+        "log_file"
+    }
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+}
+
+/// Prints notifications to stdout, optionally ringing the terminal bell.
+/// Meant for headless use where no desktop notification daemon is running.
+pub struct TerminalSink {
+    bell: bool,
+}
+
+impl TerminalSink {
+    pub fn new(bell: bool) -> Self {
+        Self { bell }
+    }
+}
+
+impl NotificationSink for TerminalSink {
+    fn name(&self) -> &str {
+        "terminal"
+    }
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()> {
+        if !terminal_enabled() {
+            return Ok(());
+        }
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}[{}] {} {}: {}",
+            if self.bell { "\x07" } else { "" },
+            event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            match event.severity {
+                Severity::Info => "INFO",
+                Severity::Warning => "WARN",
+                Severity::Critical => "CRIT",
+            },
+            event.title,
+            event.body.replace('\n', " | "))?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// Keeps delivered notifications in memory, for tests.
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    events: Arc<Mutex<Vec<NotificationEvent>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The notifications delivered so far; clones share the same buffer.
+    pub fn events(&self) -> Vec<NotificationEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl NotificationSink for MemorySink {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+/// Which sink to create, as configured in the preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkKind {
+    Desktop,
+    LogFile {
+        /// Defaults to `notifications.log` next to the preferences
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+    Terminal {
+        #[serde(default)]
+        bell: bool,
+    },
+    Webhook(WebhookConfig),
+    Email(EmailConfig),
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A notification sink and the minimum severity it receives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SinkConfig {
+    #[serde(flatten)]
+    pub kind: SinkKind,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Defaults to `critical` for email and `info` for the other sinks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<Severity>,
+}
+
+impl SinkConfig {
+    pub fn desktop() -> Self {
+        Self {
+            kind: SinkKind::Desktop,
+            enabled: true,
+            min_severity: None,
+        }
+    }
+
+    pub fn min_severity(&self) -> Severity {
+        self.min_severity.unwrap_or(match self.kind {
+            // Every notification would otherwise become an email
+            SinkKind::Email(_) => Severity::Critical,
+            _ => Severity::Info,
+        })
+    }
+
+    fn build(&self) -> Result<Box<dyn NotificationSink>> {
+        Ok(match &self.kind {
+            #[cfg(feature = "desktop-notify")]
+            SinkKind::Desktop => Box::new(DesktopSink),
+            #[cfg(not(feature = "desktop-notify"))]
+            SinkKind::Desktop => anyhow::bail!("built without the desktop-notify feature"),
+            SinkKind::LogFile { path } => Box::new(LogFileSink::new(path.clone())?),
+            SinkKind::Terminal { bell } => Box::new(TerminalSink::new(*bell)),
+            SinkKind::Webhook(config) => Box::new(WebhookSink::new(config.clone())),
+            SinkKind::Email(config) => Box::new(EmailSink::new(config.clone())),
+        })
+    }
+}
+
+pub fn default_sinks() -> Vec<SinkConfig> {
+    vec![SinkConfig::desktop()]
+}
+
+/// A sink shared between the dispatcher and the deliveries in progress.
+type SharedSink = Arc<Mutex<Box<dyn NotificationSink>>>;
+
+/// Fans each notification out to every sink whose severity filter it passes.
+/// Clones share their sinks, so notifications can be sent from a clone
+/// without holding on to the original.
+#[derive(Clone, Default)]
+pub struct NotificationDispatcher {
+    sinks: Vec<(SharedSink, Severity)>,
+}
+
+impl NotificationDispatcher {
+    pub fn from_configs(configs: &[SinkConfig]) -> Self {
+        let mut dispatcher = Self::default();
+        for config in configs.iter().filter(|config| config.enabled) {
+            match config.build() {
+                Ok(sink) => dispatcher.add_sink(sink, config.min_severity()),
+                Err(e) => log!("Failed to create notification sink {:?}: {}", config.kind, e),
+            }
+        }
+        dispatcher
+    }
+
+    pub fn add_sink(&mut self, sink: Box<dyn NotificationSink>, min_severity: Severity) {
+        self.sinks.push((Arc::new(Mutex::new(sink)), min_severity));
+    }
+
+    pub fn send(&self, event: &NotificationEvent) {
+        for (sink, min_severity) in &self.sinks {
+            if event.severity < *min_severity {
+                continue;
+            }
+            let mut sink = sink.lock().unwrap();
+            if let Err(e) = sink.send(event) {
+                log!("Failed to deliver notification to {}: {}", sink.name(), e);
+            }
+        }
+    }
+
+    pub fn flush(&self) {
+        for (sink, _) in &self.sinks {
+            let mut sink = sink.lock().unwrap();
+            if let Err(e) = sink.flush() {
+                log!("Failed to deliver notification to {}: {}", sink.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::notifications::NotificationKind;
+
+    struct FailingSink;
+
+    impl NotificationSink for FailingSink {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn send(&mut self, _event: &NotificationEvent) -> Result<()> {
+            anyhow::bail!("unreachable")
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            anyhow::bail!("unreachable")
+        }
+    }
+
+    fn event(severity: Severity) -> NotificationEvent {
+        NotificationEvent::new(NotificationKind::Alert, severity, "title", "body")
+    }
+
+    #[test]
+    fn fans_out_to_every_sink() {
+        let (first, second) = (MemorySink::new(), MemorySink::new());
+        let mut dispatcher = NotificationDispatcher::default();
+        dispatcher.add_sink(Box::new(first.clone()), Severity::Info);
+        dispatcher.add_sink(Box::new(second.clone()), Severity::Info);
+
+        dispatcher.send(&event(Severity::Info));
+        dispatcher.send(&event(Severity::Critical));
+
+        assert_eq!(first.events().len(), 2);
+        assert_eq!(second.events().len(), 2);
+        assert_eq!(second.events()[1].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn filters_by_min_severity() {
+        let (all, warnings, critical) = (MemorySink::new(), MemorySink::new(), MemorySink::new());
+        let mut dispatcher = NotificationDispatcher::default();
+        dispatcher.add_sink(Box::new(all.clone()), Severity::Info);
+        dispatcher.add_sink(Box::new(warnings.clone()), Severity::Warning);
+        dispatcher.add_sink(Box::new(critical.clone()), Severity::Critical);
+
+        for severity in [Severity::Info, Severity::Warning, Severity::Critical] {
+            dispatcher.send(&event(severity));
+        }
+
+        let severities = |sink: &MemorySink| sink.events().iter().map(|event| event.severity).collect::<Vec<_>>();
+        assert_eq!(severities(&all), vec![Severity::Info, Severity::Warning, Severity::Critical]);
+        assert_eq!(severities(&warnings), vec![Severity::Warning, Severity::Critical]);
+        assert_eq!(severities(&critical), vec![Severity::Critical]);
+    }
+
+    #[test]
+    fn failing_sink_does_not_block_the_others() {
+        let (before, after) = (MemorySink::new(), MemorySink::new());
+        let mut dispatcher = NotificationDispatcher::default();
+        dispatcher.add_sink(Box::new(before.clone()), Severity::Info);
+        dispatcher.add_sink(Box::new(FailingSink), Severity::Info);
+        dispatcher.add_sink(Box::new(after.clone()), Severity::Info);
+
+        dispatcher.send(&event(Severity::Warning));
+        dispatcher.flush();
+
+        assert_eq!(before.events().len(), 1);
+        assert_eq!(after.events().len(), 1);
+    }
+
+    #[test]
+    fn clones_share_their_sinks() {
+        let memory = MemorySink::new();
+        let mut dispatcher = NotificationDispatcher::default();
+        dispatcher.add_sink(Box::new(memory.clone()), Severity::Info);
+
+        dispatcher.clone().send(&event(Severity::Info));
+
+        assert_eq!(memory.events().len(), 1);
+    }
+
+    #[test]
+    fn disabled_sinks_are_skipped() {
+        let configs = vec![
+            SinkConfig { enabled: false, ..SinkConfig::desktop() },
+            SinkConfig { kind: SinkKind::Terminal { bell: false }, enabled: true, min_severity: None },
+        ];
+        assert_eq!(NotificationDispatcher::from_configs(&configs).sinks.len(), 1);
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::sink::NotificationSink;
+use super::{NotificationEvent, NotificationKind, Severity};
+use crate::monitor_log as log;
+
+/// Maximum number of deliveries waiting for a retry; the oldest are dropped.
+const MAX_QUEUED: usize = 100;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30 * 60);
+
+/// The chat service a webhook posts to, which decides the payload format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookService {
+    Slack,
+    Discord,
+    Teams,
+    /// The notification event as plain JSON
+    #[default]
+    Generic,
+}
+
+impl WebhookService {
+    pub fn payload(&self, event: &NotificationEvent) -> serde_json::Value {
+        let color = match event.severity {
+            Severity::Info => 0x4a9eff,
+            Severity::Warning => 0xffaa00,
+            Severity::Critical => 0xff4444,
+        };
+        match self {
+            WebhookService::Slack => json!({
+                "text": format!("{}: {}", event.title, event.body),
+                "attachments": [{
+                    "color": format!("#{:06x}", color),
+                    "title": event.title,
+                    "text": event.body,
+                    "ts": event.timestamp.timestamp(),
+                }],
+            }),
+            WebhookService::Discord => json!({
+                "username": "CC Monitor",
+                "embeds": [{
+                    "title": event.title,
+                    "description": event.body,
+                    "color": color,
+                    "timestamp": event.timestamp.to_rfc3339(),
+                }],
+            }),
+            WebhookService::Teams => json!({
+                "@type": "MessageCard",
+                "@context": "https://schema.org/extensions",
+                "themeColor": format!("{:06X}", color),
+                "summary": event.title,
+                "title": event.title,
+                "text": event.body.replace('\n', "<br>"),
+            }),
+            WebhookService::Generic => json!(event),
+        }
+    }
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// An outbound webhook, configured as a `webhook` notification sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub service: WebhookService,
+    /// Kinds of notifications to post; all kinds when empty
+    #[serde(default)]
+    pub events: Vec<NotificationKind>,
+    /// Custom JSON payload replacing the service's default. `{title}`, `{body}`,
+    /// `{severity}`, `{kind}` and `{timestamp}` are replaced with JSON-escaped values.
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl WebhookConfig {
+    pub fn accepts(&self, kind: NotificationKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+
+    pub fn render(&self, event: &NotificationEvent) -> Result<String> {
+        let Some(template) = &self.template else {
+            return Ok(self.service.payload(event).to_string());
+        };
+        // Insert each value as the inside of a JSON string literal
+        let escape = |value: &str| {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        };
+        let to_name = |value: serde_json::Value| value.as_str().unwrap_or_default().to_string();
+        let payload = template
+            .replace("{title}", &escape(&event.title))
+            .replace("{body}", &escape(&event.body))
+            .replace("{severity}", &to_name(json!(event.severity)))
+            .replace("{kind}", &to_name(json!(event.kind)))
+            .replace("{timestamp}", &event.timestamp.to_rfc3339());
+        // Catch broken templates early rather than on every retry
+        serde_json::from_str::<serde_json::Value>(&payload)
+            .map_err(|e| anyhow::anyhow!("Webhook template is not valid JSON: {}", e))?;
+        Ok(payload)
+    }
+}
+
+struct PendingDelivery {
+    payload: String,
+    attempts: u32,
+    next_attempt: Instant,
+}
+
+/// Posts notifications to a webhook, keeping failed deliveries in a retry
+/// queue with exponential backoff.
+pub struct WebhookSink {
+    config: WebhookConfig,
+    agent: ureq::Agent,
+    queue: VecDeque<PendingDelivery>,
+}
+
+impl WebhookSink {
+    pub fn new(config: WebhookConfig) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(config.timeout_secs)))
+            .build()
+            .into();
+        Self {
+            config,
+            agent,
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn post(&self, payload: &str) -> Result<(), ureq::Error> {
+        self.agent
+            .post(&self.config.url)
+            .header("Content-Type", "application/json")
+            .send(payload)?;
+        Ok(())
+    }
+
+    /// Deliver every queued payload whose retry time has come, in order. When
+    /// the endpoint can't be reached the rest of the batch waits for the same
+    /// retry rather than running into the timeout one by one.
+    fn deliver_due(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let mut last_error = None;
+        let mut unreachable_until = None;
+        let mut remaining = VecDeque::new();
+
+        while let Some(mut pending) = self.queue.pop_front() {
+            if let Some(retry) = unreachable_until {
+                pending.next_attempt = pending.next_attempt.max(retry);
+            }
+            if pending.next_attempt > now {
+                remaining.push_back(pending);
+                continue;
+            }
+            if let Err(e) = self.post(&pending.payload) {
+                pending.attempts += 1;
+                let delay = BASE_RETRY_DELAY
+                    .saturating_mul(1u32 << (pending.attempts - 1).min(16))
+                    .min(MAX_RETRY_DELAY);
+                if !matches!(e, ureq::Error::StatusCode(_)) {
+                    unreachable_until = Some(now + delay);
+                }
+                if pending.attempts > self.config.max_retries {
+                    log!("Dropping webhook delivery to {} after {} attempts: {}",
+                        self.config.url, pending.attempts, e);
+                } else {
+                    pending.next_attempt = now + delay;
+                    remaining.push_back(pending);
+                }
+                last_error = Some(e);
+            }
+        }
+
+        self.queue = remaining;
+        match last_error {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn send(&mut self, event: &NotificationEvent) -> Result<()> {
+        if !self.config.accepts(event.kind) {
+            return Ok(());
+        }
+        let payload = self.config.render(event)?;
+        if self.queue.len() >= MAX_QUEUED {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(PendingDelivery {
+            payload,
+            attempts: 0,
+            next_attempt: Instant::now(),
+        });
+        self.deliver_due()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.queue.is_empty() {
+            return Ok(());
+        }
+        self.deliver_due()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use super::*;
+
+    /// A local HTTP stand-in answering one request per status, in order, and
+    /// recording the request bodies.
+    fn serve(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let received = bodies.clone();
+        std::thread::spawn(move || {
+            for status in statuses {
+                let Ok((stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                received.lock().unwrap().push(String::from_utf8(body).unwrap());
+                let response = format!("HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (url, bodies)
+    }
+
+    fn config(url: String) -> WebhookConfig {
+        WebhookConfig {
+            url,
+            service: WebhookService::Generic,
+            events: Vec::new(),
+            template: None,
+            max_retries: 2,
+            timeout_secs: 5,
+        }
+    }
+
+    fn event() -> NotificationEvent {
+        NotificationEvent::new(NotificationKind::Budget, Severity::Critical, "Budget \"daily\"", "Spent $12\nof $10")
+    }
+
+    /// Make every queued delivery due for the next flush.
+    fn expire_backoff(sink: &mut WebhookSink) {
+        for pending in sink.queue.iter_mut() {
+            pending.next_attempt = Instant::now();
+        }
+    }
+
+    #[test]
+    fn posts_rendered_payload() {
+        let (url, bodies) = serve(vec![200]);
+        let mut sink = WebhookSink::new(WebhookConfig { service: WebhookService::Slack, ..config(url) });
+
+        sink.send(&event()).unwrap();
+
+        let bodies = bodies.lock().unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(payload["attachments"][0]["title"], "Budget \"daily\"");
+        assert_eq!(payload["attachments"][0]["color"], "#ff4444");
+        assert!(sink.queue.is_empty());
+    }
+
+    #[test]
+    fn retries_with_backoff_until_delivered() {
+        let (url, bodies) = serve(vec![500, 503, 200]);
+        let mut sink = WebhookSink::new(config(url));
+
+        let sent = Instant::now();
+        assert!(sink.send(&event()).is_err());
+        assert_eq!(sink.queue[0].attempts, 1);
+        assert!(sink.queue[0].next_attempt >= sent + BASE_RETRY_DELAY);
+
+        // Not due yet, so nothing is posted
+        sink.flush().unwrap();
+        assert_eq!(bodies.lock().unwrap().len(), 1);
+
+        expire_backoff(&mut sink);
+        let retried = Instant::now();
+        assert!(sink.flush().is_err());
+        assert_eq!(sink.queue[0].attempts, 2);
+        assert!(sink.queue[0].next_attempt >= retried + BASE_RETRY_DELAY * 2);
+
+        expire_backoff(&mut sink);
+        sink.flush().unwrap();
+        assert!(sink.queue.is_empty());
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 3);
+        assert!(bodies.iter().all(|body| body == &bodies[0]));
+    }
+
+    #[test]
+    fn drops_delivery_after_max_retries() {
+        let (url, bodies) = serve(vec![500, 500, 500]);
+        let mut sink = WebhookSink::new(config(url));
+
+        assert!(sink.send(&event()).is_err());
+        for _ in 0..2 {
+            expire_backoff(&mut sink);
+            assert!(sink.flush().is_err());
+        }
+        assert!(sink.queue.is_empty());
+        assert_eq!(bodies.lock().unwrap().len(), 3);
+    }
+
+    fn queue(sink: &mut WebhookSink, count: usize) {
+        for i in 0..count {
+            sink.queue.push_back(PendingDelivery {
+                payload: format!("{{\"n\": {}}}", i),
+                attempts: 0,
+                next_attempt: Instant::now(),
+            });
+        }
+    }
+
+    #[test]
+    fn unreachable_endpoint_defers_the_rest_of_the_batch() {
+        // Bound and closed again, so connections are refused
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/hook", listener.local_addr().unwrap())
+        };
+        let mut sink = WebhookSink::new(config(url));
+        queue(&mut sink, 3);
+
+        let flushed = Instant::now();
+        assert!(sink.flush().is_err());
+        let attempts: Vec<u32> = sink.queue.iter().map(|pending| pending.attempts).collect();
+        assert_eq!(attempts, vec![1, 0, 0]);
+        assert!(sink.queue.iter().all(|pending| pending.next_attempt >= flushed + BASE_RETRY_DELAY));
+    }
+
+    #[test]
+    fn error_status_does_not_defer_the_batch() {
+        let (url, bodies) = serve(vec![500, 200]);
+        let mut sink = WebhookSink::new(config(url));
+        queue(&mut sink, 2);
+
+        assert!(sink.flush().is_err());
+        assert_eq!(bodies.lock().unwrap().len(), 2);
+        assert_eq!(sink.queue.len(), 1);
+        assert_eq!(sink.queue[0].payload, "{\"n\": 0}");
+    }
+
+    #[test]
+    fn skips_filtered_events() {
+        let (url, bodies) = serve(vec![200]);
+        let mut sink = WebhookSink::new(WebhookConfig { events: vec![NotificationKind::Alert], ..config(url) });
+
+        sink.send(&event()).unwrap();
+        assert!(sink.queue.is_empty());
+        sink.send(&NotificationEvent::new(NotificationKind::Alert, Severity::Info, "Alert", "")).unwrap();
+
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].contains("\"kind\":\"alert\""));
+    }
+
+    #[test]
+    fn service_payloads() {
+        let event = event();
+        let discord = WebhookService::Discord.payload(&event);
+        assert_eq!(discord["embeds"][0]["description"], "Spent $12\nof $10");
+        assert_eq!(discord["embeds"][0]["color"], 0xff4444);
+        let teams = WebhookService::Teams.payload(&event);
+        assert_eq!(teams["text"], "Spent $12<br>of $10");
+        assert_eq!(teams["themeColor"], "FF4444");
+        let generic = WebhookService::Generic.payload(&event);
+        assert_eq!(generic["severity"], "critical");
+        assert_eq!(generic["kind"], "budget");
+    }
+
+    #[test]
+    fn template_values_are_escaped() {
+        let config = WebhookConfig {
+            template: Some(r#"{"msg": "{title}: {body}", "level": "{severity}", "kind": "{kind}", "at": "{timestamp}"}"#.to_string()),
+            ..config(String::new())
+        };
+        let event = event();
+
+        let payload: serde_json::Value = serde_json::from_str(&config.render(&event).unwrap()).unwrap();
+        assert_eq!(payload["msg"], "Budget \"daily\": Spent $12\nof $10");
+        assert_eq!(payload["level"], "critical");
+        assert_eq!(payload["kind"], "budget");
+        assert_eq!(payload["at"], event.timestamp.to_rfc3339());
+    }
+
+    #[test]
+    fn invalid_template_is_rejected() {
+        let config = WebhookConfig { template: Some("{\"text\": {body}}".to_string()), ..config(String::new()) };
+        assert!(config.render(&event()).is_err());
+    }
+}
@@ -1,12 +1,116 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use crate::i18n::Language;
+use crate::alerts::AlertRule;
+use crate::alerts::DigestSchedule;
+use crate::budget::BudgetSettings;
+use crate::mqtt::MqttSettings;
+use crate::telemetry::TelemetrySettings;
+use crate::export::ExportSettings;
+use crate::report::ReportSettings;
+use crate::server::{ApiSettings, MetricsSettings};
+use crate::utils::notifications::{sink, NotificationPolicySettings, SinkConfig};
+
+/// Name of the profile used when none has been selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Polling intervals for each data source of the background worker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorProfile {
+    pub network_interval_secs: u64,
+    pub ccusage_interval_secs: u64,
+    pub history_interval_secs: u64,
+}
+
+impl Default for MonitorProfile {
+    fn default() -> Self {
+        Self {
+            network_interval_secs: 1,
+            ccusage_interval_secs: 1,
+            history_interval_secs: 60,
+        }
+    }
+}
+
+impl MonitorProfile {
+    /// A slower profile for running on battery or metered connections.
+    pub fn low_power() -> Self {
+        Self {
+            network_interval_secs: 10,
+            ccusage_interval_secs: 30,
+            history_interval_secs: 600,
+        }
+    }
+}
+
+fn default_profiles() -> HashMap<String, MonitorProfile> {
+    let mut profiles = HashMap::new();
+    profiles.insert(DEFAULT_PROFILE.to_string(), MonitorProfile::default());
+    profiles.insert("low-power".to_string(), MonitorProfile::low_power());
+    profiles
+}
+
+fn default_active_profile() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+
+fn default_reset_marks() -> Vec<u32> {
+    vec![30, 10, 1]
+}
+
+fn default_true() -> bool {
+    true
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Preferences {
     pub language: String,
+    #[serde(default = "default_active_profile")]
+    pub active_profile: String,
+    #[serde(default = "default_profiles")]
+    pub profiles: HashMap<String, MonitorProfile>,
+    /// Token limit of the Claude plan, used for percent-of-plan alerts
+    #[serde(default)]
+    pub plan_token_limit: Option<u64>,
+    #[serde(default)]
+    pub alert_rules: Vec<AlertRule>,
+    /// Minutes before a block expires at which to notify
+    #[serde(default = "default_reset_marks")]
+    pub reset_marks_minutes: Vec<u32>,
+    /// Notify when a new 5-hour window opens, only after the plan limit was hit if one is set
+    #[serde(default = "default_true")]
+    pub notify_window_available: bool,
+    /// When usage digest notifications are sent
+    #[serde(default)]
+    pub digest_schedule: DigestSchedule,
+    #[serde(default)]
+    pub budgets: BudgetSettings,
+    #[serde(default)]
+    pub notification_policy: NotificationPolicySettings,
+    /// Where notifications are delivered, each with its own severity filter
+    #[serde(default = "sink::default_sinks")]
+    pub notification_sinks: Vec<SinkConfig>,
+    /// Prometheus `/metrics` endpoint
+    #[serde(default)]
+    pub metrics: MetricsSettings,
+    /// REST API and WebSocket stream
+    #[serde(default)]
+    pub api: ApiSettings,
+    /// MQTT publisher
+    #[serde(default)]
+    pub mqtt: MqttSettings,
+    /// OpenTelemetry OTLP exporter
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
+    /// Export and import folders of the window
+    #[serde(default)]
+    pub export: ExportSettings,
+    /// Usage reports, written on demand or on a schedule
+    #[serde(default)]
+    pub report: ReportSettings,
 }
 
 impl Default for Preferences {
@@ -15,6 +119,22 @@ impl Default for Preferences {
         let default_lang = detect_system_language();
         Self {
             language: default_lang.code().to_string(),
+            active_profile: default_active_profile(),
+            profiles: default_profiles(),
+            plan_token_limit: None,
+            alert_rules: Vec::new(),
+            reset_marks_minutes: default_reset_marks(),
+            notify_window_available: true,
+            digest_schedule: DigestSchedule::default(),
+            budgets: BudgetSettings::default(),
+            notification_policy: NotificationPolicySettings::default(),
+            notification_sinks: sink::default_sinks(),
+            metrics: MetricsSettings::default(),
+            api: ApiSettings::default(),
+            mqtt: MqttSettings::default(),
+            telemetry: TelemetrySettings::default(),
+            export: ExportSettings::default(),
+            report: ReportSettings::default(),
         }
     }
 }
@@ -87,4 +207,18 @@ impl Preferences {
     pub fn set_language(&mut self, lang: Language) {
         self.language = lang.code().to_string();
     }
+    
+    /// Returns the currently selected monitor profile, falling back to the defaults.
+    pub fn active_profile(&self) -> MonitorProfile {
+        self.profiles.get(&self.active_profile).cloned().unwrap_or_default()
+    }
+    
+    /// Selects the named profile. Fails if no profile with that name exists.
+    pub fn switch_profile(&mut self, name: &str) -> Result<MonitorProfile> {
+        let profile = self.profiles.get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown profile: {}", name))?;
+        self.active_profile = name.to_string();
+        Ok(profile)
+    }
 }
\ No newline at end of file